@@ -17,6 +17,10 @@ use std::path::PathBuf;
 pub const PROJECT_DIR: &'static str = "Project's dir";
 pub const OUTPUT_TS_FILE: &'static str = "Output TypeScript file";
 pub const OUTPUT_SQL_FILE: &'static str = "Output T-SQL file";
+pub const OUTPUT_PERMISSIONS_FILE: &'static str = "Output permissions report file";
+pub const DENY_WARNINGS: &'static str = "Deny warnings";
+pub const MESSAGE_FORMAT: &'static str = "Message format";
+pub const NO_CACHE: &'static str = "No cache";
 
 pub fn build_cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new("N-lang compiler")
@@ -42,6 +46,45 @@ pub fn build_cli_app<'a, 'b>() -> App<'a, 'b> {
                 .required(true)
                 .index(3)
         )
+        .arg(
+            Arg::with_name(OUTPUT_PERMISSIONS_FILE)
+                .help("Destination location for report of RPC functions available to each role")
+                .long("permissions")
+                .takes_value(true)
+                .value_name("FILE")
+        )
+        .arg(
+            Arg::with_name(DENY_WARNINGS)
+                .help("Treat warnings as errors")
+                .long("deny-warnings")
+        )
+        .arg(
+            Arg::with_name(MESSAGE_FORMAT)
+                .help("Format of printed errors and warnings")
+                .long("message-format")
+                .takes_value(true)
+                .value_name("FORMAT")
+                .possible_values(&["human", "json"])
+                .default_value("human")
+        )
+        .arg(
+            Arg::with_name(NO_CACHE)
+                .help("Compile all modules from scratch without reading or writing the .n_lang_cache directory")
+                .long("no-cache")
+        )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MessageFormat {
+    Human,
+    Json,
+}
+
+impl Default for MessageFormat {
+    #[inline]
+    fn default() -> Self {
+        MessageFormat::Human
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -49,6 +92,10 @@ pub struct CLIConfig {
     pub projects_dir: PathBuf,
     pub output_typescript_file: PathBuf,
     pub output_tsql_file: PathBuf,
+    pub output_permissions_file: Option<PathBuf>,
+    pub deny_warnings: bool,
+    pub message_format: MessageFormat,
+    pub no_cache: bool,
 }
 
 fn extract_required_param<'a>(matches: &'a ArgMatches, param: &str) -> &'a str {
@@ -64,5 +111,12 @@ pub fn match_cli_config() -> CLIConfig {
         projects_dir: PathBuf::from(extract_required_param(&matches, PROJECT_DIR)),
         output_typescript_file: PathBuf::from(extract_required_param(&matches, OUTPUT_TS_FILE)),
         output_tsql_file: PathBuf::from(extract_required_param(&matches, OUTPUT_SQL_FILE)),
+        output_permissions_file: matches.value_of(OUTPUT_PERMISSIONS_FILE).map(PathBuf::from),
+        deny_warnings: matches.is_present(DENY_WARNINGS),
+        message_format: match matches.value_of(MESSAGE_FORMAT) {
+            Some("json") => MessageFormat::Json,
+            _ => MessageFormat::Human,
+        },
+        no_cache: matches.is_present(NO_CACHE),
     }
 }
@@ -6,6 +6,15 @@ mod cli;
 mod stdlib;
 mod resolve;
 
+use cli::{
+    CLIConfig,
+    MessageFormat,
+};
+use n_lang::project_analysis::{
+    GenerateError,
+    SemanticErrors,
+    SemanticWarnings,
+};
 use std::{
     error::Error,
     fs::File,
@@ -14,26 +23,72 @@ use std::{
     process::exit,
 };
 
-fn write(filename: &Path, content: &str) -> Result<(), Box<Error>> {
+fn write(filename: &Path, content: &str) -> Result<(), Box<dyn Error>> {
     let mut file = File::create(filename)?;
     file.write(content.as_bytes())?;
     Ok(())
 }
 
-fn do_it() -> Result<(), Box<Error>> {
-    let config = cli::match_cli_config();
-    let (db, rpc) = resolve::resolve_dir(&config.projects_dir)?;
+fn print_warnings(warnings: &SemanticWarnings, format: MessageFormat) -> Result<(), Box<dyn Error>> {
+    match format {
+        MessageFormat::Human => println!("{}", warnings),
+        MessageFormat::Json => {
+            let mut result = String::new();
+            warnings.write_json_lines(&mut result)?;
+            print!("{}", result);
+        }
+    }
+    Ok(())
+}
+
+fn print_error(error: &Box<dyn Error>, format: MessageFormat) -> Result<(), Box<dyn Error>> {
+    if format == MessageFormat::Json {
+        let mut result = String::new();
+        if let Some(errors) = error.downcast_ref::<SemanticErrors>() {
+            errors.write_json_lines(&mut result)?;
+        } else if let Some(warnings) = error.downcast_ref::<SemanticWarnings>() {
+            warnings.write_json_lines(&mut result)?;
+        } else if let Some(error) = error.downcast_ref::<GenerateError>() {
+            error.write_json(&mut result)?;
+            result.push('\n');
+        }
+        if !result.is_empty() {
+            print!("{}", result);
+            return Ok(());
+        }
+    }
+    println!("Error: {}", error);
+    Ok(())
+}
+
+fn do_it(config: &CLIConfig) -> Result<(), Box<dyn Error>> {
+    let (db, rpc, mut warnings) = resolve::resolve_dir(&config.projects_dir, !config.no_cache)?;
+    if !warnings.is_empty() {
+        if config.deny_warnings {
+            warnings.deny();
+            return Err(Box::new(warnings));
+        }
+        print_warnings(&warnings, config.message_format)?;
+    }
     write(&config.output_tsql_file, &db.generate_string()?)?;
     write(&config.output_typescript_file, &rpc.generate_string()?)?;
+    if let Some(permissions_file) = &config.output_permissions_file {
+        write(permissions_file, &db.generate_permissions_report()?)?;
+    }
     Ok(())
 }
 
 fn main() {
     env_logger::init();
-    match do_it() {
-        Ok(_) => println!("Success!"),
+    let config = cli::match_cli_config();
+    match do_it(&config) {
+        Ok(_) => if config.message_format == MessageFormat::Human {
+            println!("Success!");
+        },
         Err(error) => {
-            println!("Error: {}", error);
+            if let Err(error) = print_error(&error, config.message_format) {
+                println!("Error: {}", error);
+            }
             exit(1);
         }
     }
@@ -1,34 +1,90 @@
 use n_lang::{
     code_generation::{
+        CACHE_DIR_NAME,
         DatabaseProject,
+        ProjectCache,
         RPCModule,
     },
     helpers::{
+        StableHasher,
         SyncRef,
-        Resolve,
     },
     project_analysis::{
+        hash_text,
         SemanticErrors,
+        SemanticWarnings,
         HashMapSource,
+        N_LANG_STDLIB_FILE_EXTENSION,
         ProjectContext,
     },
 };
 use std::{
+    env::current_exe,
     error::Error,
+    fs::File,
+    hash::Hasher,
+    io::{
+        self,
+        Read,
+    },
     path::Path,
 };
 use stdlib::build_ms_sql_std_lib;
 
-pub fn resolve_dir(path: &Path) -> Result<(DatabaseProject, RPCModule), Box<Error>> {
+/// Отпечаток сборки компилятора: версия пакета не меняется между сборками, в отличие от исполняемого файла.
+fn build_fingerprint() -> io::Result<u64> {
+    let mut file = File::open(current_exe()?)?;
+    let mut hasher = StableHasher::new();
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        match file.read(&mut buffer)? {
+            0 => return Ok(hasher.finish()),
+            count => hasher.write(&buffer[..count]),
+        }
+    }
+}
+
+/**
+    Соль кеша: от сборки компилятора и текстов стандартной библиотеки проекта
+    зависит разрешение всех модулей.
+*/
+fn cache_salt(stdlib_sources: &HashMapSource) -> io::Result<u64> {
+    let mut texts = stdlib_sources.texts().collect::<Vec<_>>();
+    texts.sort_by(|a, b| a.0.cmp(b.0));
+    let mut hasher = StableHasher::new();
+    hasher.write_u64(build_fingerprint()?);
+    for (module_path, text) in texts {
+        hasher.write_str(module_path.data.as_str());
+        hasher.write_u64(hash_text(text));
+    }
+    Ok(hasher.finish())
+}
+
+pub fn resolve_dir(path: &Path, use_cache: bool) -> Result<(DatabaseProject, RPCModule, SemanticWarnings), Box<dyn Error>> {
     let sources = HashMapSource::for_dir(path)?;
-    let project_context = ProjectContext::new(SyncRef::new(build_ms_sql_std_lib()));
+    let mut stdlib = build_ms_sql_std_lib()
+        .map_err(|errors| SemanticErrors::from(errors))?;
+    let stdlib_sources = HashMapSource::for_dir_with_extension(path, N_LANG_STDLIB_FILE_EXTENSION)?;
+    for (_, text) in stdlib_sources.texts() {
+        stdlib.reg_source(text.clone())
+            .map_err(|errors| SemanticErrors::from(errors))?;
+    }
+    let cache_dir = path.join(CACHE_DIR_NAME);
+    let cache = if use_cache {
+        ProjectCache::load(&cache_dir, cache_salt(&stdlib_sources)?)
+    } else {
+        // Кеш без сохранения ни с чем не сравнивается, поэтому его соль не важна
+        ProjectCache::new(0)
+    };
+    let project_context = ProjectContext::new(SyncRef::new(stdlib));
     for (module_path, _) in sources.texts() {
         project_context.request_resolving_module(module_path.as_path());
     }
-    let project = project_context.resolve(&sources)
+    let project = project_context.resolve_with_cache(&sources, &cache)
         .map_err(|errors| SemanticErrors::from(errors))?;
-    Ok((
-        DatabaseProject::new(&project),
-        RPCModule::top(&project),
-    ))
+    let (db, rpc, cache) = cache.generate(&project)?;
+    if use_cache {
+        cache.save(&cache_dir)?;
+    }
+    Ok((db, rpc, SemanticWarnings::from(project.warnings)))
 }
@@ -0,0 +1,328 @@
+use code_generation::{
+    DatabaseGrant,
+    DatabaseModule,
+    DatabaseModuleCode,
+    DatabaseProject,
+    RPCModule,
+};
+use helpers::{
+    Path,
+    PathBuf,
+};
+use project_analysis::{
+    GenerateError,
+    GenerateResult,
+    ModuleCache,
+    ModuleInterface,
+    ResolvedProject,
+};
+use std::{
+    collections::HashMap,
+    fmt::Write as FmtWrite,
+    fs::{
+        create_dir_all,
+        File,
+        read_dir,
+        remove_file,
+    },
+    io::{
+        self,
+        Read,
+        Write,
+    },
+    path,
+    str::Lines,
+};
+
+/// Имя каталога кеша внутри каталога проекта.
+pub const CACHE_DIR_NAME: &'static str = ".n_lang_cache";
+
+const CACHE_FILE_EXTENSION: &'static str = "cache";
+
+const CACHE_HEADER: &'static str = "n_lang-cache";
+
+/// Версия формата файлов кеша. Увеличивается при каждом изменении формата или способа вычисления хешей.
+const CACHE_FORMAT_VERSION: u32 = 3;
+
+/// Код, сгенерированный для модуля. Остаётся верным, пока не изменились модуль и его зависимости.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleOutput {
+    pub schema: Option<String>,
+    pub grants: Vec<DatabaseGrant>,
+    pub tsql: DatabaseModuleCode,
+    pub typescript: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheEntry {
+    pub interface: ModuleInterface,
+    pub output: ModuleOutput,
+}
+
+/**
+    Кеш инкрементальной компиляции: интерфейсы и сгенерированный код модулей, разрешённых без ошибок и предупреждений.
+    Соль `salt` описывает всё, что влияет на компиляцию помимо текстов модулей, например стандартную библиотеку.
+    Кеш с другой солью или от другой версии компилятора не используется.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectCache {
+    salt: u64,
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+impl ProjectCache {
+    #[inline]
+    pub fn new(salt: u64) -> Self {
+        ProjectCache {
+            salt,
+            entries: HashMap::new(),
+        }
+    }
+    #[inline]
+    pub fn get(&self, path: Path) -> Option<&CacheEntry> {
+        self.entries.get(&PathBuf::from_path(path))
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    /// Читает кеш из каталога `dir`. Отсутствующие, устаревшие и повреждённые записи пропускаются.
+    pub fn load(dir: &path::Path, salt: u64) -> Self {
+        let mut result = ProjectCache::new(salt);
+        let entries = match read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return result,
+        };
+        for entry in entries {
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(_) => continue,
+            };
+            if path.extension().and_then(|ext| ext.to_str()) != Some(CACHE_FILE_EXTENSION) {
+                continue;
+            }
+            let mut text = String::new();
+            if File::open(&path).and_then(|mut file| file.read_to_string(&mut text)).is_err() {
+                continue;
+            }
+            if let Some((module_path, entry)) = parse_entry(&text, salt) {
+                result.entries.insert(module_path, entry);
+            }
+        }
+        result
+    }
+    /// Записывает кеш в каталог `dir`, удаляя записи о модулях, которых в кеше больше нет.
+    pub fn save(&self, dir: &path::Path) -> io::Result<()> {
+        create_dir_all(dir)?;
+        let mut file_names = Vec::with_capacity(self.entries.len());
+        for (module_path, entry) in self.entries.iter() {
+            let file_name = format!("{}.{}", module_path.as_path().into_new_buf(".").data, CACHE_FILE_EXTENSION);
+            let mut text = String::new();
+            write_entry(&mut text, module_path, entry, self.salt)
+                .map_err(|_| io::Error::new(io::ErrorKind::Other, "cannot serialize cache entry"))?;
+            File::create(dir.join(&file_name))?.write_all(text.as_bytes())?;
+            file_names.push(file_name);
+        }
+        for entry in read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(CACHE_FILE_EXTENSION) {
+                continue;
+            }
+            let is_actual = path.file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| file_names.iter().any(|file_name| file_name == name))
+                .unwrap_or(false);
+            if !is_actual {
+                remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+    /**
+        Генерирует код разрешённых модулей проекта и собирает его вместе с кодом переиспользованных модулей.
+        Возвращает проекты для вывода и обновлённый кеш.
+        Модули с предупреждениями в кеш не попадают, чтобы предупреждения выводились при каждом запуске.
+    */
+    pub fn generate(&self, project: &ResolvedProject) -> GenerateResult<(DatabaseProject, RPCModule, ProjectCache)> {
+        let mut cache = ProjectCache::new(self.salt);
+        let mut outputs: HashMap<PathBuf, ModuleOutput> = HashMap::with_capacity(project.order.len());
+        for (module_path, module) in project.modules.iter() {
+            let module_path = module_path.read().clone();
+            let database_module = DatabaseModule::new(module);
+            let output = ModuleOutput {
+                schema: database_module.schema().map(str::to_string),
+                grants: database_module.grants().to_vec(),
+                tsql: database_module.generate_code()?,
+                typescript: RPCModule::for_module(module).generate_items_string(module_path.as_path())?,
+            };
+            if module.read().warnings().is_empty() {
+                if let Some(interface) = project.interfaces.get(&module_path) {
+                    cache.entries.insert(module_path.clone(), CacheEntry {
+                        interface: interface.clone(),
+                        output: output.clone(),
+                    });
+                }
+            }
+            outputs.insert(module_path, output);
+        }
+        for module_path in project.reused_modules.iter() {
+            let entry = self.entries.get(module_path)
+                .ok_or_else(|| GenerateError::without_pos(format!("module {} is missing in cache", module_path)))?;
+            cache.entries.insert(module_path.clone(), entry.clone());
+            outputs.insert(module_path.clone(), entry.output.clone());
+        }
+        let mut database_modules = Vec::with_capacity(outputs.len());
+        let mut rpc_modules = Vec::with_capacity(outputs.len());
+        for module_path in project.order.iter() {
+            let ModuleOutput { schema, grants, tsql, typescript } = match outputs.remove(module_path) {
+                Some(output) => output,
+                None => continue,
+            };
+            database_modules.push(DatabaseModule::from_code(module_path.clone(), schema, grants, tsql));
+            rpc_modules.push((module_path.clone(), RPCModule::from_code(typescript)));
+        }
+        Ok((
+            DatabaseProject::from_modules(database_modules),
+            RPCModule::from_modules(rpc_modules),
+            cache,
+        ))
+    }
+}
+
+impl ModuleCache for ProjectCache {
+    #[inline]
+    fn get_interface(&self, path: Path) -> Option<&ModuleInterface> {
+        self.get(path)
+            .map(|entry| &entry.interface)
+    }
+}
+
+/*
+    Запись кеша хранится в отдельном файле в виде строк, поля которых разделены табуляцией:
+    заголовок с версией компилятора и солью, путь модуля, хеш текста, отпечаток, импорты,
+    объекты в схеме модуля, схема, права доступа и, наконец, сгенерированный код с числом его строк.
+*/
+
+fn write_entry(w: &mut impl FmtWrite, module_path: &PathBuf, entry: &CacheEntry, salt: u64) -> ::std::fmt::Result {
+    let CacheEntry { interface, output } = entry;
+    writeln!(w, "{}\t{}\t{}\t{:016x}", CACHE_HEADER, CACHE_FORMAT_VERSION, env!("CARGO_PKG_VERSION"), salt)?;
+    writeln!(w, "module\t{}", module_path)?;
+    writeln!(w, "text\t{:016x}", interface.text_hash)?;
+    writeln!(w, "fingerprint\t{:016x}", interface.fingerprint)?;
+    for import in interface.imports.iter() {
+        writeln!(w, "import\t{}", import)?;
+    }
+    for object in interface.objects.iter() {
+        writeln!(w, "object\t{}", object)?;
+    }
+    if let Some(schema) = &output.schema {
+        writeln!(w, "schema\t{}", schema)?;
+    }
+    for grant in output.grants.iter() {
+        write!(
+            w,
+            "grant\t{}\t{}\t{}\t{}",
+            grant.permission,
+            if grant.is_function { 1 } else { 0 },
+            grant.item_path,
+            grant.object_name,
+        )?;
+        for role in grant.roles.iter() {
+            write!(w, "\t{}", role)?;
+        }
+        writeln!(w)?;
+    }
+    write_code(w, "tables", &output.tsql.tables)?;
+    write_code(w, "routines", &output.tsql.routines)?;
+    write_code(w, "triggers", &output.tsql.triggers)?;
+    write_code(w, "typescript", &output.typescript)
+}
+
+fn write_code(w: &mut impl FmtWrite, name: &str, code: &str) -> ::std::fmt::Result {
+    writeln!(w, "{}\t{}", name, code.split('\n').count())?;
+    for line in code.split('\n') {
+        writeln!(w, "{}", line)?;
+    }
+    Ok(())
+}
+
+fn parse_entry(text: &str, salt: u64) -> Option<(PathBuf, CacheEntry)> {
+    let mut lines = text.lines();
+    {
+        let mut header = lines.next()?.split('\t');
+        if header.next()? != CACHE_HEADER
+            || header.next()?.parse::<u32>().ok()? != CACHE_FORMAT_VERSION
+            || header.next()? != env!("CARGO_PKG_VERSION")
+            || u64::from_str_radix(header.next()?, 16).ok()? != salt {
+            return None;
+        }
+    }
+    let module_path = new_path(parse_field(lines.next()?, "module")?);
+    let text_hash = u64::from_str_radix(parse_field(lines.next()?, "text")?, 16).ok()?;
+    let fingerprint = u64::from_str_radix(parse_field(lines.next()?, "fingerprint")?, 16).ok()?;
+    let mut imports = Vec::new();
+    let mut objects = Vec::new();
+    let mut schema = None;
+    let mut grants = Vec::new();
+    let tables = loop {
+        let mut fields = lines.next()?.split('\t');
+        match fields.next()? {
+            "import" => imports.push(new_path(fields.next()?)),
+            "object" => objects.push(fields.next()?.to_string()),
+            "schema" => schema = Some(fields.next()?.to_string()),
+            "grant" => {
+                let permission = match fields.next()? {
+                    "SELECT" => "SELECT",
+                    "EXECUTE" => "EXECUTE",
+                    _ => return None,
+                };
+                let is_function = fields.next()? == "1";
+                let item_path = new_path(fields.next()?);
+                let object_name = fields.next()?.to_string();
+                let roles = fields.map(str::to_string).collect();
+                grants.push(DatabaseGrant { permission, object_name, item_path, is_function, roles });
+            }
+            "tables" => break parse_code(&mut lines, fields.next()?)?,
+            _ => return None,
+        }
+    };
+    let mut parse_section = |name: &str| {
+        let count = parse_field(lines.next()?, name)?;
+        parse_code(&mut lines, count)
+    };
+    let tsql = DatabaseModuleCode {
+        tables,
+        routines: parse_section("routines")?,
+        triggers: parse_section("triggers")?,
+    };
+    let typescript = parse_section("typescript")?;
+    Some((module_path, CacheEntry {
+        interface: ModuleInterface { text_hash, fingerprint, imports, objects },
+        output: ModuleOutput { schema, grants, tsql, typescript },
+    }))
+}
+
+fn parse_field<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let mut fields = line.splitn(2, '\t');
+    if fields.next()? != name {
+        return None;
+    }
+    fields.next()
+}
+
+fn parse_code(lines: &mut Lines, count: &str) -> Option<String> {
+    let count: usize = count.parse().ok()?;
+    let mut result = String::new();
+    for i in 0..count {
+        if i > 0 {
+            result.push('\n');
+        }
+        result.push_str(lines.next()?);
+    }
+    Some(result)
+}
+
+#[inline]
+fn new_path(data: &str) -> PathBuf {
+    PathBuf::from_path(Path::new(data, "::"))
+}
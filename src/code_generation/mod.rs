@@ -1,3 +1,7 @@
+pub use self::cache::*;
+
+pub mod cache;
+
 use helpers::{
     BlockFormatter,
     CodeFormatter,
@@ -11,92 +15,181 @@ use helpers::{
 };
 use indexmap::IndexMap;
 use language::{
-    DataType,
+    Attribute,
+    ConstDefinition,
+    DataTypeDefinition,
+    find_attribute,
+    FunctionBody,
     FunctionDefinition,
     TableDefinition,
+    TriggerDefinition,
+    ViewDefinition,
+};
+use lexeme_scanner::ItemPosition;
+use project_analysis::{
+    GenerateResult,
+    Item,
+    Module,
+    Text,
 };
-use project_analysis::Module;
 use std::{
     fmt::{
         self,
         Write,
     },
+    sync::Arc,
 };
 
 #[derive(Debug, Clone)]
 pub struct RPCModule {
-    data_types: Map<String, DataType>,
+    constants: Map<String, ConstDefinition>,
+    data_types: Map<String, DataTypeDefinition>,
     functions: Map<String, FunctionDefinition>,
+    tables: Map<String, RPCModule>,
     sub_modules: Map<String, RPCModule>,
+    /// Ранее сгенерированный код элементов модуля, заменяющий их определения.
+    cached_code: Option<String>,
+    text: Option<Arc<Text>>,
 }
 
 impl RPCModule {
-    pub fn top(project: &IndexMap<SyncRef<PathBuf>, SyncRef<Module>>) -> Self {
-        let mut sub_modules = Map::new();
-        for (path, module) in project {
-            let path_guard = path.read();
-            if let Some(name) = path_guard.the_only() {
-                sub_modules.insert(name, RPCModule::new(module, project));
-            }
-        }
-        sub_modules.sort();
+    #[inline]
+    fn empty() -> Self {
         RPCModule {
+            constants: Map::new(),
             data_types: Map::new(),
             functions: Map::new(),
-            sub_modules,
+            tables: Map::new(),
+            sub_modules: Map::new(),
+            cached_code: None,
+            text: None,
         }
     }
+    pub fn top(project: &IndexMap<SyncRef<PathBuf>, SyncRef<Module>>) -> Self {
+        RPCModule::from_modules(
+            project.iter()
+                .map(|(path, module)| (path.read().clone(), RPCModule::for_module(module)))
+                .collect()
+        )
+    }
+    /**
+        Собирает дерево модулей по их путям.
+        Модуль попадает в дерево, только если в нём есть его родительский модуль.
+    */
+    pub fn from_modules(mut modules: Vec<(PathBuf, RPCModule)>) -> Self {
+        let mut result = RPCModule::empty();
+        result.sub_modules = RPCModule::take_sub_modules(None, &mut modules);
+        result
+    }
+    fn take_sub_modules(parent: Option<Path>, modules: &mut Vec<(PathBuf, RPCModule)>) -> Map<String, RPCModule> {
+        let mut children = Vec::new();
+        let mut i = 0;
+        while i < modules.len() {
+            let name = {
+                let path = modules[i].0.as_path();
+                match parent {
+                    Some(parent) => parent.is_begin_of(path).and_then(Path::the_only),
+                    None => path.the_only(),
+                }.map(str::to_string)
+            };
+            match name {
+                Some(name) => {
+                    let (path, module) = modules.remove(i);
+                    children.push((name, path, module));
+                }
+                None => i += 1,
+            }
+        }
+        let mut result = Map::new();
+        for (name, path, mut module) in children {
+            module.sub_modules = RPCModule::take_sub_modules(Some(path.as_path()), modules);
+            result.insert(name, module);
+        }
+        result.sort();
+        result
+    }
     pub fn new(source: &SyncRef<Module>, project: &IndexMap<SyncRef<PathBuf>, SyncRef<Module>>) -> Self {
+        let mut result = RPCModule::for_module(source);
+        let source_path = source.read().path().read().clone();
+        let mut modules = project.iter()
+            .map(|(path, module)| (path.read().clone(), RPCModule::for_module(module)))
+            .collect();
+        result.sub_modules = RPCModule::take_sub_modules(Some(source_path.as_path()), &mut modules);
+        result
+    }
+    /// Собирает элементы модуля без вложенных модулей.
+    pub fn for_module(source: &SyncRef<Module>) -> Self {
         let source_guard = source.read();
 
+        let mut constants = Map::new();
         let mut data_types = Map::new();
         let mut functions = Map::new();
-        let mut sub_modules = Map::new();
+        let mut tables = Map::new();
 
         for (item_name, item) in source_guard.items() {
             let item_guard = item.value.read();
             if !item_guard.is_belongs_to(source) { continue; }
             if let Some(data_type) = item_guard.get_data_type() {
-                data_types.insert(item_name.as_str(), data_type.body.clone());
+                data_types.insert(item_name.as_str(), data_type.clone());
             } else if let Some(function) = item_guard.get_function() {
                 functions.insert(item_name.as_str(), function.clone());
             } else if let Some(table) = item_guard.get_table() {
-                sub_modules.insert(item_name.as_str(), RPCModule::for_table(table));
+                tables.insert(item_name.as_str(), RPCModule::for_table(table));
+            } else if let Some(view) = item_guard.get_view() {
+                data_types.insert(item_name.as_str(), DataTypeDefinition {
+                    name: item_name.clone(),
+                    body: view.entity.clone(),
+                    nominal: false,
+                });
+            } else if let Some(constant) = item_guard.get_constant() {
+                let mut constant = constant.clone();
+                constant.name = item_name.clone();
+                constants.insert(item_name.as_str(), constant);
             }
         }
 
-        let source_path_guard = source_guard.path().read();
-        let source_path = source_path_guard.as_path();
-
-        for (module_path, module) in project {
-            let module_path_guard = module_path.read();
-            if let Some(name) = source_path.is_begin_of(module_path_guard.as_path())
-                .and_then(Path::the_only)
-                {
-                    sub_modules.insert(name, RPCModule::new(module, project));
-                }
-        }
+        constants.sort();
         data_types.sort();
         functions.sort();
-        sub_modules.sort();
+        tables.sort();
         RPCModule {
+            constants,
             data_types,
             functions,
-            sub_modules,
+            tables,
+            sub_modules: Map::new(),
+            cached_code: None,
+            text: source_guard.text().cloned(),
         }
     }
+    /// Модуль, код элементов которого был сгенерирован ранее.
+    pub fn from_code(code: String) -> Self {
+        let mut result = RPCModule::empty();
+        result.cached_code = Some(code);
+        result
+    }
     pub fn for_table(table: &TableDefinition) -> Self {
         let mut data_types = Map::new();
-        data_types.insert("entity", table.entity.clone());
-        data_types.insert("primary_key", table.primary_key.clone());
-        RPCModule {
-            data_types,
-            functions: Map::new(),
-            sub_modules: Map::new(),
-        }
+        data_types.insert("entity", DataTypeDefinition {
+            name: "entity".to_string(),
+            body: table.entity.clone(),
+            nominal: false,
+        });
+        data_types.insert("primary_key", DataTypeDefinition {
+            name: "primary_key".to_string(),
+            body: table.primary_key.clone(),
+            nominal: false,
+        });
+        let mut result = RPCModule::empty();
+        result.data_types = data_types;
+        result
     }
-    pub fn fmt(&self, f: &mut SimpleFormatter, path: Path) -> fmt::Result {
-        for (module_name, module) in self.sub_modules.iter() {
+    pub fn fmt(&self, f: &mut SimpleFormatter, path: Path) -> GenerateResult {
+        RPCModule::fmt_modules(&self.sub_modules, f, path)?;
+        self.fmt_items(f, path)
+    }
+    fn fmt_modules(modules: &Map<String, RPCModule>, f: &mut SimpleFormatter, path: Path) -> GenerateResult {
+        for (module_name, module) in modules.iter() {
             writeln!(f, "export module {} {{", module_name)?;
             module.fmt(
                 &mut f.sub_block(),
@@ -107,15 +200,34 @@ impl RPCModule {
             )?;
             writeln!(f, "}}")?;
         }
+        Ok(())
+    }
+    /// Выводит элементы модуля без вложенных модулей.
+    pub fn fmt_items(&self, f: &mut SimpleFormatter, path: Path) -> GenerateResult {
+        if let Some(code) = &self.cached_code {
+            f.write_str(code)?;
+            return Ok(());
+        }
+        RPCModule::fmt_modules(&self.tables, f, path)?;
+        for (_name, constant) in self.constants.iter() {
+            constant.fmt_export(f)
+                .map_err(|error| error.locate_in(constant.pos, self.text.as_ref()))?;
+        }
         for (name, data_type) in self.data_types.iter() {
             data_type.fmt_export(f, &name)?;
         }
         for (_name, function) in self.functions.iter() {
-            function.fmt_export(f, path)?;
+            function.fmt_export(f, path)
+                .map_err(|error| error.locate_in(function.pos, self.text.as_ref()))?;
         }
         Ok(())
     }
-    pub fn generate_string(&self) -> Result<String, fmt::Error> {
+    pub fn generate_items_string(&self, path: Path) -> GenerateResult<String> {
+        let mut result = String::new();
+        self.fmt_items(&mut SimpleFormatter::new(&mut result, 4), path)?;
+        Ok(result)
+    }
+    pub fn generate_string(&self) -> GenerateResult<String> {
         let mut result = String::new();
         {
             let mut formatter = SimpleFormatter::new(&mut result, 4);
@@ -130,11 +242,96 @@ impl RPCModule {
     }
 }
 
+/// Право доступа к объекту базы данных, выдаваемое ролям.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatabaseGrant {
+    pub permission: &'static str,
+    pub object_name: String,
+    pub item_path: PathBuf,
+    pub is_function: bool,
+    pub roles: Vec<String>,
+}
+
+/**
+    Код объектов модуля, разделённый по этапам развёртывания.
+    Этапы выводятся для всех модулей проекта по очереди, чтобы объект создавался после объектов, от которых зависит.
+*/
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DatabaseModuleCode {
+    pub tables: String,
+    /// Представления и функции вперемешку: каждый из них создаётся после тех, к которым обращается.
+    pub routines: String,
+    pub triggers: String,
+}
+
+/// Представление или функция модуля.
+#[derive(Debug, Clone, PartialEq)]
+enum DatabaseRoutine {
+    View(ViewDefinition),
+    Function(FunctionDefinition),
+}
+
+impl DatabaseRoutine {
+    #[inline]
+    fn name(&self) -> &str {
+        match self {
+            DatabaseRoutine::View(view) => view.name.as_str(),
+            DatabaseRoutine::Function(function) => function.name.as_str(),
+        }
+    }
+    fn used_items(&self) -> Vec<SyncRef<Item>> {
+        let mut items = Vec::new();
+        match self {
+            DatabaseRoutine::View(view) => view.query.collect_used_items(&mut items),
+            DatabaseRoutine::Function(function) => if let FunctionBody::Implementation(body) = &function.body {
+                body.collect_used_items(&mut items);
+            },
+        }
+        items
+    }
+    /**
+        Упорядочивает представления и функции модуля так, чтобы каждый из них шёл после тех,
+        к которым обращается. В остальном сохраняет исходный порядок.
+    */
+    fn sort_by_usage(routines: Vec<(SyncRef<Item>, DatabaseRoutine)>) -> Vec<DatabaseRoutine> {
+        fn visit(
+            index: usize,
+            routines: &[(SyncRef<Item>, DatabaseRoutine)],
+            visited: &mut [bool],
+            result: &mut Vec<DatabaseRoutine>,
+        ) {
+            if visited[index] {
+                return;
+            }
+            visited[index] = true;
+            let (_, routine) = &routines[index];
+            for used_item in routine.used_items() {
+                if let Some(used_index) = routines.iter().position(|(item, _)| item.is_same_ref(&used_item)) {
+                    visit(used_index, routines, visited, result);
+                }
+            }
+            result.push(routine.clone());
+        }
+        let mut visited = vec![false; routines.len()];
+        let mut result = Vec::with_capacity(routines.len());
+        for index in 0..routines.len() {
+            visit(index, &routines, &mut visited, &mut result);
+        }
+        result
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct DatabaseModule {
     path: PathBuf,
+    schema: Option<String>,
+    grants: Vec<DatabaseGrant>,
     tables: Vec<TableDefinition>,
-    functions: Vec<FunctionDefinition>,
+    routines: Vec<DatabaseRoutine>,
+    triggers: Vec<TriggerDefinition>,
+    /// Ранее сгенерированный код объектов модуля, заменяющий их определения.
+    cached_code: Option<DatabaseModuleCode>,
+    text: Option<Arc<Text>>,
 }
 
 impl DatabaseModule {
@@ -142,44 +339,188 @@ impl DatabaseModule {
         let source_guard = source.read();
         let mut result = Self {
             path: source_guard.path().read().clone(),
+            schema: source_guard.schema().map(str::to_string),
+            grants: Vec::new(),
             tables: Vec::new(),
-            functions: Vec::new(),
+            routines: Vec::new(),
+            triggers: Vec::new(),
+            cached_code: None,
+            text: source_guard.text().cloned(),
         };
-        for (item_name, item) in source_guard.items().iter() {
-            let item = item.value.read();
+        let mut views = Vec::new();
+        let mut functions = Vec::new();
+        for (item_name, item_def) in source_guard.items().iter() {
+            let item = item_def.value.read();
             if !item.is_belongs_to(source) { continue; }
+            if let Some(grant) = DatabaseModule::make_grant(&*item, item_def.attributes.as_slice(), source_guard.grants()) {
+                result.grants.push(grant);
+            }
             if let Some(table) = item.get_table() {
                 let mut table = table.clone();
                 table.name = item_name.clone();
                 result.tables.push(table);
                 continue;
             }
+            if let Some(view) = item.get_view() {
+                let mut view = view.clone();
+                view.name = item_name.clone();
+                views.push((item_def.value.clone(), DatabaseRoutine::View(view)));
+                continue;
+            }
             if let Some(function) = item.get_function() {
                 let mut function = function.clone();
                 function.name = item_name.clone();
-                result.functions.push(function);
+                functions.push((item_def.value.clone(), DatabaseRoutine::Function(function)));
+                continue;
+            }
+            if let Some(trigger) = item.get_trigger() {
+                let mut trigger = trigger.clone();
+                trigger.name = item_name.clone();
+                result.triggers.push(trigger);
                 continue;
             }
         }
         result.tables.sort_by(|a, b| a.name.cmp(&b.name));
-        result.functions.sort_by(|a, b| a.name.cmp(&b.name));
+        views.sort_by(|(_, a), (_, b)| a.name().cmp(b.name()));
+        functions.sort_by(|(_, a), (_, b)| a.name().cmp(b.name()));
+        views.append(&mut functions);
+        result.routines = DatabaseRoutine::sort_by_usage(views);
+        result.triggers.sort_by(|a, b| a.name.cmp(&b.name));
+        result.grants.sort_by(|a, b| a.item_path.cmp(&b.item_path));
         result
     }
-    pub fn generate_tables(&self, mut f: BlockFormatter<impl Write>) -> fmt::Result {
-        let parameters = TSQLParameters::new(self.path.as_path());
+    /// Модуль, код объектов которого был сгенерирован ранее.
+    pub fn from_code(path: PathBuf, schema: Option<String>, grants: Vec<DatabaseGrant>, code: DatabaseModuleCode) -> Self {
+        Self {
+            path,
+            schema,
+            grants,
+            tables: Vec::new(),
+            routines: Vec::new(),
+            triggers: Vec::new(),
+            cached_code: Some(code),
+            text: None,
+        }
+    }
+    #[inline]
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+    #[inline]
+    pub fn schema(&self) -> Option<&str> {
+        self.schema.as_ref().map(String::as_str)
+    }
+    #[inline]
+    pub fn grants(&self) -> &[DatabaseGrant] {
+        &self.grants
+    }
+    /**
+        Собирает права доступа к элементу модуля.
+        Роли из атрибута `#[grant(...)]` элемента дополняют роли, указанные для всего модуля.
+    */
+    fn make_grant(item: &Item, attributes: &[Attribute], module_roles: &[String]) -> Option<DatabaseGrant> {
+        let (permission, is_function) = if item.get_table().is_some() || item.get_view().is_some() {
+            ("SELECT", false)
+        } else if let Some(function) = item.get_function() {
+            let is_table_valued = function.is_lite_weight && function.result.as_primitive().is_none();
+            (if is_table_valued { "SELECT" } else { "EXECUTE" }, true)
+        } else {
+            return None;
+        };
+        let mut roles = module_roles.to_vec();
+        if let Some(arguments) = find_attribute(attributes, "grant").and_then(|attribute| attribute.arguments.as_ref()) {
+            for role in arguments {
+                if !roles.contains(role) {
+                    roles.push(role.clone());
+                }
+            }
+        }
+        if roles.is_empty() {
+            return None;
+        }
+        let mut object_name = String::new();
+        item.fmt_object_name(&mut object_name).ok()?;
+        Some(DatabaseGrant {
+            permission,
+            object_name,
+            item_path: item.get_path().ok()?,
+            is_function,
+            roles,
+        })
+    }
+    #[inline]
+    fn generate_item(
+        &self,
+        item: &impl for<'a> Generate<TSQLParameters<'a>>,
+        pos: ItemPosition,
+        mut f: BlockFormatter<impl Write>,
+    ) -> GenerateResult {
+        Generate::fmt(item, f.clone(), self.parameters())
+            .map_err(|error| error.locate_in(pos, self.text.as_ref()))?;
+        f.write_line("GO")?;
+        f.write_line("")?;
+        Ok(())
+    }
+    #[inline]
+    fn parameters<'a>(&'a self) -> TSQLParameters<'a> {
+        TSQLParameters::new(self.path.as_path())
+            .with_schema(self.schema.as_ref().map(String::as_str))
+    }
+    /// Выводит ранее сгенерированный код этапа. Возвращает `false`, если кода нет и этап нужно сгенерировать.
+    fn replay_code(&self, code: impl Fn(&DatabaseModuleCode) -> &String, mut f: BlockFormatter<impl Write>) -> GenerateResult<bool> {
+        let cached_code = match &self.cached_code {
+            Some(cached_code) => code(cached_code),
+            None => return Ok(false),
+        };
+        for line in cached_code.lines() {
+            f.write_line(line)?;
+        }
+        Ok(true)
+    }
+    /// Генерирует код объектов модуля для всех этапов развёртывания.
+    pub fn generate_code(&self) -> GenerateResult<DatabaseModuleCode> {
+        fn generate_string(generate: impl FnOnce(BlockFormatter<String>) -> GenerateResult) -> GenerateResult<String> {
+            let mut result = String::new();
+            {
+                let mut code_formatter = CodeFormatter::new(&mut result);
+                code_formatter.indent_size = 4;
+                generate(code_formatter.root_block())?;
+            }
+            Ok(result)
+        }
+        Ok(DatabaseModuleCode {
+            tables: generate_string(|f| self.generate_tables(f))?,
+            routines: generate_string(|f| self.generate_routines(f))?,
+            triggers: generate_string(|f| self.generate_triggers(f))?,
+        })
+    }
+    pub fn generate_tables(&self, f: BlockFormatter<impl Write>) -> GenerateResult {
+        if self.replay_code(|code| &code.tables, f.clone())? {
+            return Ok(());
+        }
         for table in self.tables.iter() {
-            Generate::fmt(table, f.clone(), parameters.clone())?;
-            f.write_line("GO")?;
-            f.write_line("")?;
+            self.generate_item(table, table.pos, f.clone())?;
+        }
+        Ok(())
+    }
+    pub fn generate_routines(&self, f: BlockFormatter<impl Write>) -> GenerateResult {
+        if self.replay_code(|code| &code.routines, f.clone())? {
+            return Ok(());
+        }
+        for routine in self.routines.iter() {
+            match routine {
+                DatabaseRoutine::View(view) => self.generate_item(view, view.pos, f.clone())?,
+                DatabaseRoutine::Function(function) => self.generate_item(function, function.pos, f.clone())?,
+            }
         }
         Ok(())
     }
-    pub fn generate_functions(&self, mut f: BlockFormatter<impl Write>) -> fmt::Result {
-        let parameters = TSQLParameters::new(self.path.as_path());
-        for function in self.functions.iter() {
-            Generate::fmt(function, f.clone(), parameters.clone())?;
-            f.write_line("GO")?;
-            f.write_line("")?;
+    pub fn generate_triggers(&self, f: BlockFormatter<impl Write>) -> GenerateResult {
+        if self.replay_code(|code| &code.triggers, f.clone())? {
+            return Ok(());
+        }
+        for trigger in self.triggers.iter() {
+            self.generate_item(trigger, trigger.pos, f.clone())?;
         }
         Ok(())
     }
@@ -192,31 +533,95 @@ pub struct DatabaseProject {
 
 impl DatabaseProject {
     pub fn new(project: &IndexMap<SyncRef<PathBuf>, SyncRef<Module>>) -> Self {
-        let mut modules = Map::new();
-        for (module_path, module) in project.iter() {
-            modules.insert(
-                module_path.read().clone(),
-                DatabaseModule::new(module),
-            );
-        }
-        modules.sort();
+        DatabaseProject::from_modules(
+            project.values()
+                .map(DatabaseModule::new)
+                .collect()
+        )
+    }
+    /// Создаёт проект из модулей, перечисленных в порядке зависимостей.
+    pub fn from_modules(modules: Vec<DatabaseModule>) -> Self {
+        let modules: Map<PathBuf, DatabaseModule> = modules.into_iter()
+            .map(|module| (module.path.clone(), module))
+            .collect();
         Self {
             modules,
         }
     }
-    pub fn generate(&self, target: &mut impl Write) -> fmt::Result {
+    pub fn generate(&self, target: &mut impl Write) -> GenerateResult {
         let mut code_formatter = CodeFormatter::new(target);
         code_formatter.indent_size = 4;
-        let root = code_formatter.root_block();
+        let mut root = code_formatter.root_block();
+
+        let mut schemas: Vec<&str> = self.modules.iter()
+            .filter_map(|(_, module)| module.schema.as_ref().map(String::as_str))
+            .collect();
+        schemas.sort();
+        schemas.dedup();
+        for schema in schemas {
+            root.write_line(format_args!("IF SCHEMA_ID('{name}') IS NULL EXEC('CREATE SCHEMA [{name}]');", name = schema))?;
+            root.write_line("GO")?;
+            root.write_line("")?;
+        }
 
+        // Все таблицы создаются раньше представлений, функций и триггеров, которые могут на них ссылаться
         for (_, module) in self.modules.iter() {
             module.generate_tables(root.clone())?;
-            module.generate_functions(root.clone())?;
+        }
+        for (_, module) in self.modules.iter() {
+            module.generate_routines(root.clone())?;
+        }
+        for (_, module) in self.modules.iter() {
+            module.generate_triggers(root.clone())?;
         }
 
+        self.generate_grants(root)?;
         Ok(())
     }
-    pub fn generate_string(&self) -> Result<String, fmt::Error> {
+    fn grants(&self) -> impl Iterator<Item=&DatabaseGrant> {
+        self.modules.iter()
+            .flat_map(|(_, module)| module.grants.iter())
+    }
+    fn roles(&self) -> Vec<&str> {
+        let mut roles: Vec<&str> = self.grants()
+            .flat_map(|grant| grant.roles.iter().map(String::as_str))
+            .collect();
+        roles.sort();
+        roles.dedup();
+        roles
+    }
+    pub fn generate_grants(&self, mut f: BlockFormatter<impl Write>) -> fmt::Result {
+        let roles = self.roles();
+        if roles.is_empty() {
+            return Ok(());
+        }
+        for role in roles {
+            f.write_line(format_args!("IF DATABASE_PRINCIPAL_ID('{name}') IS NULL CREATE ROLE [{name}];", name = role))?;
+        }
+        f.write_line("GO")?;
+        f.write_line("")?;
+        for grant in self.grants() {
+            for role in grant.roles.iter() {
+                f.write_line(format_args!("GRANT {} ON {} TO [{}];", grant.permission, grant.object_name, role))?;
+            }
+        }
+        f.write_line("GO")?;
+        f.write_line("")
+    }
+    /// Формирует отчёт о том, какие функции RPC может вызывать каждая роль.
+    pub fn generate_permissions_report(&self) -> Result<String, fmt::Error> {
+        let mut result = String::new();
+        for role in self.roles() {
+            writeln!(result, "{}:", role)?;
+            for grant in self.grants() {
+                if grant.is_function && grant.roles.iter().any(|grant_role| grant_role == role) {
+                    writeln!(result, "    {}", grant.item_path)?;
+                }
+            }
+        }
+        Ok(result)
+    }
+    pub fn generate_string(&self) -> GenerateResult<String> {
         let mut result = String::new();
         self.generate(&mut result)?;
         Ok(result)
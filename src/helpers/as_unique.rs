@@ -1,7 +1,27 @@
 use indexmap::IndexMap;
+use lexeme_scanner::ItemPosition;
 use parser_basics::Identifier;
 use std::hash::Hash;
 
+/// Ключ, по имени которого ищутся повторяющиеся определения.
+pub trait NamedKey {
+    fn key_name(&self) -> &str;
+}
+
+impl<'source> NamedKey for Identifier<'source> {
+    #[inline]
+    fn key_name(&self) -> &str {
+        self.text()
+    }
+}
+
+impl NamedKey for (String, ItemPosition) {
+    #[inline]
+    fn key_name(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
 pub fn as_unique<K: Eq + Hash + Clone, V>(vec: Vec<(K, V)>) -> Result<IndexMap<K, V>, K> {
     let mut result = IndexMap::new();
     for (key, value) in vec {
@@ -12,12 +32,13 @@ pub fn as_unique<K: Eq + Hash + Clone, V>(vec: Vec<(K, V)>) -> Result<IndexMap<K
     Ok(result)
 }
 
-pub fn as_unique_identifier<'source, T, I>(source: I) -> Result<IndexMap<String, T>, Identifier<'source>>
-    where I: IntoIterator<Item=(Identifier<'source>, T)>
+pub fn as_unique_identifier<K, T, I>(source: I) -> Result<IndexMap<String, T>, K>
+    where K: NamedKey,
+          I: IntoIterator<Item=(K, T)>
 {
     let mut result = IndexMap::new();
     for (key, value) in source {
-        if result.insert(key.text().to_string(), value).is_some() {
+        if result.insert(key.key_name().to_string(), value).is_some() {
             return Err(key);
         }
     }
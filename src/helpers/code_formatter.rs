@@ -181,10 +181,15 @@ impl<'a> SimpleFormatter<'a> {
         }
     }
     pub fn write(&mut self, value: impl fmt::Display) -> fmt::Result {
+        self.buffer.write_fmt(format_args!("{}", value))?;
         if self.top {
-            return self.target.write_fmt(format_args!("{}", value));
+            if let Some(c) = self.buffer.chars().last() {
+                self.started = c != '\n';
+            }
+            self.target.write_str(&self.buffer)?;
+            self.buffer.clear();
+            return Ok(());
         }
-        self.buffer.write_fmt(format_args!("{}", value))?;
         {
             let mut chunks = self.buffer.split('\n')
                 .peekable();
@@ -0,0 +1,49 @@
+use std::cmp::{
+    max,
+    min,
+};
+
+/// Вычисляет расстояние редактирования между строками, считая перестановку соседних символов одной правкой.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut table = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in 0..a.len() + 1 {
+        table[i][0] = i;
+    }
+    for j in 0..b.len() + 1 {
+        table[0][j] = j;
+    }
+    for i in 1..a.len() + 1 {
+        for j in 1..b.len() + 1 {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = min(
+                table[i - 1][j - 1] + cost,
+                min(table[i - 1][j], table[i][j - 1]) + 1,
+            );
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = min(distance, table[i - 2][j - 2] + 1);
+            }
+            table[i][j] = distance;
+        }
+    }
+    table[a.len()][b.len()]
+}
+
+/**
+    Выбирает из кандидатов имена, похожие на `name`, в порядке возрастания расстояния редактирования.
+    Похожими считаются имена, отличающиеся не более, чем на треть длины `name` (но хотя бы на один символ).
+*/
+pub fn similar_names<'a>(name: &str, candidates: impl IntoIterator<Item=&'a str>) -> Vec<String> {
+    let max_distance = max(1, name.chars().count() / 3);
+    let mut result: Vec<(usize, &str)> = candidates.into_iter()
+        .filter(|&candidate| candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .collect();
+    result.sort();
+    result.dedup();
+    result.into_iter()
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
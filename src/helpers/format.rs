@@ -3,19 +3,21 @@ use helpers::{
     CodeFormatter,
     Path,
 };
+use project_analysis::GenerateResult;
 use std::fmt;
 
 pub trait Format<T> {
-    fn fmt(&self, f: &mut impl fmt::Write, parameters: T) -> fmt::Result;
+    fn fmt(&self, f: &mut impl fmt::Write, parameters: T) -> GenerateResult;
 }
 
 pub trait Generate<T> {
-    fn fmt(&self, f: BlockFormatter<impl fmt::Write>, parameters: T) -> fmt::Result;
+    fn fmt(&self, f: BlockFormatter<impl fmt::Write>, parameters: T) -> GenerateResult;
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TSQLParameters<'a> {
     pub module_path: Path<'a>,
+    pub schema: Option<&'a str>,
     pub indent_size: usize,
 }
 
@@ -23,9 +25,21 @@ impl<'a> TSQLParameters<'a> {
     pub fn new(module_path: Path<'a>) -> Self {
         Self {
             module_path,
+            schema: None,
             indent_size: 2,
         }
     }
+    pub fn with_schema(self, schema: Option<&'a str>) -> Self {
+        Self {
+            schema,
+            ..self
+        }
+    }
+    /// Выводит имя объекта базы данных, определяемого в текущем модуле.
+    #[inline]
+    pub fn fmt_object_name(&self, f: &mut impl fmt::Write, name: &str, is_routine: bool) -> fmt::Result {
+        fmt_tsql_object_name(f, self.schema, self.module_path, name, is_routine)
+    }
     pub fn with_indent_size(self, indent_size: usize) -> Self {
         Self {
             indent_size,
@@ -34,8 +48,35 @@ impl<'a> TSQLParameters<'a> {
     }
 }
 
+/**
+    Выводит имя объекта базы данных.
+
+    Если модулю сопоставлена схема, объект называется `[schema].[name]`.
+    Иначе объект находится в схеме по умолчанию и называется `[module::name]`,
+    а для функций и процедур схема `dbo` указывается явно.
+*/
+pub fn fmt_tsql_object_name(
+    f: &mut impl fmt::Write,
+    schema: Option<&str>,
+    module_path: Path,
+    name: &str,
+    is_routine: bool,
+) -> fmt::Result {
+    if let Some(schema) = schema {
+        return write!(f, "[{}].[{}]", schema, name);
+    }
+    if is_routine {
+        f.write_str("dbo.")?;
+    }
+    f.write_char('[')?;
+    if !module_path.data.is_empty() {
+        write!(f, "{}{}", module_path.data, module_path.delimiter)?;
+    }
+    write!(f, "{}]", name)
+}
+
 impl<'a, T: Generate<TSQLParameters<'a>>> Format<TSQLParameters<'a>> for T {
-    fn fmt(&self, f: &mut impl fmt::Write, parameters: TSQLParameters<'a>) -> fmt::Result {
+    fn fmt(&self, f: &mut impl fmt::Write, parameters: TSQLParameters<'a>) -> GenerateResult {
         let mut formatter = CodeFormatter::new(f);
         formatter.indent_size = parameters.indent_size;
         Generate::fmt(self, formatter.root_block(), parameters)
@@ -48,5 +89,6 @@ pub struct TSQL<'a, 'b, T: 'a + Format<TSQLParameters<'b>>>(pub &'a T, pub TSQLP
 impl<'a, 'b, T: 'a + Format<TSQLParameters<'b>>> fmt::Display for TSQL<'a, 'b, T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.0.fmt(f, self.1.clone())
+            .map_err(|_| fmt::Error)
     }
 }
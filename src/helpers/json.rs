@@ -0,0 +1,29 @@
+use std::fmt::{
+    Result,
+    Write,
+};
+
+/// Записывает строку в виде строкового литерала JSON, экранируя кавычки, обратную косую черту и управляющие символы.
+pub fn write_json_string<W: Write>(w: &mut W, value: &str) -> Result {
+    w.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => w.write_str("\\\"")?,
+            '\\' => w.write_str("\\\\")?,
+            '\n' => w.write_str("\\n")?,
+            '\r' => w.write_str("\\r")?,
+            '\t' => w.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(w, "\\u{:04x}", c as u32)?,
+            c => w.write_char(c)?,
+        }
+    }
+    w.write_char('"')
+}
+
+/// Записывает необязательную строку в виде строкового литерала JSON или `null`.
+pub fn write_json_optional_string<W: Write>(w: &mut W, value: Option<&str>) -> Result {
+    match value {
+        Some(value) => write_json_string(w, value),
+        None => w.write_str("null"),
+    }
+}
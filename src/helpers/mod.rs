@@ -5,6 +5,7 @@ pub use self::{
     assertion::*,
     code_formatter::*,
     display_list::*,
+    edit_distance::*,
     extract::*,
     extractor::*,
     find_index::*,
@@ -13,6 +14,7 @@ pub use self::{
     id_pull::*,
     into_static::*,
     is_f32_enough::*,
+    json::*,
     lazy::*,
     loud_rw_lock::*,
     map::*,
@@ -23,6 +25,7 @@ pub use self::{
     re_entrant_rw_lock::*,
     resolve::*,
     result_collect::*,
+    stable_hasher::*,
     sync_ref::*,
     write_pad::*,
 };
@@ -37,6 +40,7 @@ pub mod convert_macro;
 #[macro_use]
 pub mod count_expression_macro;
 pub mod display_list;
+pub mod edit_distance;
 pub mod extract;
 pub mod extractor;
 pub mod find_index;
@@ -45,6 +49,7 @@ pub mod group;
 pub mod id_pull;
 pub mod into_static;
 pub mod is_f32_enough;
+pub mod json;
 pub mod lazy;
 pub mod loud_rw_lock;
 #[macro_use]
@@ -57,6 +62,7 @@ pub mod path;
 pub mod re_entrant_rw_lock;
 pub mod resolve;
 pub mod result_collect;
+pub mod stable_hasher;
 pub mod sync_ref;
 pub mod write_pad;
 #[macro_use]
@@ -75,6 +75,15 @@ pub fn left_padding_size(line: &str) -> Option<usize> {
 }
 
 pub fn write_pointer_line<W: Write>(w: &mut W, line: &str, number_length: usize, begin: usize, end: usize) -> Result {
+    write_marker_line(w, line, number_length, begin, end, '^', None)
+}
+
+/// Подчёркивает участок строки дефисами и дописывает после него подпись, если она есть.
+pub fn write_labelled_pointer_line<W: Write>(w: &mut W, line: &str, number_length: usize, begin: usize, end: usize, label: Option<&str>) -> Result {
+    write_marker_line(w, line, number_length, begin, end, '-', label)
+}
+
+fn write_marker_line<W: Write>(w: &mut W, line: &str, number_length: usize, begin: usize, end: usize, marker: char, label: Option<&str>) -> Result {
     let begin = match left_padding_size(line) {
         Some(padding) => padding.max(begin),
         None => return Ok(()),
@@ -85,7 +94,10 @@ pub fn write_pointer_line<W: Write>(w: &mut W, line: &str, number_length: usize,
             write!(w, " ")?;
         }
         for _ in begin..=end {
-            write!(w, "^")?;
+            write!(w, "{}", marker)?;
+        }
+        if let Some(label) = label {
+            write!(w, " {}", label)?;
         }
         writeln!(w, "")?;
     }
@@ -6,6 +6,7 @@ use helpers::{
 };
 use language::{
     AssignmentTarget,
+    BOOLEAN_TYPE,
     DataType,
     Expression,
     ExpressionAST,
@@ -19,6 +20,7 @@ use parser_basics::Identifier;
 use project_analysis::{
     FunctionVariable,
     FunctionVariableScope,
+    GenerateResult,
     Item,
     SemanticError,
     SemanticItemType,
@@ -31,8 +33,34 @@ use std::fmt::{
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum JoinType {
     Cross,
+    Inner,
     Left,
     Right,
+    Full,
+}
+
+impl JoinType {
+    pub fn get_operator(&self) -> &'static str {
+        match self {
+            JoinType::Cross => "CROSS JOIN",
+            JoinType::Inner => "INNER JOIN",
+            JoinType::Left => "LEFT JOIN",
+            JoinType::Right => "RIGHT JOIN",
+            JoinType::Full => "FULL JOIN",
+        }
+    }
+}
+
+impl fmt::Display for JoinType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            JoinType::Cross => "cross join",
+            JoinType::Inner => "inner join",
+            JoinType::Left => "left join",
+            JoinType::Right => "right join",
+            JoinType::Full => "full join",
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -46,11 +74,18 @@ pub enum DataSourceAST<'source> {
         condition: Option<ExpressionAST<'source>>,
         left: Box<DataSourceAST<'source>>,
         right: Box<DataSourceAST<'source>>,
+        pos: ItemPosition,
     },
     Selection {
         query: Box<SelectionAST<'source>>,
         alias: Identifier<'source>,
     },
+    FunctionCall {
+        name: ItemPath,
+        arguments: Vec<ExpressionAST<'source>>,
+        alias: Identifier<'source>,
+        pos: ItemPosition,
+    },
 }
 
 impl<'source> Resolve<SyncRef<FunctionVariableScope>> for DataSourceAST<'source> {
@@ -62,6 +97,7 @@ impl<'source> Resolve<SyncRef<FunctionVariableScope>> for DataSourceAST<'source>
                 let pos = name.pos;
                 if let Some(name) = name.path.as_path().the_only() {
                     if let Some(var) = scope.get_variable(name) {
+                        var.mark_as_used();
                         let var_data_type = var.property_type(pos, Path::empty())?;
                         let entity_type = match var_data_type {
                             DataType::Array(entity_type) => (*entity_type).clone(),
@@ -76,22 +112,25 @@ impl<'source> Resolve<SyncRef<FunctionVariableScope>> for DataSourceAST<'source>
                             new_var.make_read_only();
                         }
                         new_var.mark_as_automatic();
-                        return Ok(DataSource::Variable { var });
+                        return Ok(DataSource::Variable { var, row_var: new_var });
                     }
                 }
                 match scope.module().get_item(name.path.as_path(), &mut Vec::new()) {
                     Some(item) => {
-                        let var = {
+                        let (var, is_view) = {
                             let mut item = item.read();
                             let item_type = item.get_type();
-                            let entity_type = match item.get_table() {
-                                Some(table) => &table.entity,
-                                None => return SemanticError::expected_item_of_another_type(
+                            let (entity_type, is_view) = if let Some(table) = item.get_table() {
+                                (&table.entity, false)
+                            } else if let Some(view) = item.get_view() {
+                                (&view.entity, true)
+                            } else {
+                                return SemanticError::expected_item_of_another_type(
                                     name.pos,
                                     SemanticItemType::Table,
                                     item_type,
                                 )
-                                    .into_err_vec(),
+                                    .into_err_vec();
                             };
                             let new_var_name = match alias {
                                 Some(alias) => alias.text(),
@@ -104,17 +143,54 @@ impl<'source> Resolve<SyncRef<FunctionVariableScope>> for DataSourceAST<'source>
                                 let mut var_guard = var.write();
                                 var_guard.mark_as_automatic();
                             }
-                            var
+                            (var, is_view)
                         };
-                        Ok(DataSource::Table { item, var })
+                        if is_view {
+                            Ok(DataSource::View { item, var })
+                        } else {
+                            Ok(DataSource::Table { item, var })
+                        }
                     }
-                    None => SemanticError::unresolved_item(name.pos, name.path.clone()).into_err_vec(),
+                    None => scope.module().unresolved_item_error(name.pos, &name.path).into_err_vec(),
                 }
             }
-            DataSourceAST::Join { join_type, condition, left, right } => {
+            DataSourceAST::Join { join_type, condition, left, right, pos } => {
                 let left = left.resolve(scope)?;
                 let right = right.resolve(scope)?;
-                let condition = condition.resolve(scope)?;
+                match (join_type, condition) {
+                    (JoinType::Cross, Some(condition)) => {
+                        return SemanticError::not_allowed_inside(condition.pos, "\"on\" condition", "cross join")
+                            .into_err_vec();
+                    }
+                    (JoinType::Cross, None) => {}
+                    // T-SQL не допускает внутренние и внешние соединения без `ON`, в том числе `LEFT JOIN` и `RIGHT JOIN`
+                    (join_type, None) => {
+                        return SemanticError::join_condition_required(*pos, *join_type)
+                            .into_err_vec();
+                    }
+                    _ => {}
+                }
+                let condition: Option<Expression> = condition.resolve(scope)?;
+                if let Some(condition) = &condition {
+                    if !condition.data_type.can_cast(&BOOLEAN_TYPE) {
+                        return SemanticError::expected_expression_of_another_type(
+                            condition.pos,
+                            BOOLEAN_TYPE.clone(),
+                            condition.data_type.clone(),
+                        )
+                            .into_err_vec();
+                    }
+                }
+                match join_type {
+                    JoinType::Left => right.make_nullable(),
+                    JoinType::Right => left.make_nullable(),
+                    JoinType::Full => {
+                        left.make_nullable();
+                        right.make_nullable();
+                    }
+                    JoinType::Cross |
+                    JoinType::Inner => {}
+                }
                 Ok(DataSource::Join { join_type: *join_type, condition, left, right })
             }
             DataSourceAST::Selection { query, alias } => {
@@ -128,6 +204,35 @@ impl<'source> Resolve<SyncRef<FunctionVariableScope>> for DataSourceAST<'source>
                 )?;
                 Ok(DataSource::Selection { query, alias: alias.to_string(), var })
             }
+            DataSourceAST::FunctionCall { name, arguments, alias, pos } => {
+                let arguments: Vec<Expression> = arguments.resolve(scope)?;
+                let function = match scope.module().get_item(name.path.as_path(), &mut Vec::new()) {
+                    Some(item) => item,
+                    None => return scope.module().unresolved_item_error(name.pos, &name.path).into_err_vec(),
+                };
+                let entity_type = {
+                    let function_guard = function.read();
+                    let function_def = match function_guard.get_function() {
+                        Some(function_def) => function_def,
+                        None => return SemanticError::expected_item_of_another_type(
+                            name.pos,
+                            SemanticItemType::Function,
+                            function_guard.get_type(),
+                        )
+                            .into_err_vec(),
+                    };
+                    let entity_type = match function_def.result.as_array() {
+                        Some(entity_type) => (**entity_type).clone(),
+                        None => return SemanticError::not_allowed_here(*pos, "function call without array result")
+                            .into_err_vec(),
+                    };
+                    Expression::check_function_arguments(*pos, function_def, &arguments)?;
+                    entity_type
+                };
+                let var = scope.new_variable(alias.item_pos(), alias.to_string(), Some(entity_type))?;
+                var.mark_as_automatic();
+                Ok(DataSource::FunctionCall { function, arguments, var })
+            }
         }
     }
 }
@@ -136,11 +241,16 @@ impl<'source> Resolve<SyncRef<FunctionVariableScope>> for DataSourceAST<'source>
 pub enum DataSource {
     Variable {
         var: SyncRef<FunctionVariable>,
+        row_var: SyncRef<FunctionVariable>,
     },
     Table {
         item: SyncRef<Item>,
         var: SyncRef<FunctionVariable>,
     },
+    View {
+        item: SyncRef<Item>,
+        var: SyncRef<FunctionVariable>,
+    },
     Join {
         join_type: JoinType,
         condition: Option<Expression>,
@@ -152,60 +262,122 @@ pub enum DataSource {
         alias: String,
         var: SyncRef<FunctionVariable>,
     },
+    FunctionCall {
+        function: SyncRef<Item>,
+        arguments: Vec<Expression>,
+        var: SyncRef<FunctionVariable>,
+    },
 }
 
 impl DataSource {
+    /// Собирает представления и функции, к которым обращается источник.
+    pub fn collect_used_items(&self, items: &mut Vec<SyncRef<Item>>) {
+        match self {
+            DataSource::Variable { var: _, row_var: _ } |
+            DataSource::Table { item: _, var: _ } => {}
+            DataSource::View { item, var: _ } => items.push(item.clone()),
+            DataSource::Join { join_type: _, condition, left, right } => {
+                if let Some(condition) = condition {
+                    condition.collect_used_items(items);
+                }
+                left.collect_used_items(items);
+                right.collect_used_items(items);
+            }
+            DataSource::Selection { query, alias: _, var: _ } => query.collect_used_items(items),
+            DataSource::FunctionCall { function, arguments, var: _ } => {
+                items.push(function.clone());
+                for argument in arguments.iter() {
+                    argument.collect_used_items(items);
+                }
+            }
+        }
+    }
+    /// Делает типы строк источника обнуляемыми. Применяется к внешней стороне внешних соединений.
+    pub fn make_nullable(&self) {
+        match self {
+            DataSource::Variable { var: _, row_var: var } |
+            DataSource::Table { item: _, var } |
+            DataSource::View { item: _, var } |
+            DataSource::Selection { query: _, alias: _, var } |
+            DataSource::FunctionCall { function: _, arguments: _, var } => {
+                let data_type = var.read()
+                    .data_type()
+                    .cloned();
+                if let Some(data_type) = data_type {
+                    var.replace_data_type(data_type.nullable());
+                }
+            }
+            DataSource::Join { join_type: _, condition: _, left, right } => {
+                left.make_nullable();
+                right.make_nullable();
+            }
+        }
+    }
     pub fn is_allows_updates(&self) -> bool {
         match self {
-            DataSource::Variable { var } => !var.is_read_only(),
+            DataSource::Variable { var, row_var: _ } => !var.is_read_only(),
             DataSource::Table { item: _, var: _ } => true,
             DataSource::Join { join_type: _, condition: _, left, right } => left.is_allows_updates() && right.is_allows_updates(),
-            DataSource::Selection { query: _, alias: _, var: _ } => false,
+            DataSource::View { item: _, var: _ } |
+            DataSource::Selection { query: _, alias: _, var: _ } |
+            DataSource::FunctionCall { function: _, arguments: _, var: _ } => false,
         }
     }
     pub fn is_allows_inserts(&self) -> bool {
         match self {
-            DataSource::Variable { var } => !var.is_read_only(),
+            DataSource::Variable { var, row_var: _ } => !var.is_read_only(),
             DataSource::Table { item: _, var: _ } => true,
             DataSource::Join { join_type: _, condition: _, left: _, right: _ } => false,
-            DataSource::Selection { query: _, alias: _, var: _ } => false,
+            DataSource::View { item: _, var: _ } |
+            DataSource::Selection { query: _, alias: _, var: _ } |
+            DataSource::FunctionCall { function: _, arguments: _, var: _ } => false,
         }
     }
     pub fn get_target_for_insert(&self, pos: ItemPosition) -> Result<SyncRef<FunctionVariable>, SemanticError> {
         match self {
-            DataSource::Variable { var } => Ok(var.clone()),
+            DataSource::Variable { var, row_var: _ } => Ok(var.clone()),
             DataSource::Table { item: _, var } => Ok(var.clone()),
             DataSource::Join { join_type: _, condition: _, left: _, right: _ } =>
                 return Err(SemanticError::not_allowed_inside(pos, "insertion", "JOIN of data sources")),
+            DataSource::View { item: _, var: _ } =>
+                return Err(SemanticError::not_allowed_inside(pos, "insertion", "view")),
             DataSource::Selection { query: _, alias: _, var: _ } =>
                 return Err(SemanticError::not_allowed_inside(pos, "insertion", "SELECT subquery")),
+            DataSource::FunctionCall { function: _, arguments: _, var: _ } =>
+                return Err(SemanticError::not_allowed_inside(pos, "insertion", "function call")),
         }
     }
     pub fn is_allows_deletes(&self) -> bool {
         match self {
-            DataSource::Variable { var } => !var.is_read_only(),
+            DataSource::Variable { var, row_var: _ } => !var.is_read_only(),
             DataSource::Table { item: _, var: _ } => true,
             DataSource::Join { join_type: _, condition: _, left: _, right: _ } => false,
-            DataSource::Selection { query: _, alias: _, var: _ } => false,
+            DataSource::View { item: _, var: _ } |
+            DataSource::Selection { query: _, alias: _, var: _ } |
+            DataSource::FunctionCall { function: _, arguments: _, var: _ } => false,
         }
     }
     pub fn is_target_belongs_to_source(&self, target: &AssignmentTarget) -> bool {
         let inner_var = match self {
-            DataSource::Variable { var } => var,
+            DataSource::Variable { var, row_var: _ } => var,
             DataSource::Table { item: _, var } => var,
+            DataSource::View { item: _, var } => var,
             DataSource::Join { join_type: _, condition: _, left, right } =>
                 return left.is_target_belongs_to_source(target) || right.is_target_belongs_to_source(target),
             DataSource::Selection { query: _, alias: _, var } => var,
+            DataSource::FunctionCall { function: _, arguments: _, var } => var,
         };
         inner_var.is_same_ref(&target.var)
     }
     pub fn is_local(&self) -> bool {
         match self {
-            DataSource::Variable { var: _ } => true,
+            DataSource::Variable { var: _, row_var: _ } => true,
             DataSource::Table { item: _, var: _ } => false,
+            DataSource::View { item: _, var: _ } => false,
             DataSource::Join { join_type: _, condition: _, left, right } =>
                 left.is_local() && right.is_local(),
             DataSource::Selection { query, alias: _, var: _ } => query.source.is_local(),
+            DataSource::FunctionCall { function: _, arguments: _, var: _ } => false,
         }
     }
     pub fn fmt(
@@ -213,42 +385,42 @@ impl DataSource {
         mut f: BlockFormatter<impl fmt::Write>,
         context: &mut TSQLFunctionContext,
         aliases: bool,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         match self {
-            DataSource::Variable { var } => {
+            DataSource::Variable { var, row_var } => {
                 let var_guard = var.read();
+                let prefix = if var_guard.is_automatic() { "" } else { "@" };
                 if aliases {
-                    f.write_line(format_args!("@{name} AS [{name}]", name = var_guard.name()))
+                    f.write_line(format_args!("{}{} AS [{}]", prefix, var_guard.name(), row_var.read().name()))?;
                 } else {
-                    f.write_line(format_args!("@{name}", name = var_guard.name()))
+                    f.write_line(format_args!("{prefix}{name}", prefix = prefix, name = var_guard.name()))?;
                 }
+                Ok(())
             }
-            DataSource::Table { item, var } => {
+            DataSource::Table { item, var } |
+            DataSource::View { item, var } => {
                 let item_guard = item.read();
+                let mut line = f.line()?;
+                item_guard.fmt_object_name(&mut line)?;
                 if aliases {
                     let var_guard = var.read();
-                    f.write_line(format_args!("[{}] AS [{}]", item_guard.get_path(), var_guard.name()))
+                    write!(line, " AS [{}]", var_guard.name())?;
                 } else {
                     let mut var_guard = var.write();
                     var_guard.set_name("".to_string());
-                    f.write_line(format_args!("[{}]", item_guard.get_path()))
                 }
+                Ok(())
             }
             DataSource::Join { join_type, condition, left, right } => {
                 left.fmt(f.clone(), context, aliases)?;
-                {
+                f.write_line(join_type.get_operator())?;
+                right.fmt(f.clone(), context, aliases)?;
+                if let Some(condition) = condition {
                     let mut line = f.line()?;
-                    line.write_str(match join_type {
-                        JoinType::Cross => "CROSS JOIN",
-                        JoinType::Left => "LEFT JOIN",
-                        JoinType::Right => "RIGHT JOIN",
-                    })?;
-                    if let Some(condition) = condition {
-                        line.write_str(" ON ")?;
-                        condition.fmt(&mut line, context)?;
-                    }
+                    line.write_str("ON ")?;
+                    condition.fmt(&mut line, context)?;
                 }
-                right.fmt(f, context, aliases)
+                Ok(())
             }
             DataSource::Selection { query, alias, var: _ } => {
                 query.fmt(f.clone(), context)?;
@@ -257,6 +429,15 @@ impl DataSource {
                 }
                 Ok(())
             }
+            DataSource::FunctionCall { function, arguments, var } => {
+                let mut line = f.line()?;
+                let pos = var.read().pos();
+                Expression::fmt_function_call(&mut line, pos, function, arguments, context)?;
+                if aliases {
+                    write!(line, " AS [{}]", var.read().name())?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -6,7 +6,10 @@
     ```md
     table = module_path not_keyword_identifier
 
+    function_call = module_path "(" comma_list(expression) ")" ["as"] not_keyword_identifier
+
     join_source =
+        | function_call
         | table
         | "(" data_source ")"
         | "(" selection ")" "as" not_keyword_identifier
@@ -23,12 +26,15 @@
         | "natural" "full" ["outer"] "join" join_source  
         | "full" ["outer"] "join" join_source [join_condition]
         | "inner" "join" join_source [join_condition]
-        | ["cross"] "join" join_source
+        | ["cross"] "join" join_source [join_condition]
         | "," join_source
     
     data_source = join_source join_tail*
     ```
 
+    Все соединения, кроме перекрёстного, требуют условия `on`; перекрёстное соединение его не допускает.
+    Строки внешней стороны соединений `left`, `right` и `full` становятся обнуляемыми.
+
     Правила `property_path` и `module_path` определены в модуле `language::others`.
 
     Правила `comma_list`, `keyword`, `not_keyword_identifier` и `symbols` определены в модуле `parser_basics`.
@@ -5,10 +5,14 @@ use language::{
     selection,
 };
 use lexeme_scanner::Token;
+use lexeme_scanner::ItemPosition;
 use parser_basics::{
+    item_position,
     keyword,
     not_keyword_identifier,
     ParserResult,
+    rounded_comma_list,
+    symbol_position,
     symbols,
 };
 use super::*;
@@ -29,9 +33,22 @@ parser_rule!(table(i) -> DataSourceAST<'source> {
     )
 });
 
+parser_rule!(function_call(i) -> DataSourceAST<'source> {
+    do_parse!(i,
+        begin: symbol_position >>
+        name: module_path >>
+        arguments: apply!(rounded_comma_list, expression) >>
+        pos: apply!(item_position, begin) >>
+        opt!(apply!(keyword, "as")) >>
+        alias: not_keyword_identifier >>
+        (DataSourceAST::FunctionCall { name, arguments, alias, pos })
+    )
+});
+
 parser_rule!(join_source(i) -> DataSourceAST<'source> {
     alt!(i,
-        table
+        function_call
+        | table
         | do_parse!(
             apply!(symbols, "(") >>
             source: data_source >>
@@ -49,53 +66,68 @@ parser_rule!(join_source(i) -> DataSourceAST<'source> {
     )
 });
 
-type JoinTail<'source> = (JoinType, Option<ExpressionAST<'source>>, DataSourceAST<'source>);
-parser_rule!(join_tail(i) -> JoinTail<'source> {
+type JoinTail<'source> = (JoinType, Option<ExpressionAST<'source>>, DataSourceAST<'source>, ItemPosition);
+parser_rule!(join_kind(i) -> JoinType {
     alt!(i,
         do_parse!(
             apply!(keyword, "left") >>
             opt!(apply!(keyword, "outer")) >>
             apply!(keyword, "join") >>
-            source: join_source >>
-            condition: opt!(join_condition) >>
-            ((JoinType::Left, condition, source))
+            (JoinType::Left)
         )
         | do_parse!(
             apply!(keyword, "right") >>
             opt!(apply!(keyword, "outer")) >>
             apply!(keyword, "join") >>
-            source: join_source >>
-            condition: opt!(join_condition) >>
-            ((JoinType::Right, condition, source))
+            (JoinType::Right)
         )
         | do_parse!(
-            apply!(keyword, "inner") >>
+            apply!(keyword, "full") >>
+            opt!(apply!(keyword, "outer")) >>
             apply!(keyword, "join") >>
-            source: join_source >>
-            condition: opt!(join_condition) >>
-            ((JoinType::Cross, condition, source))
+            (JoinType::Full)
+        )
+        | do_parse!(
+            opt!(apply!(keyword, "inner")) >>
+            apply!(keyword, "join") >>
+            (JoinType::Inner)
         )
         | do_parse!(
-            opt!(apply!(keyword, "cross")) >>
+            apply!(keyword, "cross") >>
             apply!(keyword, "join") >>
+            (JoinType::Cross)
+        )
+    )
+});
+
+parser_rule!(join_tail(i) -> JoinTail<'source> {
+    alt!(i,
+        do_parse!(
+            begin: symbol_position >>
+            join_type: join_kind >>
             source: join_source >>
-            ((JoinType::Cross, None, source))
+            condition: opt!(join_condition) >>
+            pos: apply!(item_position, begin) >>
+            ((join_type, condition, source, pos))
         )
         | do_parse!(
+            begin: symbol_position >>
             apply!(symbols, ",") >>
             source: join_source >>
-            ((JoinType::Cross, None, source))
+            pos: apply!(item_position, begin) >>
+            ((JoinType::Cross, None, source, pos))
         )
     )
 });
 
 fn fold_join<'source>(mut origin: DataSourceAST<'source>, tails: Vec<JoinTail<'source>>) -> DataSourceAST<'source> {
-    for (join_type, condition, right) in tails {
+    for (join_type, condition, right, pos) in tails {
         origin = DataSourceAST::Join {
             join_type,
             condition,
             left: Box::new(origin),
             right: Box::new(right),
+            pos,
         };
     }
     origin
@@ -19,6 +19,8 @@ use language::ItemPath;
 use lexeme_scanner::ItemPosition;
 use parser_basics::Identifier;
 use project_analysis::{
+    GenerateError,
+    GenerateResult,
     Item,
     Module,
     SemanticError,
@@ -57,75 +59,210 @@ pub enum NumberType {
 }
 
 #[inline]
-pub fn int_class(size: u32) -> &'static str {
+pub fn int_class(size: u32) -> GenerateResult<&'static str> {
     match size {
-        0..=1 => "bit",
-        2..=8 => "tinyint",
-        9..=16 => "smallint",
-        17..=32 => "int",
-        33..=64 => "bigint",
-        _ => panic!("{} is too big size for integer in ms-sql", size),
+        0..=1 => Ok("bit"),
+        2..=8 => Ok("tinyint"),
+        9..=16 => Ok("smallint"),
+        17..=32 => Ok("int"),
+        33..=64 => Ok("bigint"),
+        _ => Err(GenerateError::without_pos(format!("{} is too big size for integer in ms-sql", size))),
     }
 }
 
 #[inline]
-pub fn int_class_ts_mssql(size: u32) -> &'static str {
+pub fn int_class_ts_mssql(size: u32) -> GenerateResult<&'static str> {
     match size {
-        0..=1 => "Bit",
-        2..=8 => "TinyInt",
-        9..=16 => "SmallInt",
-        17..=32 => "Int",
-        33..=64 => "BigInt",
-        _ => panic!("{} is too big size for integer in ms-sql", size),
+        0..=1 => Ok("Bit"),
+        2..=8 => Ok("TinyInt"),
+        9..=16 => Ok("SmallInt"),
+        17..=32 => Ok("Int"),
+        33..=64 => Ok("BigInt"),
+        _ => Err(GenerateError::without_pos(format!("{} is too big size for integer in ms-sql", size))),
     }
 }
 
+/// Максимальная точность типа `decimal`. Она же используется для `decimal` без указания размера.
+pub const DECIMAL_MAX_PRECISION: u32 = 65;
+
+/// Размеры целых чисел, которые может получить результат неявного расширения.
+pub const INTEGER_SIZES: [u32; 5] = [8, 16, 24, 32, 64];
+
+/// Масштаб типа `decimal` без указания размера.
+pub const DECIMAL_DEFAULT_SCALE: u32 = 30;
+
+/// Количество десятичных цифр, достаточное для записи любого целого числа размером `size` бит.
+#[inline]
+pub fn decimal_digits(size: u32) -> u32 {
+    (f64::from(size) * 2f64.log10()).ceil() as u32
+}
+
 impl NumberType {
+    /**
+        Возвращает точность и масштаб числа в десятичном представлении.
+        Для чисел с плавающей точкой возвращает `None`.
+    */
+    pub fn decimal_size(&self) -> Option<(u32, u32)> {
+        match self {
+            NumberType::Bit { size } => Some((decimal_digits(size.unwrap_or(1)), 0)),
+            NumberType::Integer { size, .. } => Some((decimal_digits((*size).into()), 0)),
+            NumberType::Decimal { size, .. } => Some(match *size {
+                Some((precision, scale)) => (precision, scale.unwrap_or(0)),
+                None => (DECIMAL_MAX_PRECISION, DECIMAL_DEFAULT_SCALE),
+            }),
+            NumberType::Boolean |
+            NumberType::Float { .. } => None,
+        }
+    }
+    #[inline]
+    pub fn is_unsigned(&self) -> bool {
+        match self {
+            NumberType::Bit { .. } |
+            NumberType::Boolean => true,
+            NumberType::Integer { unsigned, .. } |
+            NumberType::Decimal { unsigned, .. } => *unsigned,
+            NumberType::Float { .. } => false,
+        }
+    }
+    /// Определяет, может ли число быть без потерь представлено типом с плавающей точкой.
+    fn fits_float(&self, double: bool) -> bool {
+        if double {
+            return true;
+        }
+        match self {
+            NumberType::Bit { size } => size.unwrap_or(1) <= 24,
+            NumberType::Integer { size, .. } => *size <= 24,
+            NumberType::Decimal { .. } => match self.decimal_size() {
+                Some((precision, _)) => precision <= 7,
+                None => false,
+            },
+            NumberType::Float { double: self_double, .. } => !*self_double,
+            NumberType::Boolean => false,
+        }
+    }
+    /// Проверяет, что типы одного вида и совпадают знаком и заполнением нулями, независимо от размера.
+    pub fn has_same_attributes(&self, other: &NumberType) -> bool {
+        match (self, other) {
+            (NumberType::Bit { .. }, NumberType::Bit { .. }) |
+            (NumberType::Boolean, NumberType::Boolean) => true,
+            (
+                NumberType::Integer { unsigned, zerofill, .. },
+                NumberType::Integer { unsigned: other_unsigned, zerofill: other_zerofill, .. },
+            ) |
+            (
+                NumberType::Decimal { unsigned, zerofill, .. },
+                NumberType::Decimal { unsigned: other_unsigned, zerofill: other_zerofill, .. },
+            ) => unsigned == other_unsigned && zerofill == other_zerofill,
+            (NumberType::Float { double, .. }, NumberType::Float { double: other_double, .. }) => double == other_double,
+            _ => false,
+        }
+    }
+    /**
+        Проверяет возможность неявного приведения типа.
+        Приведение выполняется только с расширением: `bit` → `integer` → `decimal` → `float`.
+    */
     pub fn can_cast(&self, target: &NumberType) -> bool {
         match self {
-            NumberType::Bit { size } => {
-                let self_size = size.unwrap_or(1);
-                if let NumberType::Bit { size } = target {
-                    let other_size = size.unwrap_or(1);
-                    return self_size <= other_size;
+            NumberType::Boolean => return *target == NumberType::Boolean,
+            NumberType::Float { double: self_double, .. } => {
+                if let NumberType::Float { double, .. } = target {
+                    return !*self_double || *double;
                 }
+                return false;
             }
-            NumberType::Boolean => {
-                if let NumberType::Boolean = target { return true; }
-            }
-            NumberType::Integer { size: self_size, unsigned: self_unsigned, zerofill: _ } => {
-                if let NumberType::Integer { size, unsigned, zerofill: _ } = target {
-                    if !*self_unsigned && *unsigned { return false; }
-                    return *self_size <= *size;
+            _ => {}
+        }
+        if !self.is_unsigned() && target.is_unsigned() {
+            return false;
+        }
+        match target {
+            NumberType::Boolean => false,
+            NumberType::Bit { size } => {
+                if let NumberType::Bit { size: self_size } = self {
+                    return self_size.unwrap_or(1) <= size.unwrap_or(1);
                 }
+                false
             }
-            NumberType::Decimal { size, unsigned: self_unsigned, zerofill: _ } => {
-                let self_size = match *size {
-                    Some((m, d)) => match d {
-                        Some(d) => (m, d),
-                        None => (m, 30),
-                    },
-                    None => (65, 30),
+            NumberType::Integer { size, .. } => match self {
+                NumberType::Bit { size: self_size } => self_size.unwrap_or(1) <= u32::from(*size),
+                NumberType::Integer { size: self_size, .. } => *self_size <= *size,
+                _ => false,
+            },
+            NumberType::Decimal { .. } => {
+                let (self_precision, self_scale) = match self.decimal_size() {
+                    Some(size) => size,
+                    None => return false,
                 };
-                if let NumberType::Decimal { size, unsigned, zerofill: _ } = target {
-                    let other_size = match *size {
-                        Some((m, d)) => match d {
-                            Some(d) => (m, d),
-                            None => (m, 30),
-                        },
-                        None => (65, 30),
-                    };
-                    if !*self_unsigned && *unsigned { return false; }
-                    return (self_size.0 <= other_size.0) && (self_size.1 <= other_size.1);
-                }
+                let (precision, scale) = match target.decimal_size() {
+                    Some(size) => size,
+                    None => return false,
+                };
+                (self_precision.saturating_sub(self_scale) <= precision.saturating_sub(scale))
+                    && (self_scale <= scale)
             }
-            NumberType::Float { size: _, double: self_double } => {
-                if let NumberType::Float { size: _, double } = target {
-                    return !*self_double || *double;
+            NumberType::Float { double, .. } => self.fits_float(*double),
+        }
+    }
+    /**
+        Возвращает наименьший тип, к которому могут быть неявно приведены оба типа.
+        Если такого типа нет, возвращает `None`.
+    */
+    pub fn promote(&self, other: &NumberType) -> Option<NumberType> {
+        if self.can_cast(other) {
+            return Some(other.clone());
+        }
+        if other.can_cast(self) {
+            return Some(self.clone());
+        }
+        match (self, other) {
+            (NumberType::Boolean, _) | (_, NumberType::Boolean) => None,
+            (NumberType::Float { .. }, _) | (_, NumberType::Float { .. }) => Some(NumberType::Float {
+                size: None,
+                double: !(self.fits_float(false) && other.fits_float(false)),
+            }),
+            (NumberType::Decimal { .. }, _) | (_, NumberType::Decimal { .. }) => {
+                let (left_precision, left_scale) = self.decimal_size()?;
+                let (right_precision, right_scale) = other.decimal_size()?;
+                let scale = left_scale.max(right_scale);
+                let integer_digits = left_precision.saturating_sub(left_scale)
+                    .max(right_precision.saturating_sub(right_scale));
+                Some(NumberType::decimal(integer_digits + scale, scale, self.is_unsigned() && other.is_unsigned()))
+            }
+            _ => {
+                // Остались только целые числа и битовые наборы
+                let unsigned = self.is_unsigned() && other.is_unsigned();
+                let integer_size = |number: &NumberType| -> u32 {
+                    let (size, number_unsigned) = match number {
+                        NumberType::Bit { size } => (size.unwrap_or(1), true),
+                        NumberType::Integer { size, unsigned, .. } => (u32::from(*size), *unsigned),
+                        _ => (0, true),
+                    };
+                    if number_unsigned && !unsigned { size + 1 } else { size }
+                };
+                let size = integer_size(self).max(integer_size(other));
+                match INTEGER_SIZES.iter().find(|&&integer_size| integer_size >= size) {
+                    Some(&size) => Some(NumberType::Integer {
+                        size: size as u8,
+                        unsigned,
+                        zerofill: false,
+                    }),
+                    None => {
+                        let (left_precision, _) = self.decimal_size()?;
+                        let (right_precision, _) = other.decimal_size()?;
+                        Some(NumberType::decimal(left_precision.max(right_precision) + 1, 0, unsigned))
+                    }
                 }
             }
         }
-        false
+    }
+    /// Создаёт тип `decimal`, ограничивая точность максимально допустимой.
+    pub fn decimal(precision: u32, scale: u32, unsigned: bool) -> NumberType {
+        let precision = precision.min(DECIMAL_MAX_PRECISION).max(1);
+        NumberType::Decimal {
+            size: Some((precision, Some(scale.min(precision)))),
+            unsigned,
+            zerofill: false,
+        }
     }
     pub fn check(&self) -> Result<(), SemanticErrorKind> {
         match self {
@@ -194,23 +331,24 @@ impl fmt::Display for NumberType {
 }
 
 impl<'a> Format<TSQLParameters<'a>> for NumberType {
-    fn fmt(&self, f: &mut impl fmt::Write, _parameters: TSQLParameters<'a>) -> fmt::Result {
+    fn fmt(&self, f: &mut impl fmt::Write, _parameters: TSQLParameters<'a>) -> GenerateResult {
         match self {
             NumberType::Bit { size } => {
-                f.write_str(int_class(size.unwrap_or(1)))
+                f.write_str(int_class(size.unwrap_or(1))?)?;
             }
-            NumberType::Boolean => f.write_str("bit"),
-            NumberType::Integer { size, .. } => f.write_str(int_class((*size).into())),
+            NumberType::Boolean => f.write_str("bit")?,
+            NumberType::Integer { size, .. } => f.write_str(int_class((*size).into())?)?,
             NumberType::Decimal { size, .. } => match size {
-                None => f.write_str("decimal"),
-                Some((p, None)) => write!(f, "decimal({})", p),
-                Some((p, Some(s))) => write!(f, "decimal({}, {})", p, s),
+                None => f.write_str("decimal")?,
+                Some((p, None)) => write!(f, "decimal({})", p)?,
+                Some((p, Some(s))) => write!(f, "decimal({}, {})", p, s)?,
             }
             NumberType::Float { double, .. } => {
                 let class = if *double { "double" } else { "float" };
-                f.write_str(class)
+                f.write_str(class)?;
             }
         }
+        Ok(())
     }
 }
 
@@ -258,7 +396,7 @@ impl fmt::Display for DateTimeType {
 }
 
 impl<'a> Format<TSQLParameters<'a>> for DateTimeType {
-    fn fmt(&self, f: &mut impl fmt::Write, _parameters: TSQLParameters<'a>) -> fmt::Result {
+    fn fmt(&self, f: &mut impl fmt::Write, _parameters: TSQLParameters<'a>) -> GenerateResult {
         let (class, precision) = match self {
             DateTimeType::Date => ("date", &None),
             DateTimeType::Time { precision } => ("time", precision),
@@ -267,10 +405,9 @@ impl<'a> Format<TSQLParameters<'a>> for DateTimeType {
         };
         f.write_str(class)?;
         if let Some(p) = precision {
-            write!(f, "({})", p)
-        } else {
-            Ok(())
+            write!(f, "({})", p)?;
         }
+        Ok(())
     }
 }
 
@@ -301,8 +438,9 @@ impl fmt::Display for YearType {
 }
 
 impl<'a> Format<TSQLParameters<'a>> for YearType {
-    fn fmt(&self, f: &mut impl fmt::Write, _parameters: TSQLParameters<'a>) -> fmt::Result {
-        f.write_str("smallint")
+    fn fmt(&self, f: &mut impl fmt::Write, _parameters: TSQLParameters<'a>) -> GenerateResult {
+        f.write_str("smallint")?;
+        Ok(())
     }
 }
 
@@ -382,18 +520,17 @@ impl fmt::Display for StringType {
 }
 
 impl<'a> Format<TSQLParameters<'a>> for StringType {
-    fn fmt(&self, f: &mut impl fmt::Write, _parameters: TSQLParameters<'a>) -> fmt::Result {
+    fn fmt(&self, f: &mut impl fmt::Write, _parameters: TSQLParameters<'a>) -> GenerateResult {
         match self {
             StringType::Varchar { size, .. } => {
                 f.write_str("nvarchar")?;
                 if let Some(size) = size {
-                    write!(f, "({})", size)
-                } else {
-                    Ok(())
+                    write!(f, "({})", size)?;
                 }
             }
-            StringType::Text { .. } => f.write_str("ntext"),
+            StringType::Text { .. } => f.write_str("ntext")?,
         }
+        Ok(())
     }
 }
 
@@ -429,6 +566,46 @@ impl PrimitiveDataType {
         }
         false
     }
+    pub fn promote(&self, other: &PrimitiveDataType) -> Option<PrimitiveDataType> {
+        match (self, other) {
+            (PrimitiveDataType::Number(left), PrimitiveDataType::Number(right)) => {
+                left.promote(right).map(PrimitiveDataType::Number)
+            }
+            _ => None,
+        }
+    }
+    /// Таблица явных преобразований, допустимых в выражении `cast(expr, type)`.
+    pub fn can_explicit_cast(&self, target: &PrimitiveDataType) -> bool {
+        match self {
+            PrimitiveDataType::Null => true,
+            PrimitiveDataType::Number(number) => match target {
+                PrimitiveDataType::Number(_) |
+                PrimitiveDataType::String(_) => true,
+                PrimitiveDataType::Year(_) => match number {
+                    NumberType::Integer { .. } |
+                    NumberType::Decimal { .. } => true,
+                    _ => false,
+                },
+                _ => false,
+            },
+            PrimitiveDataType::DateTime(_) => match target {
+                PrimitiveDataType::DateTime(_) |
+                PrimitiveDataType::String(_) => true,
+                _ => false,
+            },
+            PrimitiveDataType::Year(_) => match target {
+                PrimitiveDataType::Number(NumberType::Integer { .. }) |
+                PrimitiveDataType::Number(NumberType::Decimal { .. }) |
+                PrimitiveDataType::Year(_) |
+                PrimitiveDataType::String(_) => true,
+                _ => false,
+            },
+            PrimitiveDataType::String(_) => match target {
+                PrimitiveDataType::Null => false,
+                _ => true,
+            },
+        }
+    }
     #[inline]
     pub fn check(&self) -> Result<(), SemanticErrorKind> {
         match self {
@@ -436,30 +613,30 @@ impl PrimitiveDataType {
             _ => Ok(()),
         }
     }
-    pub fn fmt_ts_mssql(&self, f: &mut impl Write) -> fmt::Result {
+    pub fn fmt_ts_mssql(&self, f: &mut impl Write) -> GenerateResult {
         match self {
-            PrimitiveDataType::Null => f.write_str("Bit"),
+            PrimitiveDataType::Null => f.write_str("Bit")?,
             PrimitiveDataType::Number(NumberType::Bit { size }) => {
-                f.write_str(int_class_ts_mssql(size.unwrap_or(1)))
+                f.write_str(int_class_ts_mssql(size.unwrap_or(1))?)?;
             }
-            PrimitiveDataType::Number(NumberType::Boolean) => f.write_str("Bit"),
+            PrimitiveDataType::Number(NumberType::Boolean) => f.write_str("Bit")?,
             PrimitiveDataType::Number(NumberType::Integer { size, .. }) => {
-                f.write_str(int_class_ts_mssql((*size).into()))
+                f.write_str(int_class_ts_mssql((*size).into())?)?;
             }
             PrimitiveDataType::Number(NumberType::Decimal { size, .. }) => {
                 match size {
-                    Some((precision, Some(scale))) => write!(f, "Decimal({}, {})", precision, scale),
-                    Some((precision, None)) => write!(f, "Decimal({})", precision),
-                    None => write!(f, "Decimal"),
+                    Some((precision, Some(scale))) => write!(f, "Decimal({}, {})", precision, scale)?,
+                    Some((precision, None)) => write!(f, "Decimal({})", precision)?,
+                    None => write!(f, "Decimal")?,
                 }
             }
             PrimitiveDataType::Number(NumberType::Float { size, double }) => {
                 match size {
-                    Some((precision, scale)) => write!(f, "Float({}, {})", precision, scale),
+                    Some((precision, scale)) => write!(f, "Float({}, {})", precision, scale)?,
                     None => if *double {
-                        write!(f, "Float(24)")
+                        write!(f, "Float(24)")?;
                     } else {
-                        write!(f, "Float(53)")
+                        write!(f, "Float(53)")?;
                     }
                 }
             }
@@ -471,21 +648,22 @@ impl PrimitiveDataType {
                     DateTimeType::Timestamp { precision } => ("DateTime2", precision),
                 };
                 if let Some(precision) = precision {
-                    write!(f, "{}({})", class, precision)
+                    write!(f, "{}({})", class, precision)?;
                 } else {
-                    write!(f, "{}", class)
+                    write!(f, "{}", class)?;
                 }
             },
-            PrimitiveDataType::Year(_) => f.write_str("SmallInt"),
+            PrimitiveDataType::Year(_) => f.write_str("SmallInt")?,
             PrimitiveDataType::String(StringType::Varchar { size, .. }) => {
                 if let Some(size) = size {
-                    write!(f, "NVarChar({})", size)
+                    write!(f, "NVarChar({})", size)?;
                 } else {
-                    f.write_str("NVarChar")
+                    f.write_str("NVarChar")?;
                 }
             }
-            PrimitiveDataType::String(StringType::Text { .. }) => f.write_str("NText"),
+            PrimitiveDataType::String(StringType::Text { .. }) => f.write_str("NText")?,
         }
+        Ok(())
     }
 }
 
@@ -503,9 +681,9 @@ impl fmt::Display for PrimitiveDataType {
 }
 
 impl<'a> Format<TSQLParameters<'a>> for PrimitiveDataType {
-    fn fmt(&self, f: &mut impl fmt::Write, parameters: TSQLParameters<'a>) -> fmt::Result {
+    fn fmt(&self, f: &mut impl fmt::Write, parameters: TSQLParameters<'a>) -> GenerateResult {
         match self {
-            PrimitiveDataType::Null => f.write_str("null"),
+            PrimitiveDataType::Null => Ok(f.write_str("null")?),
             PrimitiveDataType::Number(x) => Format::<TSQLParameters>::fmt(x, f, parameters),
             PrimitiveDataType::DateTime(x) => Format::<TSQLParameters>::fmt(x, f, parameters),
             PrimitiveDataType::Year(x) => Format::<TSQLParameters>::fmt(x, f, parameters),
@@ -596,9 +774,94 @@ impl<'source> Resolve<SyncRef<Module>> for FieldAST<'source> {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum StructFieldAST<'source> {
+    Field(Identifier<'source>, FieldAST<'source>),
+    /// Встраивание полей именованной структуры: `..Name`.
+    Spread(ItemPath),
+}
+
+impl<'source> Assertion for StructFieldAST<'source> {
+    fn assert(&self, other: &StructFieldAST) {
+        match self {
+            StructFieldAST::Field(name, field) => match_it!(other,
+                StructFieldAST::Field(other_name, other_field) => {
+                    assert_eq!(name, other_name);
+                    field.assert(other_field);
+                }
+            ),
+            StructFieldAST::Spread(path) => match_it!(other,
+                StructFieldAST::Spread(other_path) => { assert_eq!(path, other_path); }
+            ),
+        }
+    }
+}
+
+fn resolve_spread(path: &ItemPath, ctx: &SyncRef<Module>) -> Result<Arc<IndexMap<String, Field>>, SemanticError> {
+    let item = match ctx.get_item(path.path.as_path(), &mut vec![]) {
+        Some(item) => item,
+        None => return Err(ctx.unresolved_item_error(path.pos, &path.path)),
+    };
+    let item_guard = item.read();
+    let def = match item_guard.get_data_type() {
+        Some(def) => def,
+        None => return Err(SemanticError::expected_item_of_another_type(
+            path.pos,
+            SemanticItemType::DataType,
+            item_guard.get_type(),
+        )),
+    };
+    match def.body.as_structure() {
+        Some(fields) => Ok(fields),
+        None => Err(SemanticError::not_allowed_inside(path.pos, "non-structure type", "structure spread")),
+    }
+}
+
+/**
+    Разрешает поля структуры, встраивая поля структур, указанных через `..`.
+    Атрибуты встроенных полей сохраняются.
+*/
+pub fn resolve_struct_fields<'source>(
+    fields: &[StructFieldAST<'source>],
+    ctx: &SyncRef<Module>,
+) -> Result<IndexMap<String, Field>, Vec<SemanticError>> {
+    let mut result = Vec::with_capacity(fields.len());
+    let mut errors = Vec::new();
+    for field in fields {
+        match field {
+            StructFieldAST::Field(name, field) => match field.resolve(ctx) {
+                Ok(field) => result.push(((name.text().to_string(), name.item_pos()), field)),
+                Err(mut sub_errors) => errors.append(&mut sub_errors),
+            },
+            StructFieldAST::Spread(path) => match resolve_spread(path, ctx) {
+                Ok(embedded) => for (name, field) in embedded.iter() {
+                    result.push(((name.clone(), path.pos), field.clone()));
+                },
+                Err(error) => errors.push(error),
+            },
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let keys: Vec<(String, ItemPosition)> = result.iter()
+        .map(|(key, _)| key.clone())
+        .collect();
+    match as_unique_identifier(result) {
+        Ok(map) => Ok(map),
+        Err((name, pos)) => {
+            let mut error = SemanticError::duplicate_definition(pos, name.clone(), SemanticItemType::Field);
+            if let Some((_, first_pos)) = keys.iter().find(|(key, _)| *key == name) {
+                error = error.with_label(*first_pos, "first defined here");
+            }
+            error.into_err_vec()
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum CompoundDataTypeAST<'source> {
-    Structure(Vec<(Identifier<'source>, FieldAST<'source>)>),
+    Structure(Vec<StructFieldAST<'source>>),
     Tuple(Vec<FieldAST<'source>>),
 }
 
@@ -642,6 +905,30 @@ impl CompoundDataType {
         }
         false
     }
+    pub fn can_explicit_cast(&self, target: &CompoundDataType) -> bool {
+        match self {
+            CompoundDataType::Structure(self_fields) => {
+                if let CompoundDataType::Structure(fields) = target {
+                    return fields.len() == self_fields.len() && fields.iter()
+                        .all(|(name, field)| match self_fields.get(name.as_str()) {
+                            Some(self_field) => self_field.field_type.can_explicit_cast(&field.field_type),
+                            None => false,
+                        });
+                }
+            }
+            CompoundDataType::Tuple(self_fields) => {
+                if let CompoundDataType::Tuple(fields) = target {
+                    return fields.len() == self_fields.len() && fields.iter()
+                        .enumerate()
+                        .all(|(i, field)| match self_fields.get(i) {
+                            Some(self_field) => self_field.field_type.can_explicit_cast(&field.field_type),
+                            None => false,
+                        });
+                }
+            }
+        }
+        false
+    }
     #[inline]
     pub fn get_field(&self, index: usize) -> Option<&Field> {
         match self {
@@ -666,10 +953,9 @@ impl<'source> Assertion for CompoundDataTypeAST<'source> {
                 let mut other_fields_iter = match_it!(other,
                     CompoundDataTypeAST::Structure(fields) => { fields.iter() }
                 );
-                for (field_name, field) in fields.iter() {
-                    let (other_field_name, other_field) = other_fields_iter.next()
+                for field in fields.iter() {
+                    let other_field = other_fields_iter.next()
                         .expect("Field lists should have equal sizes");
-                    assert_eq!(field_name, other_field_name);
                     field.assert(other_field);
                 }
                 assert_eq!(other_fields_iter.next(), None);
@@ -695,15 +981,7 @@ impl<'source> Resolve<SyncRef<Module>> for CompoundDataTypeAST<'source> {
     fn resolve(&self, ctx: &SyncRef<Module>) -> Result<Self::Result, Vec<Self::Error>> {
         match self {
             CompoundDataTypeAST::Structure(fields) => Ok(CompoundDataType::Structure(
-                match as_unique_identifier(fields.clone()) {
-                    Ok(map) => Arc::new(map.resolve(ctx)?),
-                    Err(name) => return SemanticError::duplicate_definition(
-                        name.item_pos(),
-                        name.text().to_string(),
-                        SemanticItemType::Field,
-                    )
-                        .into_err_vec()
-                }
+                Arc::new(resolve_struct_fields(fields, ctx)?)
             )),
             CompoundDataTypeAST::Tuple(fields) => Ok(CompoundDataType::Tuple(Arc::new(fields.resolve(ctx)?))),
         }
@@ -802,7 +1080,7 @@ impl<'source> Resolve<SyncRef<Module>> for DataTypeAST<'source> {
             DataTypeASTBody::Reference(path) => {
                 let item = match ctx.get_item(path.path.as_path(), &mut vec![]) {
                     Some(item) => item,
-                    None => return SemanticError::unresolved_item(path.pos, path.path.clone()).into_err_vec(),
+                    None => return ctx.unresolved_item_error(path.pos, &path.path).into_err_vec(),
                 };
                 let item_type = item.get_type();
                 if item_type != SemanticItemType::DataType {
@@ -825,12 +1103,64 @@ pub enum DataType {
     Compound(CompoundDataType),
     Primitive(PrimitiveDataType),
     Reference(SyncRef<Item>),
+    /// Значение, которое может отсутствовать. Неявно приводится только к обнуляемым типам.
+    Nullable(Arc<DataType>),
     Void,
 }
 
 pub const BOOLEAN_TYPE: DataType = DataType::Primitive(PrimitiveDataType::Number(NumberType::Boolean));
 
 impl DataType {
+    /// Оборачивает тип в `Nullable`, если он ещё не является таковым.
+    pub fn nullable(self) -> DataType {
+        match self {
+            DataType::Nullable(_) |
+            DataType::Void => self,
+            other => DataType::Nullable(Arc::new(other)),
+        }
+    }
+    /// Возвращает поля структуры, раскрывая ссылки на именованные типы.
+    pub fn as_structure(&self) -> Option<Arc<IndexMap<String, Field>>> {
+        match self {
+            DataType::Compound(CompoundDataType::Structure(fields)) => Some(fields.clone()),
+            DataType::Reference(item) => {
+                let item_guard = item.read();
+                item_guard.get_data_type()?.body.as_structure()
+            }
+            _ => None,
+        }
+    }
+    /// Возвращает базовый тип обнуляемого типа или сам тип, если он не обнуляемый.
+    #[inline]
+    pub fn without_nullable(&self) -> &DataType {
+        match self {
+            DataType::Nullable(sub_type) => sub_type,
+            other => other,
+        }
+    }
+    #[inline]
+    pub fn is_nullable(&self) -> bool {
+        match self {
+            DataType::Nullable(_) => true,
+            _ => false,
+        }
+    }
+    /// Возвращает определение номинального типа, на который ссылается данный тип (в том числе через псевдонимы).
+    pub fn nominal_item(&self) -> Option<SyncRef<Item>> {
+        match self {
+            DataType::Nullable(sub_type) => sub_type.nominal_item(),
+            DataType::Reference(item) => {
+                let item_guard = item.read();
+                let def = item_guard.get_data_type()?;
+                if def.nominal {
+                    Some(item.clone())
+                } else {
+                    def.body.nominal_item()
+                }
+            }
+            _ => None,
+        }
+    }
     pub fn property_type(&self, pos: ItemPosition, prop: Path) -> Result<DataType, SemanticError> {
         let mut path = prop;
         let field_name = match path.pop_left() {
@@ -838,10 +1168,18 @@ impl DataType {
             None => return Ok(self.clone()),
         };
         match self {
+            DataType::Nullable(sub_type) => {
+                return sub_type.property_type(pos, prop)
+                    .map(DataType::nullable);
+            }
             DataType::Compound(CompoundDataType::Structure(fields)) => {
                 if let Some(field) = fields.get(field_name) {
                     return field.field_type.property_type(pos, path);
                 }
+                return Err(
+                    SemanticError::wrong_property(pos, field_name.to_string())
+                        .with_suggestions(field_name, fields.keys().map(String::as_str))
+                );
             }
             DataType::Compound(CompoundDataType::Tuple(fields)) => {
                 if let Some(component) = parse_index(field_name) {
@@ -861,6 +1199,9 @@ impl DataType {
         Err(SemanticError::wrong_property(pos, field_name.to_string()))
     }
     pub fn can_cast(&self, target: &DataType) -> bool {
+        if let (Some(source), Some(target)) = (self.nominal_item(), target.nominal_item()) {
+            return source.is_same_ref(&target);
+        }
         if let DataType::Reference(reference) = target {
             let guard = reference.read();
             let data_type = match guard.get_data_type() {
@@ -869,12 +1210,20 @@ impl DataType {
             };
             return self.can_cast(&data_type.body);
         }
+        if let DataType::Nullable(subtype) = target {
+            return match self {
+                DataType::Nullable(self_subtype) => self_subtype.can_cast(&*subtype),
+                _ => self.can_cast(&*subtype),
+            };
+        }
         match self {
             DataType::Array(self_subtype) => {
                 if let DataType::Array(subtype) = target {
                     return self_subtype.can_cast(&*subtype);
                 }
             }
+            // Обнуляемое значение приводится к необнуляемому типу только явно
+            DataType::Nullable(_) => return false,
             DataType::Compound(self_subtype) => {
                 if let DataType::Compound(subtype) = target {
                     return self_subtype.can_cast(&*subtype);
@@ -908,6 +1257,70 @@ impl DataType {
             ))
         }
     }
+    /**
+        Возвращает общий расширенный тип для двух примитивных типов.
+        Для остальных типов возвращает `None`.
+    */
+    pub fn promote(&self, other: &DataType) -> Option<DataType> {
+        if (*self == DataType::Void) || (*other == DataType::Void) {
+            return None;
+        }
+        let left = self.as_primitive()?;
+        let right = other.as_primitive()?;
+        left.promote(&right).map(DataType::Primitive)
+    }
+    pub fn can_explicit_cast(&self, target: &DataType) -> bool {
+        if let DataType::Reference(reference) = target {
+            let guard = reference.read();
+            let data_type = match guard.get_data_type() {
+                Some(data_type) => data_type,
+                None => return false,
+            };
+            return self.can_explicit_cast(&data_type.body);
+        }
+        if let DataType::Nullable(subtype) = target {
+            return self.can_explicit_cast(&*subtype);
+        }
+        match self {
+            DataType::Nullable(self_subtype) => return self_subtype.can_explicit_cast(target),
+            DataType::Array(self_subtype) => {
+                if let DataType::Array(subtype) = target {
+                    return self_subtype.can_explicit_cast(&*subtype);
+                }
+            }
+            DataType::Compound(self_subtype) => {
+                if let DataType::Compound(subtype) = target {
+                    return self_subtype.can_explicit_cast(&*subtype);
+                }
+            }
+            DataType::Primitive(self_subtype) => {
+                if let DataType::Primitive(subtype) = target {
+                    return self_subtype.can_explicit_cast(&*subtype);
+                }
+            }
+            DataType::Reference(reference) => {
+                let guard = reference.read();
+                let data_type = match guard.get_data_type() {
+                    Some(data_type) => data_type,
+                    None => return false,
+                };
+                return data_type.body.can_explicit_cast(target);
+            }
+            DataType::Void => return *target == DataType::Void,
+        }
+        false
+    }
+    pub fn should_explicit_cast_to(&self, pos: ItemPosition, target: &DataType) -> Result<(), SemanticError> {
+        if self.can_explicit_cast(target) {
+            Ok(())
+        } else {
+            Err(SemanticError::cannot_convert_type(
+                pos,
+                self.clone(),
+                target.clone(),
+            ))
+        }
+    }
     pub fn get_field_type(&self, index: usize) -> Option<DataType> {
         let one = match self {
             DataType::Array(item) => &*item,
@@ -919,6 +1332,8 @@ impl DataType {
                 let def = item.get_data_type()?;
                 return def.body.get_field_type(index);
             }
+            DataType::Nullable(sub_type) => return sub_type.get_field_type(index)
+                .map(DataType::nullable),
             DataType::Void => self,
         };
         if index == 0 { Some(one.clone()) } else { None }
@@ -929,6 +1344,7 @@ impl DataType {
             DataType::Primitive(_) |
             DataType::Void => 1,
             DataType::Compound(compound) => compound.field_len(),
+            DataType::Nullable(sub_type) => sub_type.field_len(),
             DataType::Reference(item) => {
                 let item = item.read();
                 let def = match item.get_data_type() {
@@ -941,6 +1357,7 @@ impl DataType {
     }
     pub fn make_primitives(&self, prefix: PathBuf, target: &mut Vec<FieldPrimitive>) {
         match self {
+            DataType::Nullable(sub_type) => sub_type.make_primitives(prefix, target),
             DataType::Array(sub_type) => {
                 let mut sub_prefix = prefix;
                 sub_prefix.push("[]");
@@ -992,6 +1409,7 @@ impl DataType {
         match self {
             DataType::Array(_) |
             DataType::Compound(_) => true,
+            DataType::Nullable(sub_type) => sub_type.can_be_table(),
             DataType::Reference(item) => {
                 let item = item.read();
                 if let Some(data_type) = item.get_data_type() {
@@ -1013,6 +1431,7 @@ impl DataType {
                 self.make_primitives(prefix, consumer);
                 true
             }
+            DataType::Nullable(sub_type) => sub_type.make_table_type(prefix, consumer),
             DataType::Reference(item) => {
                 let item = item.read();
                 if let Some(data_type) = item.get_data_type() {
@@ -1037,6 +1456,7 @@ impl DataType {
     pub fn as_primitive(&self) -> Option<PrimitiveDataType> {
         match self {
             DataType::Primitive(x) => Some(x.clone()),
+            DataType::Nullable(sub_type) => sub_type.as_primitive(),
             DataType::Reference(item) => {
                 let item_guard = item.read();
                 let references_data_type = item_guard.get_data_type()?;
@@ -1054,6 +1474,7 @@ impl DataType {
     pub fn as_array(&self) -> Option<&Arc<DataType>> {
         match self {
             DataType::Array(sub_type) => Some(sub_type),
+            DataType::Nullable(sub_type) => sub_type.as_array(),
             _ => None,
         }
     }
@@ -1066,6 +1487,11 @@ impl DataType {
                 sub_type.fmt(f)?;
                 f.write_str("[]")
             }
+            DataType::Nullable(sub_type) => {
+                f.write_str("(")?;
+                sub_type.fmt(f)?;
+                f.write_str(" | null)")
+            }
             DataType::Compound(CompoundDataType::Structure(fields)) => {
                 if fields.is_empty() {
                     return f.write_str("{}");
@@ -1122,8 +1548,10 @@ impl DataType {
             }
             DataType::Reference(reference) => {
                 let guard = reference.read();
+                let path = guard.get_path()
+                    .map_err(|_| fmt::Error)?;
                 f.write_str(
-                    guard.get_path()
+                    path
                         .as_path()
                         .into_new_buf(".")
                         .data.as_str()
@@ -1143,6 +1571,7 @@ impl DataType {
             DataType::Array(_) |
             DataType::Primitive(_) |
             DataType::Reference(_) |
+            DataType::Nullable(_) |
             DataType::Void => {
                 write!(f, "export type {} = ", name)?;
                 self.fmt(f)?;
@@ -1160,9 +1589,10 @@ impl DataType {
         f: &mut SimpleFormatter,
         variable: &str,
         prefix: Path,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         match self {
-            DataType::Array(_) => panic!("Array-type cannot be bind"),
+            DataType::Array(_) => return Err(GenerateError::without_pos("array type cannot be bound to the result of a query")),
+            DataType::Nullable(sub_type) => sub_type.fmt_result_bind(f, variable, prefix)?,
             DataType::Compound(CompoundDataType::Tuple(fields)) => {
                 writeln!(f, "[")?;
                 {
@@ -1178,7 +1608,7 @@ impl DataType {
                         writeln!(sub_f, ",")?;
                     }
                 }
-                write!(f, "]")
+                write!(f, "]")?;
             }
             DataType::Compound(CompoundDataType::Structure(fields)) => {
                 writeln!(f, "{{")?;
@@ -1196,7 +1626,7 @@ impl DataType {
                         writeln!(sub_f, ",")?;
                     }
                 }
-                write!(f, "}}")
+                write!(f, "}}")?;
             }
             DataType::Primitive(_) => {
                 write!(
@@ -1204,20 +1634,23 @@ impl DataType {
                     "{var}['{path}']",
                     var = variable,
                     path = prefix,
-                )
+                )?;
             }
             DataType::Reference(item) => {
-                item.read()
-                    .get_data_type()
-                    .expect("Wrong references are not allowed at generate-time")
-                    .body.fmt_result_bind(
-                        f,
-                        variable,
-                        prefix
-                    )
+                let item = item.read();
+                let data_type = match item.get_data_type() {
+                    Some(data_type) => data_type,
+                    None => return Err(GenerateError::without_pos(format!("{} is not a data type", item.get_path()?))),
+                };
+                data_type.body.fmt_result_bind(
+                    f,
+                    variable,
+                    prefix
+                )?;
             }
-            DataType::Void => f.write_str("void 0"),
+            DataType::Void => f.write_str("void 0")?,
         }
+        Ok(())
     }
 }
 
@@ -1225,6 +1658,7 @@ impl fmt::Display for DataType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             DataType::Array(subtype) => write!(f, "[{}]", subtype),
+            DataType::Nullable(subtype) => write!(f, "{}?", subtype),
             DataType::Compound(CompoundDataType::Structure(fields)) => {
                 write!(f, "{{")?;
                 let mut fields = fields.iter();
@@ -1251,6 +1685,7 @@ impl fmt::Display for DataType {
             DataType::Reference(refer) => {
                 let reference = refer.read();
                 match reference.get_data_type() {
+                    Some(def) if def.nominal => write!(f, "{}", def.name),
                     Some(def) => write!(f, "{}", def.body),
                     None => write!(f, "<not a type>"),
                 }
@@ -1260,12 +1695,13 @@ impl fmt::Display for DataType {
     }
 }
 
-impl PartialEq for DataType {
-    fn eq(&self, rhs: &DataType) -> bool {
+impl DataType {
+    /// Сравнивает типы без учёта номинальности, раскрывая ссылки на определения типов.
+    fn body_eq(&self, rhs: &DataType) -> bool {
         if let DataType::Reference(item) = rhs {
             let item_guard = item.read();
             return match item_guard.get_data_type() {
-                Some(rhs) => *self == rhs.body,
+                Some(rhs) => self.body_eq(&rhs.body),
                 None => false,
             };
         }
@@ -1273,6 +1709,9 @@ impl PartialEq for DataType {
             DataType::Array(lhs_sub_type) => if let DataType::Array(rhs_sub_type) = rhs {
                 *lhs_sub_type == *rhs_sub_type
             } else { false }
+            DataType::Nullable(lhs_sub_type) => if let DataType::Nullable(rhs_sub_type) = rhs {
+                lhs_sub_type.body_eq(rhs_sub_type)
+            } else { false }
             DataType::Compound(lhs_compound) => if let DataType::Compound(rhs_compound) = rhs {
                 *lhs_compound == *rhs_compound
             } else { false }
@@ -1282,7 +1721,7 @@ impl PartialEq for DataType {
             DataType::Reference(item) => {
                 let item_guard = item.read();
                 match item_guard.get_data_type() {
-                    Some(lhs) => lhs.body == *rhs,
+                    Some(lhs) => lhs.body.body_eq(rhs),
                     None => false,
                 }
             }
@@ -1291,6 +1730,24 @@ impl PartialEq for DataType {
     }
 }
 
+impl PartialEq for DataType {
+    fn eq(&self, rhs: &DataType) -> bool {
+        if let (DataType::Reference(lhs), DataType::Reference(rhs)) = (self, rhs) {
+            if lhs.is_same_ref(rhs) {
+                return true;
+            }
+        }
+        match (self.nominal_item(), rhs.nominal_item()) {
+            (None, None) => {}
+            (Some(lhs), Some(rhs)) => if !lhs.is_same_ref(&rhs) {
+                return false;
+            }
+            _ => return false,
+        }
+        self.body_eq(rhs)
+    }
+}
+
 impl Eq for DataType {}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
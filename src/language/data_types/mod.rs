@@ -12,7 +12,9 @@
 
     tuple_field = attributes data_type
 
-    struct_body = attributes "{" ...struct_field "}"
+    struct_spread = ".." module_path
+
+    struct_body = attributes "{" ...(struct_spread | struct_field) "}"
 
     tuple_body = attributes "(" ...tuple_field ")"
 
@@ -22,6 +24,8 @@
     Правило `identifier` определено в модуле `parser_basics`.
 
     Правило `primitive_data_type` определено в модуле `language::primitive_types`.
+
+    Правило `module_path` определено в модуле `language::others`.
 */
 
 pub use self::definitions::*;
@@ -5,13 +5,13 @@ use language::{
     DataTypeASTBody,
     FieldAST,
     module_path,
+    StructFieldAST,
     primitive_data_type,
 };
 use lexeme_scanner::Token;
 use parser_basics::{
     comma_list,
     identifier,
-    Identifier,
     item_position,
     symbol_position,
     symbols,
@@ -40,7 +40,7 @@ parser_rule!(pub attributes(i) -> Vec<AttributeAST<'source>> {
 });
 
 /// attributes identifier ":" data_type
-parser_rule!(struct_field(i) -> (Identifier<'source>, FieldAST<'source>) {
+parser_rule!(struct_field(i) -> StructFieldAST<'source> {
     do_parse!(i,
         begin: symbol_position >>
         attributes: attributes >>
@@ -48,7 +48,16 @@ parser_rule!(struct_field(i) -> (Identifier<'source>, FieldAST<'source>) {
         apply!(symbols, ":") >>
         field_type: data_type >>
         position: apply!(item_position, begin) >>
-        ((name, FieldAST { attributes, field_type, position }))
+        (StructFieldAST::Field(name, FieldAST { attributes, field_type, position }))
+    )
+});
+
+// ".." module_path
+parser_rule!(struct_spread(i) -> StructFieldAST<'source> {
+    do_parse!(i,
+        apply!(symbols, "..") >>
+        path: module_path >>
+        (StructFieldAST::Spread(path))
     )
 });
 
@@ -63,16 +72,20 @@ parser_rule!(tuple_field(i) -> FieldAST<'source> {
     )
 });
 
-/// attributes "{" ...struct_field "}"
-parser_rule!(pub struct_body(i) -> Vec<(Identifier<'source>, FieldAST<'source>)> {
+/// attributes "{" ...(struct_spread | struct_field) "}"
+parser_rule!(pub struct_body(i) -> Vec<StructFieldAST<'source>> {
     do_parse!(i,
         apply!(symbols, "{") >>
-        fields: apply!(comma_list, struct_field) >>
+        fields: apply!(comma_list, struct_item) >>
         apply!(symbols, "}") >>
         (fields)
     )
 });
 
+parser_rule!(struct_item(i) -> StructFieldAST<'source> {
+    alt!(i, struct_spread | struct_field)
+});
+
 /// attributes "(" ...tuple_field ")"
 parser_rule!(tuple_body(i) -> Vec<FieldAST<'source>> {
     do_parse!(i,
@@ -1,4 +1,5 @@
 use helpers::{
+    Format,
     parse_index,
     Path,
     PathBuf,
@@ -7,15 +8,20 @@ use helpers::{
 };
 use helpers::{
     Assertion,
+    CodeFormatter,
     is_f32_enough,
 };
 use language::{
     CompoundDataType,
     DataType,
+    DataTypeAST,
     Field,
+    FunctionDefinition,
     ItemPath,
     NumberType,
     PrimitiveDataType,
+    Selection,
+    SelectionAST,
     StringType,
     TSQLFunctionContext,
 };
@@ -24,6 +30,8 @@ use parser_basics::Identifier;
 use project_analysis::{
     FunctionVariable,
     FunctionVariableScope,
+    GenerateError,
+    GenerateResult,
     Item,
     SemanticError,
     SemanticItemType,
@@ -167,7 +175,497 @@ impl Literal {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Значение константного выражения, вычисленное во время компиляции.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstantValue {
+    Integer(i64),
+    /// Точное десятичное число `value * 10^-scale`.
+    Decimal {
+        value: i128,
+        scale: u32,
+    },
+    Boolean(bool),
+    String(String),
+}
+
+#[inline]
+fn big_integer() -> DataType {
+    DataType::Primitive(PrimitiveDataType::Number(NumberType::Integer {
+        size: 64,
+        unsigned: false,
+        zerofill: false,
+    }))
+}
+
+#[inline]
+fn big_decimal() -> DataType {
+    DataType::Primitive(PrimitiveDataType::Number(NumberType::Decimal {
+        size: None,
+        unsigned: false,
+        zerofill: false,
+    }))
+}
+
+/// Приводит два десятичных числа к общему масштабу. Возвращает `None` при переполнении.
+fn align_decimals(a: (i128, u32), b: (i128, u32)) -> Option<(i128, i128, u32)> {
+    let scale = cmp::max(a.1, b.1);
+    let a_value = a.0.checked_mul(10i128.checked_pow(scale - a.1)?)?;
+    let b_value = b.0.checked_mul(10i128.checked_pow(scale - b.1)?)?;
+    Some((a_value, b_value, scale))
+}
+
+fn greatest_common_divisor(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        let rest = a % b;
+        a = b;
+        b = rest;
+    }
+    // Модуль наименьшего числа не представим, но и без сокращения дроби деление останется верным
+    a.checked_abs().unwrap_or(1)
+}
+
+/**
+    Делит целые числа, получая точное десятичное частное.
+    Возвращает `Ok(None)`, если частное не выражается конечной десятичной дробью,
+    и `Err(())`, если оно не помещается в представление.
+*/
+fn divide_exactly(dividend: i128, divisor: i128) -> Result<Option<(i128, u32)>, ()> {
+    let divider = greatest_common_divisor(dividend, divisor);
+    let (mut numerator, mut denominator) = (dividend / divider, divisor / divider);
+    if denominator < 0 {
+        numerator = numerator.checked_neg().ok_or(())?;
+        denominator = -denominator;
+    }
+    // Конечная десятичная дробь получается, только если знаменатель раскладывается на двойки и пятёрки
+    let mut scale = 0;
+    let mut rest = denominator;
+    while rest % 10 == 0 {
+        rest /= 10;
+        scale += 1;
+    }
+    while rest % 2 == 0 || rest % 5 == 0 {
+        rest /= if rest % 2 == 0 { 2 } else { 5 };
+        scale += 1;
+    }
+    if rest != 1 {
+        return Ok(None);
+    }
+    let multiplier = 10i128.checked_pow(scale).ok_or(())? / denominator;
+    Ok(Some((numerator.checked_mul(multiplier).ok_or(())?, scale)))
+}
+
+/// Проверяет, что целое число помещается в целочисленный тип размером `size` бит.
+fn is_integer_in_range(value: i64, size: u32, unsigned: bool) -> bool {
+    if unsigned {
+        value >= 0 && (size >= 63 || value < (1 << size))
+    } else if size == 0 {
+        value == 0
+    } else {
+        size >= 64 || (value >= -(1 << (size - 1)) && value < (1 << (size - 1)))
+    }
+}
+
+impl ConstantValue {
+    pub fn from_literal(lit: &Literal) -> Result<Self, SemanticError> {
+        match &lit.literal_type {
+            LiteralType::NumberLiteral { fractional: true, negative, radix, .. } => {
+                let out_of_range = || SemanticError::constant_out_of_range(lit.pos, lit.text.clone(), big_decimal());
+                let digits = if *negative { &lit.text[1..] } else { lit.text.as_str() };
+                let digits = if digits.len() > 1 && digits.as_bytes()[1].is_ascii_alphabetic() {
+                    &digits[2..]
+                } else {
+                    digits
+                };
+                let fractional_digits = digits.len() - digits.find('.').map_or(digits.len(), |dot| dot + 1);
+                let mantissa = i128::from_str_radix(&digits.replace('.', ""), *radix)
+                    .map_err(|_| out_of_range())?;
+                let mantissa = if *negative { -mantissa } else { mantissa };
+                // Дробь по основанию 2, 8 или 16 точно выражается десятичной: `1 / 2^n = 5^n / 10^n`
+                let bits = match radix {
+                    2 => 1,
+                    8 => 3,
+                    16 => 4,
+                    _ => 0,
+                };
+                let (value, scale) = if bits == 0 {
+                    (mantissa, fractional_digits as u32)
+                } else {
+                    let scale = bits * fractional_digits as u32;
+                    let value = 5i128.checked_pow(scale)
+                        .and_then(|multiplier| mantissa.checked_mul(multiplier))
+                        .ok_or_else(out_of_range)?;
+                    (value, scale)
+                };
+                Ok(ConstantValue::decimal(value, scale))
+            }
+            LiteralType::NumberLiteral { negative, radix, .. } => {
+                let digits = if *negative { &lit.text[1..] } else { lit.text.as_str() };
+                // После ведущего нуля буква обозначает основание системы счисления: `0x`, `0o` или `0b`
+                let digits = if digits.len() > 1 && digits.as_bytes()[1].is_ascii_alphabetic() {
+                    &digits[2..]
+                } else {
+                    digits
+                };
+                let value = i64::from_str_radix(digits, *radix)
+                    .ok()
+                    .and_then(|value| if *negative { value.checked_neg() } else { Some(value) });
+                match value {
+                    Some(value) => Ok(ConstantValue::Integer(value)),
+                    None => Err(SemanticError::constant_out_of_range(lit.pos, lit.text.clone(), big_integer())),
+                }
+            }
+            LiteralType::StringLiteral { .. } => Ok(ConstantValue::String(lit.text.clone())),
+            LiteralType::BracedExpressionLiteral { .. } => Err(SemanticError::not_supported_yet(lit.pos, "braced expression literals")),
+            LiteralType::KeywordLiteral(keyword) => match keyword {
+                KeywordLiteralType::True => Ok(ConstantValue::Boolean(true)),
+                KeywordLiteralType::False => Ok(ConstantValue::Boolean(false)),
+                KeywordLiteralType::Null => Err(SemanticError::not_supported_yet(lit.pos, "null")),
+            },
+        }
+    }
+    /// Создаёт десятичное число `value * 10^-scale`, отбрасывая нули в конце дробной части.
+    fn decimal(mut value: i128, mut scale: u32) -> Self {
+        while scale > 0 && value % 10 == 0 {
+            value /= 10;
+            scale -= 1;
+        }
+        ConstantValue::Decimal { value, scale }
+    }
+    fn as_decimal(&self) -> Option<(i128, u32)> {
+        match self {
+            ConstantValue::Integer(value) => Some((i128::from(*value), 0)),
+            ConstantValue::Decimal { value, scale } => Some((*value, *scale)),
+            _ => None,
+        }
+    }
+    fn compare(&self, other: &Self) -> Option<cmp::Ordering> {
+        match (self, other) {
+            (ConstantValue::Integer(left), ConstantValue::Integer(right)) => Some(left.cmp(right)),
+            (ConstantValue::Boolean(left), ConstantValue::Boolean(right)) => Some(left.cmp(right)),
+            (ConstantValue::String(left), ConstantValue::String(right)) => Some(left.cmp(right)),
+            (left, right) => {
+                let (left, right, _) = align_decimals(left.as_decimal()?, right.as_decimal()?)?;
+                Some(left.cmp(&right))
+            }
+        }
+    }
+    /**
+        Выполняет бинарную операцию по правилам T-SQL.
+        Целые числа вычисляются как `bigint`, при этом деление целых отбрасывает дробную часть.
+        Дробные числа вычисляются точно; частное, не выражаемое конечной десятичной дробью,
+        и дробная степень не вычисляются.
+    */
+    pub fn binary_operation(pos: ItemPosition, left: Self, op: BinaryOperator, right: Self) -> Result<Self, SemanticError> {
+        let overflow = || SemanticError::constant_out_of_range(pos, format!("{} {} {}", left, op, right), big_integer());
+        let result = match (&left, op, &right) {
+            (ConstantValue::Boolean(a), BinaryOperator::Or, ConstantValue::Boolean(b)) => ConstantValue::Boolean(*a || *b),
+            (ConstantValue::Boolean(a), BinaryOperator::And, ConstantValue::Boolean(b)) => ConstantValue::Boolean(*a && *b),
+            (ConstantValue::Boolean(a), BinaryOperator::XOr, ConstantValue::Boolean(b)) => ConstantValue::Boolean(a != b),
+            (_, BinaryOperator::Equals, _) |
+            (_, BinaryOperator::MoreThanOrEquals, _) |
+            (_, BinaryOperator::MoreThan, _) |
+            (_, BinaryOperator::LessThanOrEquals, _) |
+            (_, BinaryOperator::LessThan, _) => {
+                let ordering = left.compare(&right)
+                    .ok_or_else(|| SemanticError::not_supported_yet(pos, "this comparison in constant expressions"))?;
+                ConstantValue::Boolean(match op {
+                    BinaryOperator::Equals => ordering == cmp::Ordering::Equal,
+                    BinaryOperator::MoreThanOrEquals => ordering != cmp::Ordering::Less,
+                    BinaryOperator::MoreThan => ordering == cmp::Ordering::Greater,
+                    BinaryOperator::LessThanOrEquals => ordering != cmp::Ordering::Greater,
+                    _ => ordering == cmp::Ordering::Less,
+                })
+            }
+            (ConstantValue::Integer(a), op, ConstantValue::Integer(b)) => {
+                let (a, b) = (*a, *b);
+                let result = match op {
+                    BinaryOperator::BitOr => Some(a | b),
+                    BinaryOperator::BitXOr => Some(a ^ b),
+                    BinaryOperator::BitAnd => Some(a & b),
+                    BinaryOperator::ShiftLeft |
+                    BinaryOperator::ShiftRight => {
+                        // Сдвиги генерируются как умножение и деление на степень двойки
+                        let power = if b < 0 { None } else { 2i64.checked_pow(b as u32) };
+                        match (power, op) {
+                            (Some(power), BinaryOperator::ShiftLeft) => a.checked_mul(power),
+                            (Some(power), _) => a.checked_div(power),
+                            (None, _) => None,
+                        }
+                    }
+                    BinaryOperator::Plus => a.checked_add(b),
+                    BinaryOperator::Minus => a.checked_sub(b),
+                    BinaryOperator::Times => a.checked_mul(b),
+                    BinaryOperator::Divide |
+                    BinaryOperator::Mod => {
+                        if b == 0 {
+                            return Err(SemanticError::division_by_zero(pos));
+                        }
+                        if op == BinaryOperator::Divide { a.checked_div(b) } else { a.checked_rem(b) }
+                    }
+                    BinaryOperator::Pow => {
+                        if b >= 0 {
+                            if b > i64::from(u32::max_value()) { None } else { a.checked_pow(b as u32) }
+                        } else {
+                            match a {
+                                0 => return Err(SemanticError::division_by_zero(pos)),
+                                1 => Some(1),
+                                -1 => Some(if b % 2 == 0 { 1 } else { -1 }),
+                                _ => Some(0),
+                            }
+                        }
+                    }
+                    _ => return Err(SemanticError::not_supported_yet(pos, "this operation in constant expressions")),
+                };
+                ConstantValue::Integer(result.ok_or_else(overflow)?)
+            }
+            (left, op, right) => {
+                let decimal_overflow = || SemanticError::constant_out_of_range(pos, format!("{} {} {}", left, op, right), big_decimal());
+                let (left, right) = match (left.as_decimal(), right.as_decimal()) {
+                    (Some(left), Some(right)) => (left, right),
+                    _ => return Err(SemanticError::not_supported_yet(pos, "this operation in constant expressions")),
+                };
+                let (a, b, scale) = align_decimals(left, right).ok_or_else(decimal_overflow)?;
+                let (value, scale) = match op {
+                    BinaryOperator::Plus => (a.checked_add(b), scale),
+                    BinaryOperator::Minus => (a.checked_sub(b), scale),
+                    BinaryOperator::Times => (left.0.checked_mul(right.0), left.1 + right.1),
+                    BinaryOperator::Divide |
+                    BinaryOperator::Mod => {
+                        if b == 0 {
+                            return Err(SemanticError::division_by_zero(pos));
+                        }
+                        if op == BinaryOperator::Mod {
+                            (a.checked_rem(b), scale)
+                        } else {
+                            match divide_exactly(a, b) {
+                                Ok(Some((value, scale))) => (Some(value), scale),
+                                Ok(None) => return Err(SemanticError::not_supported_yet(pos, "inexact division in constant expressions")),
+                                Err(()) => (None, 0),
+                            }
+                        }
+                    }
+                    BinaryOperator::Pow => {
+                        if right.1 != 0 {
+                            return Err(SemanticError::not_supported_yet(pos, "fractional powers in constant expressions"));
+                        }
+                        if left.0 == 0 && right.0 < 0 {
+                            return Err(SemanticError::division_by_zero(pos));
+                        }
+                        let power = match right.0.abs() {
+                            power if power > i128::from(u32::max_value()) => None,
+                            power => left.0.checked_pow(power as u32)
+                                .and_then(|value| Some((value, left.1.checked_mul(power as u32)?))),
+                        };
+                        match power {
+                            Some((value, scale)) if right.0 < 0 => {
+                                // Отрицательная степень вычисляется как частное `10^scale / value`
+                                match 10i128.checked_pow(scale).map(|one| divide_exactly(one, value)) {
+                                    Some(Ok(Some((value, scale)))) => (Some(value), scale),
+                                    Some(Ok(None)) => return Err(SemanticError::not_supported_yet(pos, "inexact division in constant expressions")),
+                                    _ => (None, 0),
+                                }
+                            }
+                            Some((value, scale)) => (Some(value), scale),
+                            None => (None, 0),
+                        }
+                    }
+                    _ => return Err(SemanticError::not_supported_yet(pos, "this operation in constant expressions")),
+                };
+                ConstantValue::decimal(value.ok_or_else(decimal_overflow)?, scale)
+            }
+        };
+        Ok(result)
+    }
+    pub fn prefix_unary_operation(pos: ItemPosition, op: PrefixUnaryOperator, input: Self) -> Result<Self, SemanticError> {
+        let result = match (op, &input) {
+            (PrefixUnaryOperator::Not, ConstantValue::Boolean(value)) => ConstantValue::Boolean(!value),
+            (PrefixUnaryOperator::Plus, ConstantValue::Integer(_)) |
+            (PrefixUnaryOperator::Plus, ConstantValue::Decimal { .. }) => input.clone(),
+            (PrefixUnaryOperator::Minus, ConstantValue::Integer(value)) => {
+                let value = value.checked_neg()
+                    .ok_or_else(|| SemanticError::constant_out_of_range(pos, format!("-{}", input), big_integer()))?;
+                ConstantValue::Integer(value)
+            }
+            (PrefixUnaryOperator::Minus, ConstantValue::Decimal { value, scale }) => {
+                let value = value.checked_neg()
+                    .ok_or_else(|| SemanticError::constant_out_of_range(pos, format!("-{}", input), big_decimal()))?;
+                ConstantValue::Decimal { value, scale: *scale }
+            }
+            (PrefixUnaryOperator::Tilde, ConstantValue::Integer(value)) => ConstantValue::Integer(!value),
+            _ => return Err(SemanticError::not_supported_yet(pos, "this operation in constant expressions")),
+        };
+        Ok(result)
+    }
+    /**
+        Приводит значение к типу `data_type` так, как это сделал бы `CAST` в T-SQL:
+        дробная часть при приведении к целому отбрасывается.
+        Возвращает ошибку, если значение не входит в диапазон типа.
+    */
+    pub fn cast_to(self, pos: ItemPosition, data_type: &DataType) -> Result<Self, SemanticError> {
+        let number_type = match data_type.as_primitive() {
+            Some(PrimitiveDataType::Number(number_type)) => number_type,
+            _ => return Ok(self),
+        };
+        let result = match (&number_type, self) {
+            (NumberType::Boolean, ConstantValue::Integer(value)) => ConstantValue::Boolean(value != 0),
+            (NumberType::Boolean, ConstantValue::Decimal { value, .. }) => ConstantValue::Boolean(value != 0),
+            (NumberType::Float { .. }, ConstantValue::Integer(value)) => ConstantValue::Decimal { value: i128::from(value), scale: 0 },
+            (NumberType::Float { .. }, ConstantValue::Boolean(value)) => ConstantValue::Decimal { value: if value { 1 } else { 0 }, scale: 0 },
+            (NumberType::Integer { .. }, ConstantValue::Decimal { value, scale }) |
+            (NumberType::Bit { .. }, ConstantValue::Decimal { value, scale }) => {
+                let value = 10i128.checked_pow(scale).map_or(0, |divider| value / divider);
+                if value < i128::from(i64::min_value()) || value > i128::from(i64::max_value()) {
+                    return Err(SemanticError::constant_out_of_range(pos, value.to_string(), data_type.clone()));
+                }
+                ConstantValue::Integer(value as i64)
+            }
+            (NumberType::Decimal { size: Some((_, Some(target_scale))), .. }, ConstantValue::Decimal { value, scale }) if scale > *target_scale => {
+                // Лишние знаки дробной части округляются от нуля, как это делает `CAST` в T-SQL
+                let divider = 10i128.checked_pow(scale - target_scale);
+                let rounded = divider.map_or(0, |divider| {
+                    let half = if value < 0 { -divider / 2 } else { divider / 2 };
+                    (value / divider) + if (value % divider).abs() >= half.abs() { half.signum() } else { 0 }
+                });
+                ConstantValue::decimal(rounded, *target_scale)
+            }
+            (NumberType::Integer { .. }, ConstantValue::Boolean(value)) |
+            (NumberType::Bit { .. }, ConstantValue::Boolean(value)) |
+            (NumberType::Decimal { .. }, ConstantValue::Boolean(value)) => ConstantValue::Integer(if value { 1 } else { 0 }),
+            (_, value) => value,
+        };
+        let is_in_range = match (&number_type, &result) {
+            (NumberType::Integer { size, unsigned, .. }, ConstantValue::Integer(value)) => {
+                is_integer_in_range(*value, u32::from(*size), *unsigned)
+            }
+            (NumberType::Bit { size }, ConstantValue::Integer(value)) => {
+                is_integer_in_range(*value, size.unwrap_or(1), true)
+            }
+            (NumberType::Decimal { size: Some((precision, scale)), unsigned, .. }, value) => match value.as_decimal() {
+                Some((value, value_scale)) => {
+                    let integer_digits = precision.saturating_sub(scale.unwrap_or(0));
+                    let integer_part = 10i128.checked_pow(value_scale).map_or(0, |divider| value / divider);
+                    let is_in_precision = match 10i128.checked_pow(integer_digits) {
+                        Some(limit) => integer_part.abs() < limit,
+                        None => true,
+                    };
+                    !(*unsigned && value < 0) && is_in_precision
+                }
+                None => true,
+            },
+            _ => true,
+        };
+        if !is_in_range {
+            return Err(SemanticError::constant_out_of_range(pos, result.to_string(), data_type.clone()));
+        }
+        Ok(result)
+    }
+    /// Выводит значение в виде литерала T-SQL.
+    pub fn fmt(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        match self {
+            ConstantValue::Boolean(value) => f.write_str(if *value { "1" } else { "0" }),
+            other => write!(f, "{}", other),
+        }
+    }
+}
+
+/// Выводит значение в виде литерала TypeScript.
+impl fmt::Display for ConstantValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConstantValue::Integer(value) => write!(f, "{}", value),
+            ConstantValue::Decimal { value, scale } => {
+                let digits = value.unsigned_abs().to_string();
+                let scale = *scale as usize;
+                let digits = if digits.len() <= scale {
+                    format!("{}{}", "0".repeat(scale + 1 - digits.len()), digits)
+                } else {
+                    digits
+                };
+                let (integer_part, fractional_part) = digits.split_at(digits.len() - scale);
+                write!(
+                    f,
+                    "{}{}.{}",
+                    if *value < 0 { "-" } else { "" },
+                    integer_part,
+                    if fractional_part.is_empty() { "0" } else { fractional_part },
+                )
+            }
+            ConstantValue::Boolean(value) => write!(f, "{}", value),
+            ConstantValue::String(value) => f.write_str(value),
+        }
+    }
+}
+
+/// Возвращает значение строкового литерала: без обрамляющих кавычек и с раскрытыми экранированными символами.
+fn decode_string_literal(text: &str) -> String {
+    let mut chars = text[1..text.len() - 1].chars();
+    let mut result = String::new();
+    while let Some(c) = chars.next() {
+        result.push(match c {
+            '\\' => match chars.next() {
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                Some('0') => '\0',
+                Some(c) => c,
+                None => break,
+            },
+            c => c,
+        });
+    }
+    result
+}
+
+/**
+    Преобразует регулярное выражение в шаблон `LIKE`.
+    Поддерживаются только якоря `^` и `$`, символ `.`, последовательность `.*` и экранированные метасимволы.
+    Возвращает `None`, если выражение не может быть выражено шаблоном.
+*/
+fn reg_exp_to_like_pattern(reg_exp: &str) -> Option<String> {
+    let mut chars = reg_exp.chars().peekable();
+    let mut result = String::new();
+    if chars.peek() == Some(&'^') {
+        chars.next();
+    } else {
+        result.push('%');
+    }
+    let mut anchored_end = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '$' if chars.peek().is_none() => anchored_end = true,
+            '.' => if chars.peek() == Some(&'*') {
+                chars.next();
+                result.push('%');
+            } else {
+                result.push('_');
+            },
+            '\\' => match chars.next()? {
+                c @ '%' | c @ '_' | c @ '[' => {
+                    result.push('[');
+                    result.push(c);
+                    result.push(']');
+                }
+                c @ '\\' | c @ '.' | c @ '^' | c @ '$' | c @ '*' | c @ '+' | c @ '?' |
+                c @ '(' | c @ ')' | c @ ']' | c @ '{' | c @ '}' | c @ '|' => result.push(c),
+                // Классы символов вроде `\d` и прочие последовательности шаблоном не выражаются
+                _ => return None,
+            },
+            '%' | '_' | '[' => {
+                result.push('[');
+                result.push(c);
+                result.push(']');
+            }
+            '^' | '$' | '*' | '+' | '?' | '(' | ')' | ']' | '{' | '}' | '|' => return None,
+            c => result.push(c),
+        }
+    }
+    if !anchored_end {
+        result.push('%');
+    }
+    Some(result)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BinaryOperator {
     // Logical operators
     Or,
@@ -261,6 +759,15 @@ impl BinaryOperator {
             BinaryOperator::Interval => "..",
         }
     }
+    /// Возвращает оператор T-SQL, используемый для инфиксной записи операции.
+    pub fn get_tsql_operator(&self) -> &'static str {
+        match self {
+            BinaryOperator::Or => "OR",
+            BinaryOperator::And => "AND",
+            BinaryOperator::Like => "LIKE",
+            other => other.get_operator(),
+        }
+    }
 }
 
 impl fmt::Display for BinaryOperator {
@@ -269,7 +776,7 @@ impl fmt::Display for BinaryOperator {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PrefixUnaryOperator {
     // Logical operators
     Not,
@@ -313,6 +820,13 @@ impl PrefixUnaryOperator {
             PrefixUnaryOperator::Exists => "exists",
         }
     }
+    /// Возвращает оператор T-SQL, используемый для префиксной записи операции.
+    pub fn get_tsql_operator(&self) -> &'static str {
+        match self {
+            PrefixUnaryOperator::Not => "NOT",
+            other => other.get_operator(),
+        }
+    }
 }
 
 impl fmt::Display for PrefixUnaryOperator {
@@ -321,7 +835,7 @@ impl fmt::Display for PrefixUnaryOperator {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PostfixUnaryOperator {
     IsNull,
     IsTrue,
@@ -364,6 +878,8 @@ pub enum ExpressionASTBody<'source> {
     PropertyAccess(Box<ExpressionAST<'source>>, ItemPath),
     Set(Vec<ExpressionAST<'source>>),
     FunctionCall(ItemPath, Vec<ExpressionAST<'source>>),
+    Cast(Box<ExpressionAST<'source>>, DataTypeAST<'source>),
+    Subquery(Box<SelectionAST<'source>>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -421,6 +937,13 @@ impl<'source> Assertion for ExpressionAST<'source> {
                     left_args.as_slice().assert(&right_args.as_slice());
                 } else { assert_eq!(self.body, other.body) }
             }
+            ExpressionASTBody::Cast(left, left_type) => {
+                if let ExpressionASTBody::Cast(right, right_type) = &other.body {
+                    (*left).assert(&**right);
+                    left_type.assert(right_type);
+                } else { assert_eq!(self.body, other.body) }
+            }
+            ExpressionASTBody::Subquery(_) => assert_eq!(self.body, other.body),
         }
     }
 }
@@ -472,6 +995,12 @@ impl<'source> Resolve<SyncRef<FunctionVariableScope>> for ExpressionAST<'source>
             ExpressionASTBody::FunctionCall(function, arguments) => {
                 Expression::function_call(scope, self.pos, function, arguments)
             }
+            ExpressionASTBody::Cast(expr, target) => {
+                Expression::cast(scope, self.pos, expr, target)
+            }
+            ExpressionASTBody::Subquery(query) => {
+                Expression::subquery(scope, self.pos, query)
+            }
         }
     }
 }
@@ -481,12 +1010,16 @@ pub enum ExpressionBody {
     Literal(Literal),
     Variable(SyncRef<FunctionVariable>),
     BinaryOperation(Box<Expression>, BinaryOperator, Box<Expression>),
+    Between(Box<Expression>, Box<Expression>, Box<Expression>),
     PostfixUnaryOperation(PostfixUnaryOperator, Box<Expression>),
     PrefixUnaryOperation(PrefixUnaryOperator, Box<Expression>),
     PropertyAccess(Box<Expression>, ItemPath),
     Set(Vec<Expression>),
     FunctionCall(SyncRef<Item>, Vec<Expression>),
     StdFunctionCall(Arc<StdLibFunction>, Vec<Expression>),
+    Cast(Box<Expression>),
+    Subquery(Box<Selection>),
+    Constant(SyncRef<Item>),
 }
 
 impl cmp::PartialEq for ExpressionBody {
@@ -507,6 +1040,11 @@ impl cmp::PartialEq for ExpressionBody {
                     return (op == other_op) && left.eq(other_left) && right.eq(other_right);
                 }
             }
+            ExpressionBody::Between(expr, low, high) => {
+                if let ExpressionBody::Between(other_expr, other_low, other_high) = other {
+                    return expr.eq(other_expr) && low.eq(other_low) && high.eq(other_high);
+                }
+            }
             ExpressionBody::PostfixUnaryOperation(op, expr) => {
                 if let ExpressionBody::PostfixUnaryOperation(other_op, other_expr) = other {
                     return (op == other_op) && expr.eq(other_expr);
@@ -541,6 +1079,21 @@ impl cmp::PartialEq for ExpressionBody {
                         (arguments == other_arguments);
                 }
             }
+            ExpressionBody::Cast(expr) => {
+                if let ExpressionBody::Cast(other_expr) = other {
+                    return expr.eq(other_expr);
+                }
+            }
+            ExpressionBody::Subquery(query) => {
+                if let ExpressionBody::Subquery(other_query) = other {
+                    return query == other_query;
+                }
+            }
+            ExpressionBody::Constant(item) => {
+                if let ExpressionBody::Constant(other_item) = other {
+                    return item.is_same_ref(other_item);
+                }
+            }
         }
         false
     }
@@ -568,7 +1121,26 @@ impl Expression {
         pos: ItemPosition,
         ident: &Identifier,
     ) -> Result<Self, SemanticError> {
-        let var = scope.access_to_variable(ident.item_pos(), ident.text())?;
+        let var = match scope.access_to_variable(ident.item_pos(), ident.text()) {
+            Ok(var) => var,
+            Err(error) => {
+                let item = scope.module().get_item(Path::new(ident.text(), "::"), &mut Vec::new());
+                if let Some(item) = item {
+                    let data_type = item.read()
+                        .get_constant()
+                        .map(|constant| constant.data_type.clone());
+                    if let Some(data_type) = data_type {
+                        return Ok(Expression {
+                            body: ExpressionBody::Constant(item),
+                            pos,
+                            data_type,
+                        });
+                    }
+                }
+                return Err(error);
+            }
+        };
+        var.mark_as_used();
         let data_type = var.property_type(pos, Path::empty())?;
         Ok(Expression::variable_access(var, pos, data_type))
     }
@@ -592,6 +1164,21 @@ impl Expression {
         right: &Box<ExpressionAST>,
     ) -> Result<Self, Vec<SemanticError>>
     {
+        match op {
+            BinaryOperator::IsIn => {
+                if let ExpressionASTBody::BinaryOperation(low, BinaryOperator::Interval, high) = &right.body {
+                    return Expression::between(scope, pos, left, low, high);
+                }
+            }
+            BinaryOperator::Interval => {
+                return SemanticError::not_allowed_here(pos, "interval outside of \"is in\" expression")
+                    .into_err_vec();
+            }
+            BinaryOperator::RegExp => {
+                return Expression::reg_exp(scope, pos, left, right);
+            }
+            _ => {}
+        }
         let (left, right) = (left, right).resolve(scope)?;
         let data_type = scope.project()
             .resolve_binary_operation(pos, op, &left.data_type, &right.data_type)?
@@ -603,6 +1190,101 @@ impl Expression {
             data_type,
         })
     }
+    pub fn between(
+        scope: &SyncRef<FunctionVariableScope>,
+        pos: ItemPosition,
+        expr: &Box<ExpressionAST>,
+        low: &Box<ExpressionAST>,
+        high: &Box<ExpressionAST>,
+    ) -> Result<Self, Vec<SemanticError>>
+    {
+        let (expr, (low, high)) = (expr, (low, high)).resolve(scope)?;
+        let project = scope.project();
+        let data_type = project
+            .resolve_binary_operation(pos, BinaryOperator::MoreThanOrEquals, &expr.data_type, &low.data_type)?
+            .output
+            .clone();
+        project.resolve_binary_operation(pos, BinaryOperator::LessThanOrEquals, &expr.data_type, &high.data_type)?;
+        Ok(Expression {
+            body: ExpressionBody::Between(expr, low, high),
+            pos,
+            data_type,
+        })
+    }
+    /// Регулярные выражения не поддерживаются T-SQL, поэтому допускаются только шаблоны, выразимые через `LIKE`.
+    pub fn reg_exp(
+        scope: &SyncRef<FunctionVariableScope>,
+        pos: ItemPosition,
+        left: &Box<ExpressionAST>,
+        right: &Box<ExpressionAST>,
+    ) -> Result<Self, Vec<SemanticError>>
+    {
+        let pattern = match &right.body {
+            ExpressionASTBody::Literal(LiteralAST { literal_type: LiteralType::StringLiteral { .. }, text, pos: _ }) => {
+                reg_exp_to_like_pattern(&decode_string_literal(text.text()))
+            }
+            _ => None,
+        };
+        let pattern = match pattern {
+            Some(pattern) => pattern,
+            None => return SemanticError::not_supported_yet(right.pos, "regular expressions which can not be expressed as LIKE patterns")
+                .into_err_vec(),
+        };
+        let left = left.resolve(scope)?;
+        let right = Box::new(Expression {
+            data_type: LiteralType::StringLiteral { length: pattern.len() as u32 }.type_of(right.pos)?,
+            body: ExpressionBody::Literal(Literal {
+                literal_type: LiteralType::StringLiteral { length: pattern.len() as u32 },
+                text: format!("'{}'", pattern.replace('\'', "''")),
+                pos: right.pos,
+            }),
+            pos: right.pos,
+        });
+        let data_type = scope.project()
+            .resolve_binary_operation(pos, BinaryOperator::Like, &left.data_type, &right.data_type)?
+            .output
+            .clone();
+        Ok(Expression {
+            body: ExpressionBody::BinaryOperation(left, BinaryOperator::Like, right),
+            pos,
+            data_type,
+        })
+    }
+    /**
+        Скалярный подзапрос должен выбирать ровно одну примитивную колонку.
+        Если запрос может не вернуть ни одной строки, его тип становится `Nullable`.
+    */
+    pub fn subquery(
+        scope: &SyncRef<FunctionVariableScope>,
+        pos: ItemPosition,
+        query: &Box<SelectionAST>,
+    ) -> Result<Self, Vec<SemanticError>>
+    {
+        let query = query.resolve(scope)?;
+        if query.result.len() != 1 {
+            return SemanticError::select_with_wrong_column_count(pos, 1, query.result.len())
+                .into_err_vec();
+        }
+        let column_type = query.result[0].expr.data_type.clone();
+        if column_type.as_primitive().is_none() {
+            return SemanticError::not_allowed_inside(query.result[0].expr.pos, "non-primitive column", "scalar subquery")
+                .into_err_vec();
+        }
+        // Агрегатный запрос без группировки возвращает ровно одну строку, а запрос с `limit 1` - не больше одной
+        let data_type = if query.result_data_type.as_array().is_none() {
+            column_type
+        } else if query.limit_clause.map_or(false, |limit| limit.count <= 1) {
+            column_type.nullable()
+        } else {
+            return SemanticError::not_allowed_inside(pos, "query which may return several rows", "scalar subquery")
+                .into_err_vec();
+        };
+        Ok(Expression {
+            body: ExpressionBody::Subquery(query),
+            pos,
+            data_type,
+        })
+    }
     pub fn postfix_unary_operation(
         scope: &SyncRef<FunctionVariableScope>,
         pos: ItemPosition,
@@ -695,10 +1377,7 @@ impl Expression {
                         Expression::std_function_call(scope, pos, name, arguments)
                             .map_err(|e| vec![e])
                     }
-                    None => SemanticError::unresolved_item(
-                        function.pos,
-                        function.path.clone(),
-                    )
+                    None => module.unresolved_item_error(function.pos, &function.path)
                         .into_err_vec(),
                 };
             }
@@ -724,24 +1403,7 @@ impl Expression {
                     .into_err_vec();
             }
 
-            if arguments.len() != function.arguments.len() {
-                return SemanticError::wrong_arguments_count(
-                    pos,
-                    function.arguments.len(),
-                    arguments.len(),
-                )
-                    .into_err_vec();
-            }
-
-            for (i, argument) in arguments.iter().enumerate() {
-                let (_, target) = function.arguments.get_index(i)
-                    .expect("The argument can not cease to exist immediately after checking the length of the collection");
-                argument.should_cast_to_type(
-                    target.read()
-                        .data_type()
-                        .expect("Function arguments cannot have undefined data type")
-                )?;
-            }
+            Expression::check_function_arguments(pos, function, &arguments)?;
 
             function.result.clone()
         };
@@ -751,18 +1413,54 @@ impl Expression {
             data_type,
         })
     }
+    pub fn check_function_arguments(
+        pos: ItemPosition,
+        function: &FunctionDefinition,
+        arguments: &[Expression],
+    ) -> Result<(), Vec<SemanticError>> {
+        if arguments.len() != function.arguments.len() {
+            return SemanticError::wrong_arguments_count(
+                pos,
+                function.arguments.len(),
+                arguments.len(),
+            )
+                .into_err_vec();
+        }
+
+        for (i, argument) in arguments.iter().enumerate() {
+            let (_, target) = function.arguments.get_index(i)
+                .expect("The argument can not cease to exist immediately after checking the length of the collection");
+            argument.should_cast_to_type(
+                target.read()
+                    .data_type()
+                    .expect("Function arguments cannot have undefined data type")
+            )?;
+        }
+        Ok(())
+    }
     pub fn std_function_call(
         scope: &SyncRef<FunctionVariableScope>,
         pos: ItemPosition,
         name: &str,
         arguments: Vec<Expression>,
     ) -> Result<Self, SemanticError> {
-        let function = match scope.project().resolve_stdlib_function(name) {
+        let function = {
+            let argument_types: Vec<&DataType> = arguments.iter()
+                .map(|argument| &argument.data_type)
+                .collect();
+            scope.project().resolve_stdlib_function(pos, name, &argument_types)?
+        };
+        let function = match function {
             Some(f) => f,
             None => {
                 let mut path = PathBuf::empty();
                 path.push(name);
-                return Err(SemanticError::unresolved_item(pos, path));
+                let mut candidates = scope.module().read().visible_item_names();
+                candidates.append(&mut scope.project().stdlib_function_names());
+                return Err(
+                    SemanticError::unresolved_item(pos, path)
+                        .with_suggestions(name, candidates.iter().map(String::as_str))
+                );
             }
         };
 
@@ -791,7 +1489,8 @@ impl Expression {
         for (i, argument) in arguments.iter().enumerate() {
             let target_data_type = function.arguments.get(i)
                 .expect("The argument can not cease to exist immediately after checking the length of the collection");
-            argument.should_cast_to_type(target_data_type)?;
+            // Функции стандартной библиотеки, как и в T-SQL, принимают обнуляемые аргументы
+            argument.data_type.without_nullable().should_cast_to(argument.pos, target_data_type)?;
         }
 
         let data_type = function.output.clone();
@@ -802,6 +1501,22 @@ impl Expression {
             data_type,
         })
     }
+    pub fn cast(
+        scope: &SyncRef<FunctionVariableScope>,
+        pos: ItemPosition,
+        expr: &Box<ExpressionAST>,
+        target: &DataTypeAST,
+    ) -> Result<Self, Vec<SemanticError>>
+    {
+        let expr = expr.resolve(scope)?;
+        let data_type = target.resolve(&scope.module())?;
+        expr.data_type.should_explicit_cast_to(pos, &data_type)?;
+        Ok(Expression {
+            body: ExpressionBody::Cast(expr),
+            pos,
+            data_type,
+        })
+    }
     #[inline]
     pub fn can_expressions_be_selected_by_aggregation_query<'a, 'b>(
         expressions: impl IntoIterator<Item=&'a Expression>,
@@ -827,7 +1542,9 @@ impl Expression {
             return Ok(true);
         }
         match &self.body {
-            ExpressionBody::Literal(_) => Ok(true),
+            ExpressionBody::Literal(_) |
+            ExpressionBody::Constant(_) |
+            ExpressionBody::Subquery(_) => Ok(true),
             ExpressionBody::Variable(_) => Ok(false),
             ExpressionBody::BinaryOperation(left, _, right) => {
                 Ok(
@@ -835,13 +1552,21 @@ impl Expression {
                         && right.can_be_selected_by_aggregation_query(aggregates)?
                 )
             }
+            ExpressionBody::Between(expr, low, high) => {
+                Ok(
+                    expr.can_be_selected_by_aggregation_query(aggregates.clone())?
+                        && low.can_be_selected_by_aggregation_query(aggregates.clone())?
+                        && high.can_be_selected_by_aggregation_query(aggregates)?
+                )
+            }
             ExpressionBody::PostfixUnaryOperation(_, expr) => {
                 expr.can_be_selected_by_aggregation_query(aggregates)
             }
             ExpressionBody::PrefixUnaryOperation(_, expr) => {
                 expr.can_be_selected_by_aggregation_query(aggregates)
             }
-            ExpressionBody::PropertyAccess(expr, _) => {
+            ExpressionBody::PropertyAccess(expr, _) |
+            ExpressionBody::Cast(expr) => {
                 expr.can_be_selected_by_aggregation_query(aggregates)
             }
             ExpressionBody::Set(expressions) => {
@@ -906,16 +1631,22 @@ impl Expression {
         match &self.body {
             ExpressionBody::Literal(_) => true,
             ExpressionBody::Variable(_) => true,
+            ExpressionBody::Subquery(_) => true,
+            ExpressionBody::Constant(_) => true,
             ExpressionBody::BinaryOperation(left, _, right) => {
                 left.is_lite_weight() && right.is_lite_weight()
             }
+            ExpressionBody::Between(expr, low, high) => {
+                expr.is_lite_weight() && low.is_lite_weight() && high.is_lite_weight()
+            }
             ExpressionBody::PostfixUnaryOperation(_, expr) => {
                 expr.is_lite_weight()
             }
             ExpressionBody::PrefixUnaryOperation(_, expr) => {
                 expr.is_lite_weight()
             }
-            ExpressionBody::PropertyAccess(expr, _) => {
+            ExpressionBody::PropertyAccess(expr, _) |
+            ExpressionBody::Cast(expr) => {
                 expr.is_lite_weight()
             }
             ExpressionBody::Set(expressions) => {
@@ -937,6 +1668,103 @@ impl Expression {
             }
         }
     }
+    /// Собирает представления и функции, к которым обращается выражение.
+    pub fn collect_used_items(&self, items: &mut Vec<SyncRef<Item>>) {
+        match &self.body {
+            ExpressionBody::Literal(_) |
+            ExpressionBody::Variable(_) |
+            ExpressionBody::Constant(_) => {}
+            ExpressionBody::BinaryOperation(left, _, right) => {
+                left.collect_used_items(items);
+                right.collect_used_items(items);
+            }
+            ExpressionBody::Between(expr, low, high) => {
+                expr.collect_used_items(items);
+                low.collect_used_items(items);
+                high.collect_used_items(items);
+            }
+            ExpressionBody::PostfixUnaryOperation(_, expr) |
+            ExpressionBody::PrefixUnaryOperation(_, expr) |
+            ExpressionBody::PropertyAccess(expr, _) |
+            ExpressionBody::Cast(expr) => {
+                expr.collect_used_items(items);
+            }
+            ExpressionBody::Set(expressions) |
+            ExpressionBody::StdFunctionCall(_, expressions) => {
+                for expr in expressions.iter() {
+                    expr.collect_used_items(items);
+                }
+            }
+            ExpressionBody::FunctionCall(function, expressions) => {
+                items.push(function.clone());
+                for expr in expressions.iter() {
+                    expr.collect_used_items(items);
+                }
+            }
+            ExpressionBody::Subquery(query) => {
+                query.collect_used_items(items);
+            }
+        }
+    }
+    /**
+        Проверяет, что значение выражения известно во время компиляции.
+        Константными считаются литералы, константы, а также операции и приведения над ними.
+    */
+    pub fn is_constant(&self) -> bool {
+        match &self.body {
+            ExpressionBody::Literal(_) |
+            ExpressionBody::Constant(_) => true,
+            ExpressionBody::BinaryOperation(left, _, right) => left.is_constant() && right.is_constant(),
+            ExpressionBody::PrefixUnaryOperation(_, expr) |
+            ExpressionBody::Cast(expr) => expr.is_constant(),
+            _ => false,
+        }
+    }
+    /// Вычисляет значение константного выражения. Приведения проверяют, что значение входит в диапазон типа.
+    pub fn fold_constant(&self) -> Result<ConstantValue, SemanticError> {
+        match &self.body {
+            ExpressionBody::Literal(lit) => ConstantValue::from_literal(lit),
+            ExpressionBody::Constant(item) => {
+                let item_guard = item.read();
+                match item_guard.get_constant() {
+                    Some(constant) => Ok(constant.value.clone()),
+                    None => Err(SemanticError::non_constant_expression(self.pos)),
+                }
+            }
+            ExpressionBody::BinaryOperation(left, op, right) => {
+                ConstantValue::binary_operation(self.pos, left.fold_constant()?, *op, right.fold_constant()?)
+            }
+            ExpressionBody::PrefixUnaryOperation(op, expr) => {
+                ConstantValue::prefix_unary_operation(self.pos, *op, expr.fold_constant()?)
+            }
+            ExpressionBody::Cast(expr) => expr.fold_constant()?.cast_to(self.pos, &self.data_type),
+            _ => Err(SemanticError::non_constant_expression(self.pos)),
+        }
+    }
+    /// Проверяет, читает ли выражение значение переменной. Для подзапросов всегда возвращает `true`.
+    pub fn uses_variable(&self, var: &SyncRef<FunctionVariable>) -> bool {
+        match &self.body {
+            ExpressionBody::Literal(_) |
+            ExpressionBody::Constant(_) => false,
+            ExpressionBody::Variable(other_var) => other_var.is_same_ref(var),
+            ExpressionBody::Subquery(_) => true,
+            ExpressionBody::BinaryOperation(left, _, right) => {
+                left.uses_variable(var) || right.uses_variable(var)
+            }
+            ExpressionBody::Between(expr, low, high) => {
+                expr.uses_variable(var) || low.uses_variable(var) || high.uses_variable(var)
+            }
+            ExpressionBody::PostfixUnaryOperation(_, expr) |
+            ExpressionBody::PrefixUnaryOperation(_, expr) |
+            ExpressionBody::PropertyAccess(expr, _) |
+            ExpressionBody::Cast(expr) => expr.uses_variable(var),
+            ExpressionBody::Set(expressions) |
+            ExpressionBody::FunctionCall(_, expressions) |
+            ExpressionBody::StdFunctionCall(_, expressions) => {
+                expressions.iter().any(|expr| expr.uses_variable(var))
+            }
+        }
+    }
     pub fn get_property(&self, path: Path) -> Option<Expression> {
         if path.is_empty() {
             return Some(self.clone());
@@ -1052,9 +1880,9 @@ impl Expression {
     pub fn fmt_variable_data(
         f: &mut impl fmt::Write,
         var: &FunctionVariable,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         let data_type = var.data_type()
-            .expect("Variables cannot have unknown data-type at generate-time");
+            .ok_or_else(|| GenerateError::new(var.pos(), format!("data type of variable {} is unknown", var.name())))?;
 
         if data_type.as_primitive().is_some() {
             if var.is_automatic() {
@@ -1095,23 +1923,25 @@ impl Expression {
             }
         }
 
-        f.write_str(")")
+        f.write_str(")")?;
+        Ok(())
     }
     pub fn fmt_property_access(
         f: &mut impl fmt::Write,
         expr: &Expression,
         path: Path,
         context: &mut TSQLFunctionContext,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         let path_buf = path.into_new_buf("#");
         let property_data_type = expr.data_type
             .property_type(ItemPosition::default(), path.clone())
-            .expect("Property existing should be already checked at generate-time");
+            .map_err(|_| GenerateError::new(expr.pos, format!("property {} does not exist", path_buf)))?;
         if let ExpressionBody::Variable(var) = &expr.body {
             let var_guard = var.read();
             if property_data_type.as_primitive().is_some() {
                 Expression::fmt_variable(f, &*var_guard, !path_buf.is_empty())?;
-                return f.write_str(&path_buf.data);
+                f.write_str(&path_buf.data)?;
+                return Ok(());
             }
         }
         if let Some(sub_expr) = expr.get_property(path) {
@@ -1135,27 +1965,32 @@ impl Expression {
 
         write!(f, " FROM ")?;
         expr.fmt(f, context)?;
-        f.write_str(" as t )")
+        f.write_str(" as t )")?;
+        Ok(())
     }
     pub fn fmt_function_call(
         f: &mut impl fmt::Write,
+        pos: ItemPosition,
         function: &SyncRef<Item>,
         arguments: &[Expression],
         context: &mut TSQLFunctionContext,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         let function_guard = function.read();
-        let function_def = function_guard.get_function()
-            .expect("item argument of Statement::fmt_pre_call is not a function!");
-        write!(f, "dbo.[{}](", function.read().get_path().data)?;
+        let function_def = match function_guard.get_function() {
+            Some(function_def) => function_def,
+            None => return Err(GenerateError::new(pos, format!("{} is not a function", function_guard.get_path()?))),
+        };
+        function_guard.fmt_object_name(f)?;
+        f.write_char('(')?;
         let mut arguments = arguments.iter()
             .enumerate()
             .peekable();
         while let Some((i, argument)) = arguments.next() {
             let (_, argument_target) = function_def.arguments.get_index(i)
-                .expect("Arguments should not have different count at generate-time.");
+                .ok_or_else(|| GenerateError::new(argument.pos, "function has less arguments than passed"))?;
             let argument_target_guard = argument_target.read();
             let argument_target_data_type = argument_target_guard.data_type()
-                .expect("Arguments cannot have unknown data-type at generate-time");
+                .ok_or_else(|| GenerateError::new(argument_target_guard.pos(), "data type of argument is unknown"))?;
 
             let mut primitives = argument_target_data_type.primitives(PathBuf::new("#"))
                 .into_iter()
@@ -1171,41 +2006,173 @@ impl Expression {
                 }
             }
         }
-        f.write_str(")")
+        f.write_str(")")?;
+        Ok(())
+    }
+    pub fn fmt_cast(
+        f: &mut impl fmt::Write,
+        expr: &Expression,
+        target: &DataType,
+        context: &mut TSQLFunctionContext,
+    ) -> GenerateResult {
+        if let Some(primitive) = target.as_primitive() {
+            f.write_str("CAST(")?;
+            expr.fmt(f, context)?;
+            f.write_str(" AS ")?;
+            Format::fmt(&primitive, f, context.parameters.clone())?;
+            f.write_str(")")?;
+            return Ok(());
+        }
+
+        if let Some(sub_type) = target.as_array() {
+            // Массив преобразуется построчно - каждое поле записи отдельно
+            f.write_str("(SELECT ")?;
+            let mut primitives = sub_type.primitives(PathBuf::new("#"))
+                .into_iter()
+                .peekable();
+            while let Some(primitive) = primitives.next() {
+                write!(f, "CAST(t.[{}] AS ", primitive.path)?;
+                Format::fmt(&primitive.field_type, f, context.parameters.clone())?;
+                write!(f, ") as [{}]", primitive.path)?;
+                if primitives.peek().is_some() {
+                    f.write_str(", ")?;
+                }
+            }
+            f.write_str(" FROM ")?;
+            expr.fmt(f, context)?;
+            f.write_str(" as t)")?;
+            return Ok(());
+        }
+
+        f.write_str("(SELECT ")?;
+        let mut primitives = target.primitives(PathBuf::new("#"))
+            .into_iter()
+            .peekable();
+        while let Some(primitive) = primitives.next() {
+            f.write_str("CAST(")?;
+            match expr.get_property_or_wrap(primitive.path.as_path()) {
+                Some(sub_expr) => sub_expr.fmt(f, context)?,
+                None => expr.fmt(f, context)?,
+            }
+            f.write_str(" AS ")?;
+            Format::fmt(&primitive.field_type, f, context.parameters.clone())?;
+            write!(f, ") as [{}]", primitive.path)?;
+            if primitives.peek().is_some() {
+                f.write_str(", ")?;
+            }
+        }
+        f.write_str(")")?;
+        Ok(())
+    }
+    pub fn fmt_binary_operation(
+        f: &mut impl fmt::Write,
+        left: &Expression,
+        op: BinaryOperator,
+        right: &Expression,
+        data_type: &DataType,
+        context: &mut TSQLFunctionContext,
+    ) -> GenerateResult {
+        match op {
+            BinaryOperator::Pow => {
+                f.write_str("POWER(")?;
+                left.fmt(f, context)?;
+                f.write_str(", ")?;
+                right.fmt(f, context)?;
+                f.write_str(")")?;
+            }
+            BinaryOperator::Div => {
+                f.write_str("CAST(( ")?;
+                left.fmt(f, context)?;
+                f.write_str(" / ")?;
+                right.fmt(f, context)?;
+                let result_type = data_type.as_primitive()
+                    .ok_or_else(|| GenerateError::new(left.pos, "result of division should be primitive"))?;
+                f.write_str(" ) AS ")?;
+                Format::fmt(&result_type, f, context.parameters.clone())?;
+                f.write_str(")")?;
+            }
+            BinaryOperator::SoundsLike => {
+                f.write_str("( SOUNDEX(")?;
+                left.fmt(f, context)?;
+                f.write_str(") = SOUNDEX(")?;
+                right.fmt(f, context)?;
+                f.write_str(") )")?;
+            }
+            BinaryOperator::ShiftLeft |
+            BinaryOperator::ShiftRight => {
+                // Сдвиг вычисляется в bigint, чтобы промежуточное значение не переполняло узкий тип
+                let result_type = data_type.as_primitive()
+                    .ok_or_else(|| GenerateError::new(left.pos, "result of shift should be primitive"))?;
+                f.write_str("CAST(( CAST(")?;
+                left.fmt(f, context)?;
+                f.write_str(" AS bigint)")?;
+                f.write_str(if op == BinaryOperator::ShiftLeft { " * " } else { " / " })?;
+                f.write_str("POWER(CAST(2 AS bigint), ")?;
+                right.fmt(f, context)?;
+                f.write_str(") ) AS ")?;
+                Format::fmt(&result_type, f, context.parameters.clone())?;
+                f.write_str(")")?;
+            }
+            BinaryOperator::XOr => {
+                // Операнды логического исключающего «или» являются предикатами, которые T-SQL не умеет сравнивать
+                f.write_str("( ( ")?;
+                left.fmt(f, context)?;
+                f.write_str(" AND NOT ")?;
+                right.fmt(f, context)?;
+                f.write_str(" ) OR ( NOT ")?;
+                left.fmt(f, context)?;
+                f.write_str(" AND ")?;
+                right.fmt(f, context)?;
+                f.write_str(" ) )")?;
+            }
+            _ => {
+                f.write_str("( ")?;
+                left.fmt(f, context)?;
+                f.write_str(" ")?;
+                f.write_str(op.get_tsql_operator())?;
+                f.write_str(" ")?;
+                right.fmt(f, context)?;
+                f.write_str(" )")?;
+            }
+        }
+        Ok(())
     }
     pub fn fmt(
         &self,
         f: &mut impl fmt::Write,
         context: &mut TSQLFunctionContext,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         match &self.body {
-            ExpressionBody::Literal(lit) => lit.fmt(f),
+            ExpressionBody::Literal(lit) => lit.fmt(f)?,
             ExpressionBody::Variable(var) => {
                 let var_guard = var.read();
-                Expression::fmt_variable_data(f, &*var_guard)
+                Expression::fmt_variable_data(f, &*var_guard)?;
             }
             ExpressionBody::BinaryOperation(left, op, right) => {
+                Expression::fmt_binary_operation(f, &left, *op, &right, &self.data_type, context)?;
+            }
+            ExpressionBody::Between(expr, low, high) => {
                 f.write_str("( ")?;
-                left.fmt(f, context)?;
-                f.write_str(" ")?;
-                f.write_str(op.get_operator())?;
-                f.write_str(" ")?;
-                right.fmt(f, context)?;
-                f.write_str(" )")
+                expr.fmt(f, context)?;
+                f.write_str(" BETWEEN ")?;
+                low.fmt(f, context)?;
+                f.write_str(" AND ")?;
+                high.fmt(f, context)?;
+                f.write_str(" )")?;
             }
             ExpressionBody::PostfixUnaryOperation(op, expr) => {
                 f.write_str("( ")?;
                 f.write_str(op.get_operator())?;
                 f.write_str(" ")?;
                 expr.fmt(f, context)?;
-                f.write_str(" )")
+                f.write_str(" )")?;
             }
             ExpressionBody::PrefixUnaryOperation(op, expr) => {
                 f.write_str("( ")?;
-                f.write_str(op.get_operator())?;
+                f.write_str(op.get_tsql_operator())?;
                 f.write_str(" ")?;
                 expr.fmt(f, context)?;
-                f.write_str(" )")
+                f.write_str(" )")?;
             }
             ExpressionBody::PropertyAccess(expr, path) => {
                 Expression::fmt_property_access(
@@ -1213,7 +2180,7 @@ impl Expression {
                     &expr,
                     path.path.as_path(),
                     context,
-                )
+                )?;
             }
             ExpressionBody::Set(expressions) => {
                 f.write_str("(SELECT ")?;
@@ -1229,7 +2196,7 @@ impl Expression {
                         }
                     }
                 }
-                f.write_str(")")
+                f.write_str(")")?;
             }
             ExpressionBody::FunctionCall(function, arguments) => {
                 let is_primitive = {
@@ -1247,18 +2214,19 @@ impl Expression {
                 if is_primitive {
                     Expression::fmt_function_call(
                         f,
+                        self.pos,
                         function,
                         &arguments,
                         context,
-                    )
+                    )?;
                 } else {
-                    let var = context.add_pre_calc_call(function, &arguments)?;
+                    let var = context.add_pre_calc_call(self.pos, function, &arguments)?;
                     let var_guard = var.read();
-                    Expression::fmt_variable_data(f, &*var_guard)
+                    Expression::fmt_variable_data(f, &*var_guard)?;
                 }
             }
             ExpressionBody::StdFunctionCall(function, arguments) => {
-                write!(f, "{}(", function.name)?;
+                write!(f, "{}(", function.get_sql_name())?;
                 let mut arguments = arguments.iter().peekable();
                 while let Some(argument) = arguments.next() {
                     argument.fmt(f, context)?;
@@ -1266,9 +2234,31 @@ impl Expression {
                         f.write_str(", ")?;
                     }
                 }
-                f.write_str(")")
+                f.write_str(")")?;
+            }
+            ExpressionBody::Cast(expr) => {
+                Expression::fmt_cast(f, &expr, &self.data_type, context)?;
+            }
+            ExpressionBody::Subquery(query) => {
+                let mut text = String::new();
+                query.fmt(CodeFormatter::new(&mut text).root_block(), context)?;
+                f.write_str("(")?;
+                for line in text.lines() {
+                    f.write_char(' ')?;
+                    f.write_str(line.trim())?;
+                }
+                f.write_str(" )")?;
+            }
+            ExpressionBody::Constant(item) => {
+                let item_guard = item.read();
+                let constant = match item_guard.get_constant() {
+                    Some(constant) => constant,
+                    None => return Err(GenerateError::new(self.pos, format!("{} is not a constant", item_guard.get_path()?))),
+                };
+                constant.value.fmt(f)?;
             }
         }
+        Ok(())
     }
 }
 
@@ -63,6 +63,8 @@
         | expression "." property_path
         | expression "(" comma_list(expression) ")"
         | expression module_path "(" comma_list(expression) ")"
+        | "cast" "(" expression "," data_type ")"
+        | "(" selection ")"
         | prefix_unary_operator expression
         | expression postfix_unary_operator
         | expression binary_operator expression
@@ -72,6 +74,10 @@
 
     Правила `property_path` и `module_path` определены в модуле `language::others`.
 
+    Правило `data_type` определено в модуле `language::data_types`.
+
+    Правило `selection` определено в модуле `language::selections`.
+
     Правила `comma_list` и `token` определены в модуле `parser_basics`.
 */
 
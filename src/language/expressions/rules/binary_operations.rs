@@ -189,3 +189,34 @@ pub fn binary_expression<'token, 'source>(
     );
     infix(input, &resolvers[..], atom)
 }
+
+/// Функция, выполняющая разбор отдельного бинарного оператора
+pub fn binary_operator<'token, 'source>(input: &'token [Token<'source>]) -> ParserResult<'token, 'source, BinaryOperator> {
+    alt!(input,
+        logic_or => { |_| BinaryOperator::Or }
+        | logic_xor => { |_| BinaryOperator::XOr }
+        | logic_and => { |_| BinaryOperator::And }
+        | bit_or => { |_| BinaryOperator::BitOr }
+        | bit_xor => { |_| BinaryOperator::BitXOr }
+        | bit_and => { |_| BinaryOperator::BitAnd }
+        | shift_left => { |_| BinaryOperator::ShiftLeft }
+        | shift_right => { |_| BinaryOperator::ShiftRight }
+        | is_in => { |_| BinaryOperator::IsIn }
+        | equals => { |_| BinaryOperator::Equals }
+        | more_than_or_equals => { |_| BinaryOperator::MoreThanOrEquals }
+        | more_than => { |_| BinaryOperator::MoreThan }
+        | less_than_or_equals => { |_| BinaryOperator::LessThanOrEquals }
+        | less_than => { |_| BinaryOperator::LessThan }
+        | like => { |_| BinaryOperator::Like }
+        | sounds_like => { |_| BinaryOperator::SoundsLike }
+        | reg_exp => { |_| BinaryOperator::RegExp }
+        | arithmetic_plus => { |_| BinaryOperator::Plus }
+        | arithmetic_minus => { |_| BinaryOperator::Minus }
+        | arithmetic_pow => { |_| BinaryOperator::Pow }
+        | arithmetic_times => { |_| BinaryOperator::Times }
+        | arithmetic_divide => { |_| BinaryOperator::Divide }
+        | arithmetic_mod => { |_| BinaryOperator::Mod }
+        | arithmetic_div => { |_| BinaryOperator::Div }
+        | interval => { |_| BinaryOperator::Interval }
+    )
+}
@@ -6,13 +6,20 @@ use parser_basics::{
     symbol_position,
 };
 use self::binary_operations::binary_expression;
+pub use self::binary_operations::binary_operator;
 use self::literals::literal;
 use self::others::{
+    cast,
     function_call,
     property_access,
     set,
+    subquery,
 };
 use self::unary_operations::unary_operation;
+pub use self::unary_operations::{
+    postfix_unary_operator,
+    prefix_unary_operator,
+};
 use super::*;
 
 pub mod literals;
@@ -25,7 +32,9 @@ parser_rule!(expression_atom(i) -> ExpressionAST<'source> {
         begin: symbol_position >>
         body: alt!(
             literal => { |x| ExpressionASTBody::Literal(x) } |
+            apply!(cast, expression) |
             apply!(function_call, expression) |
+            subquery |
             apply!(set, expression) |
             identifier => { |x| ExpressionASTBody::Reference(x) }
         ) >>
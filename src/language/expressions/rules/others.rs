@@ -1,10 +1,13 @@
 use language::{
+    data_type,
     module_path,
     property_path,
+    selection,
 };
 use lexeme_scanner::ItemPosition;
 use parser_basics::{
     comma_list,
+    keyword,
     Parser,
     ParserResult,
     symbols,
@@ -59,6 +62,15 @@ pub fn set<'token, 'source>(
         })
 }
 
+parser_rule!(pub subquery(i) -> ExpressionASTBody<'source> {
+    do_parse!(i,
+        apply!(symbols, "(") >>
+        query: selection >>
+        apply!(symbols, ")") >>
+        (ExpressionASTBody::Subquery(Box::new(query)))
+    )
+});
+
 pub fn function_call<'token, 'source>(
     input: &'token [Token<'source>],
     atom: Parser<'token, 'source, ExpressionAST<'source>>,
@@ -69,3 +81,18 @@ pub fn function_call<'token, 'source>(
         (ExpressionASTBody::FunctionCall(name, args))
     )
 }
+
+pub fn cast<'token, 'source>(
+    input: &'token [Token<'source>],
+    atom: Parser<'token, 'source, ExpressionAST<'source>>,
+) -> ParserResult<'token, 'source, ExpressionASTBody<'source>> {
+    do_parse!(input,
+        apply!(keyword, "cast") >>
+        apply!(symbols, "(") >>
+        expr: atom >>
+        apply!(symbols, ",") >>
+        target: data_type >>
+        apply!(symbols, ")") >>
+        (ExpressionASTBody::Cast(Box::new(expr), target))
+    )
+}
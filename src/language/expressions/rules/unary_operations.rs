@@ -153,3 +153,12 @@ pub fn unary_operation<'token, 'source>(
         (result)
     )
 }
+
+/// Функция, выполняющая разбор отдельного постфиксного унарного оператора
+pub fn postfix_unary_operator<'token, 'source>(input: &'token [Token<'source>]) -> ParserResult<'token, 'source, PostfixUnaryOperator> {
+    do_parse!(input,
+        apply!(keyword, "is") >>
+        what: is_what >>
+        (what)
+    )
+}
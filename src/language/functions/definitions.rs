@@ -1,7 +1,9 @@
 use helpers::{
     BlockFormatter,
+    fmt_tsql_object_name,
     CodeFormatter,
     Extractor,
+    Format,
     Generate,
     generate_name,
     NameUniquer,
@@ -10,7 +12,6 @@ use helpers::{
     Resolve,
     SimpleFormatter,
     SyncRef,
-    TSQL,
     TSQLParameters,
 };
 use indexmap::IndexMap;
@@ -31,6 +32,8 @@ use project_analysis::{
     FunctionContext,
     FunctionVariable,
     FunctionVariableScope,
+    GenerateError,
+    GenerateResult,
     Item,
     Module,
     SemanticError,
@@ -89,11 +92,17 @@ impl<'source> Resolve<(SyncRef<Module>, Vec<AttributeAST<'source>>)> for Functio
             let name = identifier.text();
             let position = identifier.item_pos();
             if arguments.contains_key(name) {
-                errors.push(SemanticError::duplicate_definition(
-                    position,
-                    name.to_string(),
-                    SemanticItemType::Variable,
-                ));
+                let first = self.arguments.iter()
+                    .find(|(first, _)| first.text() == name)
+                    .expect("Duplicated argument should have the first definition");
+                errors.push(
+                    SemanticError::duplicate_definition(
+                        position,
+                        name.to_string(),
+                        SemanticItemType::Variable,
+                    )
+                        .with_label(first.0.item_pos(), "first defined here")
+                );
                 continue;
             }
             let data_type = match data_type.resolve(&ctx.0) {
@@ -114,6 +123,9 @@ impl<'source> Resolve<(SyncRef<Module>, Vec<AttributeAST<'source>>)> for Functio
             var.mark_as_argument();
             arguments.insert(name.to_string(), var);
         }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
 
         let (result_pos, result) = match &self.result {
             Some(data_type) => (data_type.pos, data_type.resolve(&ctx.0)?),
@@ -123,12 +135,17 @@ impl<'source> Resolve<(SyncRef<Module>, Vec<AttributeAST<'source>>)> for Functio
         let body = self.body.resolve(&root)?;
 
         if let FunctionBody::Implementation(body) = &body {
-            let body_jumping = body.jumping_check(StatementFlowControlPosition::new(), &result)?;
+            let mut warnings = Vec::new();
+            let body_jumping = body.jumping_check(StatementFlowControlPosition::new(), &result, &mut warnings)?;
             if (body_jumping != StatementFlowControlJumping::AlwaysReturns)
                 && (result != DataType::Void) {
                 return SemanticError::not_all_branches_returns(body.pos)
                     .into_err_vec();
             }
+            for warning in warnings {
+                ctx.0.add_warning(warning);
+            }
+            context.check_unused_variables();
         }
 
         let is_lite_weight = match &body {
@@ -202,7 +219,7 @@ impl FunctionDefinition {
         is_automatic: bool,
         last_comma: bool,
         is_output: bool,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         let mut arguments = primitives.into_iter().peekable();
         while let Some(primitive) = arguments.next() {
             let mut line = f.line()?;
@@ -211,7 +228,8 @@ impl FunctionDefinition {
             } else {
                 write!(line, "[{}]", primitive.path)?;
             }
-            write!(line, " {}", TSQL(&primitive.field_type, context.parameters.clone()))?;
+            line.write(" ")?;
+            Format::fmt(&primitive.field_type, &mut line, context.parameters.clone())?;
             if is_output {
                 line.write(" OUTPUT")?;
             }
@@ -224,7 +242,7 @@ impl FunctionDefinition {
     pub fn fmt_arguments(
         mut f: BlockFormatter<impl fmt::Write>,
         context: &mut TSQLFunctionContext,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         let is_procedure = !context.function.is_lite_weight;
         if !is_procedure {
             f.write_line('(')?;
@@ -237,7 +255,7 @@ impl FunctionDefinition {
                 let mut prefix = PathBuf::new("#");
                 prefix.push(argument_name.as_str());
                 let primitives = argument_guard.data_type()
-                    .expect("Function argument cannot have unknown data-type")
+                    .ok_or_else(|| GenerateError::new(argument_guard.pos(), format!("data type of argument {} is unknown", argument_name)))?
                     .primitives(prefix);
                 FunctionDefinition::fmt_primitives_as_args(
                     sub_f.clone(),
@@ -269,7 +287,7 @@ impl FunctionDefinition {
                     let mut line = sub_f.line()?;
                     line.write(format_args!("@{} ", result_variable_name))?;
                     if let Some(result) = context.function.result.as_primitive() {
-                        line.write(TSQL(&result, context.parameters.clone()))?;
+                        Format::fmt(&result, &mut line, context.parameters.clone())?;
                     } else {
                         line.write("bit")?;
                     }
@@ -284,7 +302,7 @@ impl FunctionDefinition {
             };
             if let Some(primitives) = table {
                 let result_variable_name = context.function.result_var_name.as_ref()
-                    .expect("Table-valued functions should know their return-value name.");
+                    .ok_or_else(|| GenerateError::new(context.function.pos, "table-valued function has no result variable"))?;
                 f.write_line(format_args!(") RETURNS @{} TABLE (", result_variable_name))?;
                 FunctionDefinition::fmt_primitives_as_args(
                     sub_f,
@@ -297,7 +315,9 @@ impl FunctionDefinition {
                 f.write_line(')')?;
             } else {
                 if let Some(result) = context.function.result.as_primitive() {
-                    f.write_line(format_args!(") RETURNS {}", TSQL(&result, context.parameters.clone())))?;
+                    let mut line = f.line()?;
+                    line.write(") RETURNS ")?;
+                    Format::fmt(&result, &mut line, context.parameters.clone())?;
                 } else {
                     f.write_line(") RETURNS bit")?;
                 }
@@ -308,22 +328,21 @@ impl FunctionDefinition {
     pub fn fmt_head(
         mut f: BlockFormatter<impl fmt::Write>,
         context: &mut TSQLFunctionContext,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         let sub_f = f.sub_block();
         // TODO Добавить переменную-результат в контекст (в случае табличных данных на выходе)
         let class = if context.function.is_lite_weight { "FUNCTION" } else { "PROCEDURE" };
-        f.write_line(format_args!("CREATE OR ALTER {} dbo.[{}]", class, context.make_function_name().data))?;
+        f.write_line(format_args!("CREATE OR ALTER {} {}", class, context.make_function_name()?))?;
         FunctionDefinition::fmt_arguments(sub_f.clone(), context)
     }
     pub fn fmt_variable(
         mut f: BlockFormatter<impl fmt::Write>,
         context: &mut TSQLFunctionContext,
         var: &FunctionVariable,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         if var.is_automatic() || var.is_argument() { return Ok(()); }
-        // TODO Адекватный проброс ошибок наверх
         let data_type = var.data_type()
-            .expect("Variable must have determined data-type in generate-time");
+            .ok_or_else(|| GenerateError::new(var.pos(), format!("data type of variable {} is unknown", var.name())))?;
         if let DataType::Array(sub_type) = data_type {
             f.write_line(format_args!("DECLARE @{} TABLE (", var.name()))?;
             TableDefinition::fmt_primitives_as_columns(
@@ -338,7 +357,10 @@ impl FunctionDefinition {
             let mut prefix = PathBuf::new("#");
             prefix.push(var.name());
             for primitive in data_type.primitives(prefix) {
-                f.write_line(format_args!("DECLARE @{} {};", primitive.path, TSQL(&primitive.field_type, context.parameters.clone())))?;
+                let mut line = f.line()?;
+                write!(line, "DECLARE @{} ", primitive.path)?;
+                Format::fmt(&primitive.field_type, &mut line, context.parameters.clone())?;
+                line.write(";")?;
             }
         }
         Ok(())
@@ -346,7 +368,7 @@ impl FunctionDefinition {
     pub fn fmt_body(
         mut f: BlockFormatter<impl fmt::Write>,
         context: &mut TSQLFunctionContext,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         let body = match &context.function.body {
             FunctionBody::Implementation(stmt) => stmt,
             FunctionBody::External => return Ok(()),
@@ -391,13 +413,22 @@ impl FunctionDefinition {
             }
         }
 
-        f.write_line("END")
+        f.write_line("END")?;
+        Ok(())
     }
     pub fn fmt_export(
         &self,
         f: &mut SimpleFormatter,
         module_path: Path,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
+        let sql_name = {
+            let mut sql_name = String::new();
+            let module = self.context.module();
+            let module_guard = module.read();
+            fmt_tsql_object_name(&mut sql_name, module_guard.schema(), module_path, &self.name, true)?;
+            sql_name
+        };
+
         writeln!(f, "export function {}(", self.name)?;
 
         // Arguments
@@ -408,7 +439,7 @@ impl FunctionDefinition {
                 write!(args_f, "{}: ", argument_name)?;
                 let argument_guard = argument.read();
                 let argument_data_type = argument_guard.data_type()
-                    .expect("Variable's data-type should not be unknown at generate-time");
+                    .ok_or_else(|| GenerateError::new(argument_guard.pos(), format!("data type of argument {} is unknown", argument_name)))?;
                 argument_data_type.fmt(&mut args_f.sub_block())?;
                 writeln!(args_f, ",")?;
             }
@@ -428,7 +459,7 @@ impl FunctionDefinition {
             for (argument_name, argument) in &self.arguments {
                 let argument_guard = argument.read();
                 let argument_data_type = argument_guard.data_type()
-                    .expect("Variable's data-type should not be unknown at generate-time");
+                    .ok_or_else(|| GenerateError::new(argument_guard.pos(), format!("data type of argument {} is unknown", argument_name)))?;
                 let mut prefix = PathBuf::new("#");
                 prefix.push(&*argument_name);
                 for primitive in argument_data_type.primitives(prefix) {
@@ -449,7 +480,7 @@ impl FunctionDefinition {
                     while let Some((argument_name, argument)) = arguments.next() {
                         let argument_guard = argument.read();
                         let argument_data_type = argument_guard.data_type()
-                            .expect("Variable's data-type should not be unknown at generate-time");
+                            .ok_or_else(|| GenerateError::new(argument_guard.pos(), format!("data type of argument {} is unknown", argument_name)))?;
 
                         let mut prefix = PathBuf::new("#");
                         prefix.push(&*argument_name);
@@ -471,9 +502,8 @@ impl FunctionDefinition {
                 if self.result.as_primitive().is_some() {
                     writeln!(
                         body_f,
-                        "return _req.query('SELECT dbo.[{module}::{name}]({args}) as result')",
-                        module = module_path,
-                        name = self.name,
+                        "return _req.query('SELECT {name}({args}) as result')",
+                        name = sql_name,
                         args = arguments,
                     )?;
                     let mut then_f = body_f.sub_block();
@@ -486,9 +516,8 @@ impl FunctionDefinition {
                 } else {
                     writeln!(
                         body_f,
-                        "return _req.query('SELECT * FROM dbo.[{module}::{name}]({args})')",
-                        module = module_path,
-                        name = self.name,
+                        "return _req.query('SELECT * FROM {name}({args})')",
+                        name = sql_name,
                         args = arguments,
                     )?;
 
@@ -528,7 +557,7 @@ impl FunctionDefinition {
                 // Binding result of procedure
                 let mut prefix = PathBuf::new("#");
                 let result_variable_name = self.result_var_name.as_ref()
-                    .expect("Procedures should know their return-value name.");
+                    .ok_or_else(|| GenerateError::new(self.pos, "procedure has no result variable"))?;
                 prefix.push(result_variable_name);
                 for primitive in self.result.primitives(prefix) {
                     write!(body_f, "_req.output('{}', _mssql.", primitive.path)?;
@@ -539,9 +568,8 @@ impl FunctionDefinition {
                 // Calling procedure
                 writeln!(
                     body_f,
-                    "return _req.execute('dbo.[{module}::{name}]')",
-                    module = module_path,
-                    name = self.name,
+                    "return _req.execute('{name}')",
+                    name = sql_name,
                 )?;
 
                 // Awaiting result
@@ -565,12 +593,13 @@ impl FunctionDefinition {
         }
 
         /// End of function
-        writeln!(f, "}}")
+        writeln!(f, "}}")?;
+        Ok(())
     }
 }
 
 impl<'a> Generate<TSQLParameters<'a>> for FunctionDefinition {
-    fn fmt(&self, f: BlockFormatter<impl fmt::Write>, parameters: TSQLParameters<'a>) -> fmt::Result {
+    fn fmt(&self, f: BlockFormatter<impl fmt::Write>, parameters: TSQLParameters<'a>) -> GenerateResult {
         let mut context = TSQLFunctionContext::new(self, parameters);
         FunctionDefinition::fmt_head(f.clone(), &mut context)?;
         FunctionDefinition::fmt_body(f, &mut context)
@@ -582,7 +611,7 @@ pub struct TSQLFunctionContext<'a, 'b> {
     pub function: &'a FunctionDefinition,
     pub parameters: TSQLParameters<'b>,
     pub names: NameUniquer,
-    pub function_name: Option<PathBuf>,
+    pub function_name: Option<String>,
     // TODO Учесть пре-вызовы перед каждой вставкой выражения
     pub temp_vars_scope: SyncRef<FunctionVariableScope>,
     pub pre_calc_calls: Vec<String>,
@@ -607,15 +636,15 @@ impl<'a, 'b> TSQLFunctionContext<'a, 'b> {
             pre_calc_calls: Vec::new(),
         }
     }
-    pub fn make_function_name(&mut self) -> Path {
+    pub fn make_function_name(&mut self) -> GenerateResult<&str> {
         if self.function_name.is_none() {
-            let mut path = self.parameters.module_path.into_buf();
-            path.push(self.function.name.as_str());
-            self.function_name = Some(path)
+            let mut name = String::new();
+            self.parameters.fmt_object_name(&mut name, &self.function.name, true)?;
+            self.function_name = Some(name)
         }
         match &self.function_name {
-            Some(function_name) => return function_name.as_path(),
-            None => unreachable!()
+            Some(function_name) => Ok(function_name.as_str()),
+            None => Err(GenerateError::new(self.function.pos, "function name is not generated")),
         }
     }
     pub fn make_result_variable_prefix(&mut self) -> PathBuf {
@@ -625,12 +654,19 @@ impl<'a, 'b> TSQLFunctionContext<'a, 'b> {
         }
         prefix
     }
-    pub fn add_pre_calc_call(&mut self, function: &SyncRef<Item>, arguments: &[Expression]) -> Result<SyncRef<FunctionVariable>, fmt::Error> {
+    pub fn add_temp_variable(&mut self, data_type: DataType) -> GenerateResult<SyncRef<FunctionVariable>> {
+        let name = self.names.add_name("t".into());
+        self.temp_vars_scope.new_variable(self.function.pos, name, Some(data_type))
+            .map_err(|error| GenerateError::from(error).locate(self.function.pos))
+    }
+    pub fn add_pre_calc_call(&mut self, pos: ItemPosition, function: &SyncRef<Item>, arguments: &[Expression]) -> GenerateResult<SyncRef<FunctionVariable>> {
         let result_name = self.names.add_name("t".into());
         let result_data_type = {
             let function_guard = function.read();
-            let inner_function = function_guard.get_function()
-                .expect("Not-functions in function calls should not exist at generate-time");
+            let inner_function = match function_guard.get_function() {
+                Some(inner_function) => inner_function,
+                None => return Err(GenerateError::new(pos, format!("{} is not a function", function_guard.get_path()?))),
+            };
             inner_function.result.clone()
         };
         let var = self.temp_vars_scope.new_variable(
@@ -638,7 +674,7 @@ impl<'a, 'b> TSQLFunctionContext<'a, 'b> {
             result_name,
             Some(result_data_type),
         )
-            .expect("Temp variable should not fail while initializing");
+            .map_err(|error| GenerateError::from(error).locate(pos))?;
         var.make_read_only();
         let mut buffer = String::new();
         {
@@ -652,6 +688,7 @@ impl<'a, 'b> TSQLFunctionContext<'a, 'b> {
             )?;
             Statement::fmt_pre_call(
                 f,
+                pos,
                 Some(&var),
                 function,
                 arguments,
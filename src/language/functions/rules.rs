@@ -24,7 +24,7 @@ parser_rule!(type_of(i) -> DataTypeAST<'source> {
     )
 });
 
-parser_rule!(result_type_of(i) -> DataTypeAST<'source> {
+parser_rule!(pub result_type_of(i) -> DataTypeAST<'source> {
     do_parse!(i,
         apply!(symbols, ":") >>
         data_type: data_type >>
@@ -45,7 +45,7 @@ parser_rule!(argument(i) -> (Identifier<'source>, DataTypeAST<'source>) {
     )
 });
 
-parser_rule!(arguments(i) -> Vec<(Identifier<'source>, DataTypeAST<'source>)> {
+parser_rule!(pub arguments(i) -> Vec<(Identifier<'source>, DataTypeAST<'source>)> {
     do_parse!(i,
         apply!(symbols, "(") >>
         argument_list: apply!(comma_list, argument) >>
@@ -7,6 +7,7 @@ pub use self::other_requests::*;
 pub use self::others::*;
 pub use self::selections::*;
 pub use self::statements::*;
+pub use self::stdlib::*;
 
 pub mod data_types;
 
@@ -26,3 +27,5 @@ pub mod selections;
 
 pub mod statements;
 
+pub mod stdlib;
+
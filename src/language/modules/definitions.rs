@@ -1,12 +1,12 @@
 use helpers::{
-    as_unique_identifier,
     BlockFormatter,
     Extractor,
+    Format,
     Generate,
     PathBuf,
     Resolve,
+    SimpleFormatter,
     SyncRef,
-    TSQL,
     TSQLParameters,
 };
 use indexmap::IndexMap;
@@ -14,25 +14,46 @@ use language::{
     Attribute,
     AttributeAST,
     CompoundDataType,
+    ConstantValue,
     DataType,
     DataTypeAST,
+    Expression,
+    ExpressionAST,
     Field,
-    FieldAST,
     FieldPrimitive,
     find_attribute,
+    find_attribute_ast,
+    FunctionBody,
+    FunctionDefinition,
     FunctionDefinitionAST,
     ItemPath,
+    resolve_struct_fields,
+    Selection,
+    SelectionAST,
+    Statement,
+    StatementAST,
+    StructFieldAST,
+    TSQLFunctionContext,
 };
 use lexeme_scanner::ItemPosition;
-use parser_basics::Identifier;
+use parser_basics::{
+    Identifier,
+    ParserError,
+};
 use project_analysis::{
+    FunctionContext,
+    GenerateResult,
     Item,
     Module,
     SemanticError,
     SemanticItemType,
+    StatementFlowControlPosition,
 };
 use std::{
-    fmt,
+    fmt::{
+        self,
+        Write,
+    },
     sync::Arc,
 };
 
@@ -42,16 +63,17 @@ pub struct DataTypeDefinitionAST<'source> {
     pub body: DataTypeAST<'source>,
 }
 
-impl<'source> Resolve<SyncRef<Module>> for DataTypeDefinitionAST<'source> {
+impl<'source> Resolve<(SyncRef<Module>, Vec<AttributeAST<'source>>)> for DataTypeDefinitionAST<'source> {
     type Result = Item;
     type Error = SemanticError;
-    fn resolve(&self, ctx: &SyncRef<Module>) -> Result<Self::Result, Vec<Self::Error>> {
-        let body = self.body.resolve(ctx)?;
+    fn resolve(&self, ctx: &(SyncRef<Module>, Vec<AttributeAST<'source>>)) -> Result<Self::Result, Vec<Self::Error>> {
+        let body = self.body.resolve(&ctx.0)?;
         let def = DataTypeDefinition {
             name: self.name.to_string(),
             body,
+            nominal: find_attribute_ast(&ctx.1, "nominal").is_some(),
         };
-        Ok(Item::data_type(ctx.clone(), def))
+        Ok(Item::data_type(ctx.0.clone(), def))
     }
 }
 
@@ -59,28 +81,33 @@ impl<'source> Resolve<SyncRef<Module>> for DataTypeDefinitionAST<'source> {
 pub struct DataTypeDefinition {
     pub name: String,
     pub body: DataType,
+    /// Номинальный тип не приводится неявно к другим номинальным типам, даже при совпадении структуры.
+    pub nominal: bool,
+}
+
+impl DataTypeDefinition {
+    pub fn fmt_export(&self, f: &mut SimpleFormatter, name: &str) -> fmt::Result {
+        if !self.nominal {
+            return self.body.fmt_export(f, name);
+        }
+        write!(f, "export type {} = ", name)?;
+        self.body.fmt(f)?;
+        writeln!(f, " & {{ readonly __brand: \"{}\" }};", name)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TableDefinitionAST<'source> {
     pub name: Identifier<'source>,
     pub pos: ItemPosition,
-    pub body: Vec<(Identifier<'source>, FieldAST<'source>)>,
+    pub body: Vec<StructFieldAST<'source>>,
 }
 
 impl<'source> Resolve<SyncRef<Module>> for TableDefinitionAST<'source> {
     type Result = TableDefinition;
     type Error = SemanticError;
     fn resolve(&self, ctx: &SyncRef<Module>) -> Result<Self::Result, Vec<Self::Error>> {
-        let body = match as_unique_identifier(self.body.clone()) {
-            Ok(map) => Arc::new(map.resolve(ctx)?),
-            Err(name) => return SemanticError::duplicate_definition(
-                name.item_pos(),
-                name.text().to_string(),
-                SemanticItemType::Field,
-            )
-                .into_err_vec(),
-        };
+        let body = Arc::new(resolve_struct_fields(&self.body, ctx)?);
         let entity = DataType::Compound(CompoundDataType::Structure(body.clone()));
         let primary_key = {
             let mut primary_key = IndexMap::new();
@@ -121,11 +148,12 @@ impl TableDefinition {
         columns: impl IntoIterator<Item=FieldPrimitive>,
         last_comma: bool,
         postfix: Option<&str>,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         let mut columns = columns.into_iter().peekable();
         while let Some(primitive) = columns.next() {
             let mut line = f.line()?;
-            line.write(format_args!("[{}] {}", primitive.path, TSQL(&primitive.field_type, parameters.clone())))?;
+            write!(line, "[{}] ", primitive.path)?;
+            Format::fmt(&primitive.field_type, &mut line, parameters.clone())?;
             if let Some(postfix) = &postfix {
                 line.write(format_args!(" {}", postfix))?;
             }
@@ -138,14 +166,12 @@ impl TableDefinition {
 }
 
 impl<'a> Generate<TSQLParameters<'a>> for TableDefinition {
-    fn fmt(&self, mut root: BlockFormatter<impl fmt::Write>, parameters: TSQLParameters<'a>) -> fmt::Result {
+    fn fmt(&self, mut root: BlockFormatter<impl fmt::Write>, parameters: TSQLParameters<'a>) -> GenerateResult {
         {
             let mut line = root.line()?;
-            line.write("CREATE TABLE [")?;
-            if !parameters.module_path.data.is_empty() {
-                line.write(format_args!("{}{}", parameters.module_path.data, parameters.module_path.delimiter))?;
-            }
-            line.write(format_args!("{}] (", self.name))?;
+            line.write("CREATE TABLE ")?;
+            parameters.fmt_object_name(&mut line, &self.name, false)?;
+            line.write(" (")?;
         }
 
         let mut columns = root.sub_block();
@@ -181,7 +207,278 @@ impl<'a> Generate<TSQLParameters<'a>> for TableDefinition {
             primary_key.write(")")?;
         }
 
-        root.write_line(")")
+        root.write_line(")")?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerEvent {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl TriggerEvent {
+    pub fn get_sql_name(&self) -> &'static str {
+        match self {
+            TriggerEvent::Insert => "INSERT",
+            TriggerEvent::Update => "UPDATE",
+            TriggerEvent::Delete => "DELETE",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerDefinitionAST<'source> {
+    pub name: Identifier<'source>,
+    pub pos: ItemPosition,
+    pub table: ItemPath,
+    pub events: Vec<TriggerEvent>,
+    pub body: StatementAST<'source>,
+}
+
+impl<'source> Resolve<SyncRef<Module>> for TriggerDefinitionAST<'source> {
+    type Result = TriggerDefinition;
+    type Error = SemanticError;
+    fn resolve(&self, ctx: &SyncRef<Module>) -> Result<Self::Result, Vec<Self::Error>> {
+        let table = match ctx.get_item(self.table.path.as_path(), &mut Vec::new()) {
+            Some(item) => item,
+            None => return ctx.unresolved_item_error(self.table.pos, &self.table.path)
+                .into_err_vec(),
+        };
+        let entity = {
+            let table_guard = table.read();
+            match table_guard.get_table() {
+                Some(def) => def.entity.clone(),
+                None => return SemanticError::expected_item_of_another_type(
+                    self.table.pos,
+                    SemanticItemType::Table,
+                    table_guard.get_type(),
+                )
+                    .into_err_vec(),
+            }
+        };
+        {
+            // T-SQL создаёт триггер в схеме его таблицы
+            let trigger_schema = ctx.read().schema().map(str::to_string);
+            let table_schema = table.read().parent().read().schema().map(str::to_string);
+            if trigger_schema != table_schema {
+                return SemanticError::trigger_schema_mismatch(self.table.pos, trigger_schema, table_schema)
+                    .into_err_vec();
+            }
+        }
+        let mut events = Vec::with_capacity(self.events.len());
+        for event in self.events.iter() {
+            if !events.contains(event) {
+                events.push(*event);
+            }
+        }
+        let context = FunctionContext::new(ctx.clone());
+        let root = context.root();
+        for name in ["inserted", "deleted"].iter() {
+            let var = root.new_variable(self.pos, name.to_string(), Some(DataType::Array(Arc::new(entity.clone()))))
+                .map_err(|error| vec![error])?;
+            var.make_read_only();
+            var.mark_as_automatic();
+        }
+        let body: Statement = self.body.resolve(&root)?;
+        let mut warnings = Vec::new();
+        body.jumping_check(StatementFlowControlPosition::new(), &DataType::Void, &mut warnings)?;
+        for warning in warnings {
+            ctx.add_warning(warning);
+        }
+        context.check_unused_variables();
+        Ok(TriggerDefinition {
+            name: self.name.to_string(),
+            pos: self.pos,
+            table,
+            events,
+            body,
+            context,
+        })
+    }
+}
+
+/**
+    Триггер таблицы. Тело анализируется как тело процедуры,
+    в котором доступны только для чтения массивы `inserted` и `deleted` с типом сущности таблицы.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerDefinition {
+    pub name: String,
+    pub pos: ItemPosition,
+    pub table: SyncRef<Item>,
+    pub events: Vec<TriggerEvent>,
+    pub body: Statement,
+    pub context: SyncRef<FunctionContext>,
+}
+
+impl TriggerDefinition {
+    /// Процедура-заглушка без аргументов, в контексте которой генерируется тело триггера.
+    fn as_function(&self) -> FunctionDefinition {
+        FunctionDefinition {
+            name: self.name.clone(),
+            arguments: IndexMap::new(),
+            result: DataType::Void,
+            result_var_name: None,
+            body: FunctionBody::Implementation(self.body.clone()),
+            context: self.context.clone(),
+            is_lite_weight: false,
+            pos: self.pos,
+        }
+    }
+}
+
+impl<'a> Generate<TSQLParameters<'a>> for TriggerDefinition {
+    fn fmt(&self, mut root: BlockFormatter<impl fmt::Write>, parameters: TSQLParameters<'a>) -> GenerateResult {
+        {
+            let mut line = root.line()?;
+            line.write("CREATE OR ALTER TRIGGER ")?;
+            parameters.fmt_object_name(&mut line, &self.name, false)?;
+        }
+        {
+            let mut head = root.sub_block();
+            {
+                let mut line = head.line()?;
+                line.write("ON ")?;
+                self.table.read().fmt_object_name(&mut line)?;
+            }
+            let mut line = head.line()?;
+            line.write("AFTER ")?;
+            let mut events = self.events.iter().peekable();
+            while let Some(event) = events.next() {
+                line.write(event.get_sql_name())?;
+                if events.peek().is_some() {
+                    line.write(", ")?;
+                }
+            }
+        }
+        let function = self.as_function();
+        let mut context = TSQLFunctionContext::new(&function, parameters);
+        FunctionDefinition::fmt_body(root, &mut context)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewDefinitionAST<'source> {
+    pub name: Identifier<'source>,
+    pub pos: ItemPosition,
+    pub query: SelectionAST<'source>,
+}
+
+impl<'source> Resolve<SyncRef<Module>> for ViewDefinitionAST<'source> {
+    type Result = ViewDefinition;
+    type Error = SemanticError;
+    fn resolve(&self, ctx: &SyncRef<Module>) -> Result<Self::Result, Vec<Self::Error>> {
+        let context = FunctionContext::new(ctx.clone());
+        let query: Selection = self.query.resolve(&context.root())?;
+        let entity = match query.result_data_type.as_array() {
+            Some(entity) => (**entity).clone(),
+            None => query.result_data_type.clone(),
+        };
+        Ok(ViewDefinition {
+            name: self.name.to_string(),
+            pos: self.pos,
+            query,
+            entity,
+            context,
+        })
+    }
+}
+
+/// Представление: именованная выборка, доступная только для чтения.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewDefinition {
+    pub name: String,
+    pub pos: ItemPosition,
+    pub query: Selection,
+    pub entity: DataType,
+    pub context: SyncRef<FunctionContext>,
+}
+
+impl ViewDefinition {
+    /// Функция-заглушка без аргументов, в контексте которой генерируется запрос представления.
+    fn as_function(&self) -> FunctionDefinition {
+        FunctionDefinition {
+            name: self.name.clone(),
+            arguments: IndexMap::new(),
+            result: DataType::Array(Arc::new(self.entity.clone())),
+            result_var_name: None,
+            body: FunctionBody::External,
+            context: self.context.clone(),
+            is_lite_weight: true,
+            pos: self.pos,
+        }
+    }
+}
+
+impl<'a> Generate<TSQLParameters<'a>> for ViewDefinition {
+    fn fmt(&self, mut root: BlockFormatter<impl fmt::Write>, parameters: TSQLParameters<'a>) -> GenerateResult {
+        {
+            let mut line = root.line()?;
+            line.write("CREATE OR ALTER VIEW ")?;
+            parameters.fmt_object_name(&mut line, &self.name, false)?;
+            line.write(" AS")?;
+        }
+        let function = self.as_function();
+        let mut context = TSQLFunctionContext::new(&function, parameters);
+        self.query.fmt(root.sub_block(), &mut context)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstDefinitionAST<'source> {
+    pub name: Identifier<'source>,
+    pub pos: ItemPosition,
+    pub data_type: DataTypeAST<'source>,
+    pub value: ExpressionAST<'source>,
+}
+
+impl<'source> Resolve<SyncRef<Module>> for ConstDefinitionAST<'source> {
+    type Result = ConstDefinition;
+    type Error = SemanticError;
+    fn resolve(&self, ctx: &SyncRef<Module>) -> Result<Self::Result, Vec<Self::Error>> {
+        let data_type = self.data_type.resolve(ctx)?;
+        if data_type.as_primitive().is_none() {
+            return SemanticError::not_allowed_inside(self.data_type.pos, "non-primitive type", "constant")
+                .into_err_vec();
+        }
+        let context = FunctionContext::new(ctx.clone());
+        let value: Expression = self.value.resolve(&context.root())?;
+        if !value.is_constant() {
+            return SemanticError::non_constant_expression(value.pos)
+                .into_err_vec();
+        }
+        value.should_cast_to_type(&data_type)
+            .map_err(|error| vec![error])?;
+        let value = value.fold_constant()
+            .and_then(|folded| folded.cast_to(value.pos, &data_type))
+            .map_err(|error| vec![error])?;
+        Ok(ConstDefinition {
+            name: self.name.to_string(),
+            pos: self.pos,
+            data_type,
+            value,
+        })
+    }
+}
+
+/// Константа модуля. Её значение вычисляется при разрешении и подставляется в место использования в виде литерала.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstDefinition {
+    pub name: String,
+    pub pos: ItemPosition,
+    pub data_type: DataType,
+    pub value: ConstantValue,
+}
+
+impl ConstDefinition {
+    pub fn fmt_export(&self, f: &mut SimpleFormatter) -> GenerateResult {
+        write!(f, "export const {}: ", self.name)?;
+        self.data_type.fmt(f)?;
+        writeln!(f, " = {};", self.value)?;
+        Ok(())
     }
 }
 
@@ -225,6 +522,9 @@ pub struct ExternalItemImport {
 pub enum ModuleDefinitionValueAST<'source> {
     DataType(DataTypeDefinitionAST<'source>),
     Table(TableDefinitionAST<'source>),
+    View(ViewDefinitionAST<'source>),
+    Trigger(TriggerDefinitionAST<'source>),
+    Constant(ConstDefinitionAST<'source>),
     Function(FunctionDefinitionAST<'source>),
     Module(ModuleDefinitionAST<'source>),
     Import(ExternalItemImportAST<'source>),
@@ -248,9 +548,30 @@ impl<'source> ModuleDefinitionValueAST<'source> {
             }
             ModuleDefinitionValueAST::Function(def) => def.name.text(),
             ModuleDefinitionValueAST::Table(def) => def.name.text(),
+            ModuleDefinitionValueAST::View(def) => def.name.text(),
+            ModuleDefinitionValueAST::Trigger(def) => def.name.text(),
+            ModuleDefinitionValueAST::Constant(def) => def.name.text(),
             ModuleDefinitionValueAST::Module(def) => def.name.text(),
         }
     }
+    /// Возвращает положение имени, под которым определение попадает в модуль.
+    pub fn name_pos(&self) -> ItemPosition {
+        match self {
+            ModuleDefinitionValueAST::DataType(def) => def.name.item_pos(),
+            ModuleDefinitionValueAST::Import(def) => {
+                match &def.tail {
+                    ExternalItemTailAST::None | &ExternalItemTailAST::Asterisk => def.path.pos,
+                    ExternalItemTailAST::Alias(alias) => alias.item_pos(),
+                }
+            }
+            ModuleDefinitionValueAST::Function(def) => def.name.item_pos(),
+            ModuleDefinitionValueAST::Table(def) => def.name.item_pos(),
+            ModuleDefinitionValueAST::View(def) => def.name.item_pos(),
+            ModuleDefinitionValueAST::Trigger(def) => def.name.item_pos(),
+            ModuleDefinitionValueAST::Constant(def) => def.name.item_pos(),
+            ModuleDefinitionValueAST::Module(def) => def.name.item_pos(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -262,6 +583,22 @@ pub enum ModuleDefinitionValue {
     Import(ExternalItemImport),
 }
 
+/// Возвращает роли, перечисленные в атрибуте `#[grant(...)]`.
+pub fn resolve_grant_roles(attributes: &[AttributeAST]) -> Result<Vec<String>, SemanticError> {
+    let attribute = match find_attribute_ast(attributes, "grant") {
+        Some(attribute) => attribute,
+        None => return Ok(Vec::new()),
+    };
+    match &attribute.arguments {
+        Some(arguments) if !arguments.is_empty() => Ok(
+            arguments.iter()
+                .map(|role| role.to_string())
+                .collect()
+        ),
+        _ => Err(SemanticError::wrong_arguments_count(attribute.name.item_pos(), 1, 0)),
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ModuleDefinitionItemAST<'source> {
     pub public: bool,
@@ -275,18 +612,31 @@ impl<'source> Resolve<SyncRef<Module>> for ModuleDefinitionItemAST<'source> {
     type Error = SemanticError;
     fn resolve(&self, ctx: &SyncRef<Module>) -> Result<Self::Result, Vec<Self::Error>> {
         let ModuleDefinitionItemAST { public, position, attributes, value } = self;
+        if find_attribute_ast(attributes, "grant").is_some() {
+            match value {
+                ModuleDefinitionValueAST::Function(_) |
+                ModuleDefinitionValueAST::Table(_) |
+                ModuleDefinitionValueAST::View(_) => {
+                    resolve_grant_roles(attributes)
+                        .map_err(|error| vec![error])?;
+                }
+                _ => return SemanticError::not_allowed_here(*position, "grant attribute")
+                    .into_err_vec(),
+            }
+        }
         let item = {
             let value = match value {
                 ModuleDefinitionValueAST::DataType(def) => {
-                    SyncRef::new(def.resolve(ctx)?)
+                    let ctx = (ctx.clone(), attributes.clone());
+                    SyncRef::new(def.resolve(&ctx)?)
                 }
                 ModuleDefinitionValueAST::Import(
                     ExternalItemImportAST { path, tail }
                 ) => {
-                    let mut item_path = path.path.as_path();
+                    let item_path = path.path.as_path();
                     let item = match ctx.resolve_import(item_path) {
                         Some(item) => item,
-                        None => return SemanticError::unresolved_item(path.pos, path.path.clone()).into_err_vec(),
+                        None => return ctx.unresolved_item_error(path.pos, &path.path).into_err_vec(),
                     };
                     if *tail == ExternalItemTailAST::Asterisk {
                         let item = item.read();
@@ -313,6 +663,18 @@ impl<'source> Resolve<SyncRef<Module>> for ModuleDefinitionItemAST<'source> {
                     let def = def.resolve(ctx)?;
                     SyncRef::new(Item::table(ctx.clone(), def))
                 }
+                ModuleDefinitionValueAST::View(def) => {
+                    let def = def.resolve(ctx)?;
+                    SyncRef::new(Item::view(ctx.clone(), def))
+                }
+                ModuleDefinitionValueAST::Trigger(def) => {
+                    let def = def.resolve(ctx)?;
+                    SyncRef::new(Item::trigger(ctx.clone(), def))
+                }
+                ModuleDefinitionValueAST::Constant(def) => {
+                    let def = def.resolve(ctx)?;
+                    SyncRef::new(Item::constant(ctx.clone(), def))
+                }
                 ModuleDefinitionValueAST::Module(_) => {
                     return SemanticError::not_supported_yet(self.position, "file-scoped modules")
                         .into_err_vec();
@@ -321,6 +683,7 @@ impl<'source> Resolve<SyncRef<Module>> for ModuleDefinitionItemAST<'source> {
             ModuleDefinitionItem {
                 public: *public,
                 position: *position,
+                name_position: self.value.name_pos(),
                 attributes: attributes.iter()
                     .map(|attr| attr.into())
                     .collect(),
@@ -328,6 +691,13 @@ impl<'source> Resolve<SyncRef<Module>> for ModuleDefinitionItemAST<'source> {
             }
         };
         let name = value.name();
+        let first_pos = ctx.read().items().get(name)
+            .map(|first| first.name_position);
+        if let Some(first_pos) = first_pos {
+            return SemanticError::duplicate_definition(item.name_position, name.to_string(), SemanticItemType::Definition)
+                .with_label(first_pos, "first defined here")
+                .into_err_vec();
+        }
         ctx.put_item(name, item);
         Ok(())
     }
@@ -337,11 +707,24 @@ impl<'source> Resolve<SyncRef<Module>> for ModuleDefinitionItemAST<'source> {
 pub struct ModuleDefinitionItem {
     pub public: bool,
     pub position: ItemPosition,
+    pub name_position: ItemPosition,
     // TODO Продумать перемещение аттрибутов дефиниции
     pub attributes: Vec<Attribute>,
     pub value: SyncRef<Item>,
 }
 
+/**
+    Модуль-файл: атрибуты модуля, успешно разобранные элементы и ошибки разбора остальных.
+    Имена элементов, которые не удалось разобрать, сохраняются, если их удалось прочитать.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleAST<'source> {
+    pub attributes: Vec<AttributeAST<'source>>,
+    pub items: Vec<ModuleDefinitionItemAST<'source>>,
+    pub errors: ParserError<'source>,
+    pub failed_items: Vec<Identifier<'source>>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ModuleDefinitionAST<'source> {
     pub name: Identifier<'source>,
@@ -6,8 +6,18 @@
     ```md
     data_type_definition = "struct" identifier compound_type
 
+    type_alias_definition = "type" identifier "=" data_type ";"
+
     table_definition = "table" identifier struct_body
 
+    view_definition = "view" identifier "=" selection ";"
+
+    trigger_event = "insert" | "update" | "delete"
+
+    trigger_definition = "trigger" identifier "on" module_path "after" comma_list(trigger_event) block
+
+    const_definition = "const" identifier ":" data_type "=" expression ";"
+
     function_definition_in_module = function_definition
 
     module_definitions = "mod" identifier "{" module "}"
@@ -19,22 +29,34 @@
 
     module_definition_item = attributes ["pub"] (
         | data_type_definition
+        | type_alias_definition
         | table_definition
+        | view_definition
+        | trigger_definition
+        | const_definition
         | function_definition_in_module
         | module_definitions
         | external_item_definition
     )
 
-    module = module_definition_item*
+    module_attribute = "#" "!" "[" identifier ["(" comma_list(identifier) ")"] "]"
+
+    module = module_attribute* module_definition_item*
     ```
 
-    Правила `attributes`, `compound_type, и `struct_body` определены в модуле `language::data_types`.
+    Правила `attributes`, `compound_type`, `data_type` и `struct_body` определены в модуле `language::data_types`.
 
     Правило `module_path` определено в модуле `language::others`.
 
-    Правило `identifier` определено в модуле `parser_basics`.
+    Правила `identifier` и `comma_list` определены в модуле `parser_basics`.
 
     Правило `function_definition` определено в модуле `language::functions`.
+
+    Правило `block` определено в модуле `language::statements`.
+
+    Правило `selection` определено в модуле `language::selections`.
+
+    Правило `expression` определено в модуле `language::expressions`.
 */
 
 pub use self::definitions::*;
@@ -1,19 +1,34 @@
+use helpers::Group;
 use language::{
+    AttributeAST,
     attributes,
+    block,
     compound_type,
+    data_type,
+    expression,
     function_definition,
     module_path,
+    selection,
     struct_body,
 };
 use lexeme_scanner::Token;
-use nom::IResult;
+use nom::{
+    ErrorKind,
+    IResult,
+};
 use parser_basics::{
+    comma_list,
     end_of_input,
+    extract_parser_error,
+    furthest_errors,
     identifier,
+    Identifier,
     item_position,
     keyword,
     none,
+    ParserError,
     ParserResult,
+    skip_to_boundary,
     symbol_position,
     symbols,
 };
@@ -28,6 +43,17 @@ parser_rule!(data_type_definition(i) -> ModuleDefinitionValueAST<'source> {
     )
 });
 
+parser_rule!(type_alias_definition(i) -> ModuleDefinitionValueAST<'source> {
+    do_parse!(i,
+        apply!(keyword, "type") >>
+        name: identifier >>
+        apply!(symbols, "=") >>
+        body: data_type >>
+        apply!(symbols, ";") >>
+        (ModuleDefinitionValueAST::DataType(DataTypeDefinitionAST { name, body }))
+    )
+});
+
 parser_rule!(table_definition(i) -> ModuleDefinitionValueAST<'source> {
     do_parse!(i,
         begin: symbol_position >>
@@ -39,6 +65,57 @@ parser_rule!(table_definition(i) -> ModuleDefinitionValueAST<'source> {
     )
 });
 
+parser_rule!(view_definition(i) -> ModuleDefinitionValueAST<'source> {
+    do_parse!(i,
+        begin: symbol_position >>
+        apply!(keyword, "view") >>
+        name: identifier >>
+        apply!(symbols, "=") >>
+        query: selection >>
+        pos: apply!(item_position, begin) >>
+        apply!(symbols, ";") >>
+        (ModuleDefinitionValueAST::View(ViewDefinitionAST { name, pos, query }))
+    )
+});
+
+parser_rule!(trigger_event(i) -> TriggerEvent {
+    alt!(i,
+        apply!(keyword, "insert") => { |_| TriggerEvent::Insert }
+        | apply!(keyword, "update") => { |_| TriggerEvent::Update }
+        | apply!(keyword, "delete") => { |_| TriggerEvent::Delete }
+    )
+});
+
+parser_rule!(trigger_definition(i) -> ModuleDefinitionValueAST<'source> {
+    do_parse!(i,
+        begin: symbol_position >>
+        apply!(keyword, "trigger") >>
+        name: identifier >>
+        apply!(keyword, "on") >>
+        table: module_path >>
+        apply!(keyword, "after") >>
+        events: apply!(comma_list, trigger_event) >>
+        pos: apply!(item_position, begin) >>
+        body: block >>
+        (ModuleDefinitionValueAST::Trigger(TriggerDefinitionAST { name, pos, table, events, body }))
+    )
+});
+
+parser_rule!(const_definition(i) -> ModuleDefinitionValueAST<'source> {
+    do_parse!(i,
+        begin: symbol_position >>
+        apply!(keyword, "const") >>
+        name: identifier >>
+        apply!(symbols, ":") >>
+        data_type: data_type >>
+        apply!(symbols, "=") >>
+        value: expression >>
+        pos: apply!(item_position, begin) >>
+        apply!(symbols, ";") >>
+        (ModuleDefinitionValueAST::Constant(ConstDefinitionAST { name, pos, data_type, value }))
+    )
+});
+
 parser_rule!(function_definition_in_module(i) -> ModuleDefinitionValueAST<'source> {
     do_parse!(i,
         def: function_definition >>
@@ -51,7 +128,7 @@ parser_rule!(module_definitions(i) -> ModuleDefinitionValueAST<'source> {
         apply!(keyword, "mod") >>
         name: identifier >>
         apply!(symbols, "{") >>
-        items: apply!(module_items, true) >>
+        items: local_module_items >>
         apply!(symbols, "}") >>
         (ModuleDefinitionValueAST::Module(ModuleDefinitionAST { name, items }))
     )
@@ -87,7 +164,11 @@ parser_rule!(module_definition_item(i) -> ModuleDefinitionItemAST<'source> {
         public: opt!(apply!(keyword, "pub")) >>
         value: alt!(
             data_type_definition
+            | type_alias_definition
             | table_definition
+            | view_definition
+            | trigger_definition
+            | const_definition
             | function_definition_in_module
             | module_definitions
             | external_item_definition
@@ -102,34 +183,116 @@ parser_rule!(module_definition_item(i) -> ModuleDefinitionItemAST<'source> {
     )
 });
 
-#[inline]
+// Читает имя элемента модуля, не вникая в его содержимое.
+parser_rule!(item_name(i) -> Identifier<'source> {
+    do_parse!(i,
+        attributes >>
+        opt!(apply!(keyword, "pub")) >>
+        opt!(apply!(keyword, "extern")) >>
+        alt!(
+            apply!(keyword, "struct")
+            | apply!(keyword, "type")
+            | apply!(keyword, "table")
+            | apply!(keyword, "view")
+            | apply!(keyword, "trigger")
+            | apply!(keyword, "const")
+            | apply!(keyword, "fn")
+            | apply!(keyword, "mod")
+        ) >>
+        name: identifier >>
+        (name)
+    )
+});
+
+/**
+    Разбирает элементы модуля до его конца (`}` у локального модуля или конца ввода).
+
+    После ошибки разбора элемента продолжает со следующей границы элемента,
+    возвращая успешно разобранные элементы вместе со всеми найденными ошибками
+    и именами элементов, которые не удалось разобрать.
+*/
 fn module_items<'token, 'source>(
     mut input: &'token [Token<'source>],
     is_local: bool,
-) -> ParserResult<'token, 'source, Vec<ModuleDefinitionItemAST<'source>>> {
+) -> ParserResult<'token, 'source, (Vec<ModuleDefinitionItemAST<'source>>, ParserError<'source>, Vec<Identifier<'source>>)> {
     let mut result = Vec::new();
+    let mut errors = Group::None;
+    let mut failed_items = Vec::new();
     loop {
+        if is_local {
+            if let IResult::Done(_, _) = symbols(input, "}") {
+                return IResult::Done(input, (result, errors, failed_items));
+            }
+        } else {
+            if let IResult::Done(newest_input, _) = end_of_input(input) {
+                return IResult::Done(newest_input, (result, errors, failed_items));
+            }
+        };
+        match symbol_position(input) {
+            IResult::Done(_, _) => {}
+            IResult::Incomplete(n) => return IResult::Incomplete(n),
+            IResult::Error(e) => return IResult::Error(e),
+        }
         match module_definition_item(input) {
             IResult::Done(new_input, output) => {
                 input = new_input;
                 result.push(output);
-                if is_local {
-                    if let IResult::Done(_, _) = symbols(input, "}") {
-                        return IResult::Done(input, result);
-                    }
+            }
+            IResult::Incomplete(n) => return IResult::Incomplete(n),
+            IResult::Error(e) => {
+                errors.append_group(furthest_errors(extract_parser_error(e), input));
+                if let IResult::Done(_, name) = item_name(input) {
+                    failed_items.push(name);
+                }
+                let rest = skip_to_boundary(input, true);
+                input = if rest.len() < input.len() {
+                    rest
+                } else if !is_local && symbols(input, "}").is_done() {
+                    &input[1..]
                 } else {
-                    if let IResult::Done(newest_input, _) = end_of_input(input) {
-                        return IResult::Done(newest_input, result);
-                    }
+                    return IResult::Error(ErrorKind::Custom(errors));
                 };
             }
-            IResult::Incomplete(n) => return IResult::Incomplete(n),
-            IResult::Error(e) => return IResult::Error(e),
         }
     }
 }
 
+parser_rule!(local_module_items(i) -> Vec<ModuleDefinitionItemAST<'source>> {
+    match module_items(i, true) {
+        IResult::Done(i, (items, Group::None, _)) => IResult::Done(i, items),
+        IResult::Done(_, (_, errors, _)) => IResult::Error(ErrorKind::Custom(errors)),
+        IResult::Incomplete(n) => IResult::Incomplete(n),
+        IResult::Error(e) => IResult::Error(e),
+    }
+});
+
+parser_rule!(module_attribute(i) -> AttributeAST<'source> {
+    do_parse!(i,
+        apply!(symbols, "#") >>
+        apply!(symbols, "!") >>
+        apply!(symbols, "[") >>
+        name: identifier >>
+        arguments: opt!(do_parse!(
+            apply!(symbols, "(") >>
+            x: apply!(comma_list, identifier) >>
+            apply!(symbols, ")") >>
+            (x)
+        )) >>
+        apply!(symbols, "]") >>
+        (AttributeAST { name, arguments })
+    )
+});
+
 /// Выполняет разбор грамматики модуля
-pub fn module<'token, 'source>(input: &'token [Token<'source>]) -> ParserResult<'token, 'source, Vec<ModuleDefinitionItemAST<'source>>> {
-    module_items(input, false)
+pub fn module<'token, 'source>(input: &'token [Token<'source>]) -> ParserResult<'token, 'source, ModuleAST<'source>> {
+    do_parse!(input,
+        attributes: many0!(module_attribute) >>
+        items_and_errors: apply!(module_items, false) >>
+        (ModuleAST {
+            attributes,
+            items: items_and_errors.0,
+            errors: items_and_errors.1,
+            failed_items: items_and_errors.2,
+        })
+    )
 }
@@ -25,7 +25,10 @@ use language::{
 use lexeme_scanner::ItemPosition;
 use project_analysis::{
     FunctionVariableScope,
+    GenerateError,
+    GenerateResult,
     InsertSourceContext,
+    Item,
     SemanticError,
 };
 use std::fmt::{
@@ -89,12 +92,11 @@ impl UpdatingAssignment {
         mut f: BlockFormatter<impl fmt::Write>,
         context: &mut TSQLFunctionContext,
         last_comma: bool,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         let var_guard = self.target.var.read();
         let var_data_type = var_guard.data_type()
-            .expect("Variable cannot have unknown data-type at generate time.")
-            .property_type(self.target.pos, self.target.property.as_path())
-            .expect("Property existing should be already checked at generate time.");
+            .ok_or_else(|| GenerateError::new(self.target.pos, format!("data type of variable {} is unknown", var_guard.name())))?
+            .property_type(self.target.pos, self.target.property.as_path())?;
 
         if var_data_type.as_primitive().is_some() {
             let mut line = f.line()?;
@@ -216,11 +218,26 @@ impl Updating {
     pub fn is_lite_weight(&self) -> bool {
         self.source.is_local()
     }
+    /// Собирает представления и функции, к которым обращается запрос.
+    pub fn collect_used_items(&self, items: &mut Vec<SyncRef<Item>>) {
+        self.source.collect_used_items(items);
+        for assignment in self.assignments.iter() {
+            assignment.value.collect_used_items(items);
+        }
+        if let Some(where_clause) = &self.where_clause {
+            where_clause.collect_used_items(items);
+        }
+        if let Some(order_by_clause) = &self.order_by_clause {
+            for item in order_by_clause.iter() {
+                item.expr.collect_used_items(items);
+            }
+        }
+    }
     pub fn fmt(
         &self,
         mut f: BlockFormatter<impl fmt::Write>,
         context: &mut TSQLFunctionContext,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         f.write_line("UPDATE")?;
         let mut sub_f = f.sub_block();
         let sub_sub_f = sub_f.sub_block();
@@ -404,7 +421,7 @@ impl InsertingSource {
     pub fn fmt_target_list(
         target: &[AssignmentTarget],
         line: &mut impl fmt::Write,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         line.write_char('(')?;
 
         let mut properties = target.iter()
@@ -412,9 +429,8 @@ impl InsertingSource {
         while let Some(property) = properties.next() {
             let var_guard = property.var.read();
             let mut primitives = var_guard.data_type()
-                .expect("Variable data-type should be known at generate time")
-                .property_type(ItemPosition::default(), property.property.as_path())
-                .expect("Property existing should be already checked at generate time.")
+                .ok_or_else(|| GenerateError::new(property.pos, format!("data type of variable {} is unknown", var_guard.name())))?
+                .property_type(property.pos, property.property.as_path())?
                 .primitives(property.property.as_path().into_new_buf("#"))
                 .into_iter()
                 .peekable();
@@ -427,13 +443,14 @@ impl InsertingSource {
             }
         }
 
-        line.write_char(')')
+        line.write_char(')')?;
+        Ok(())
     }
     pub fn fmt(
         &self,
         mut f: BlockFormatter<impl fmt::Write>,
         context: &mut TSQLFunctionContext,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         let mut sub_f = f.sub_block();
         match self {
             InsertingSource::ValueLists { properties, lists } => {
@@ -450,9 +467,8 @@ impl InsertingSource {
                         let property = &properties[i];
                         let var_guard = property.var.read();
                         let mut primitives = var_guard.data_type()
-                            .expect("Variable data-type should be known at generate time")
-                            .property_type(ItemPosition::default(), property.property.as_path())
-                            .expect("Property existing should be already checked at generate time.")
+                            .ok_or_else(|| GenerateError::new(property.pos, format!("data type of variable {} is unknown", var_guard.name())))?
+                            .property_type(property.pos, property.property.as_path())?
                             .primitives(PathBuf::new("#"))
                             .into_iter()
                             .peekable();
@@ -484,9 +500,8 @@ impl InsertingSource {
                 while let Some(property) = properties_iter.next() {
                     let var_guard = property.var.read();
                     let mut primitives = var_guard.data_type()
-                        .expect("Variable data-type should be known at generate time")
-                        .property_type(ItemPosition::default(), property.property.as_path())
-                        .expect("Property existing should be already checked at generate time.")
+                        .ok_or_else(|| GenerateError::new(property.pos, format!("data type of variable {} is unknown", var_guard.name())))?
+                        .property_type(property.pos, property.property.as_path())?
                         .primitives(property.property.as_path().into_new_buf("#"))
                         .into_iter()
                         .peekable();
@@ -500,7 +515,8 @@ impl InsertingSource {
                 }
                 f.write_line("FROM (")?;
                 query.fmt(sub_f, context)?;
-                f.write_line(") as t")
+                f.write_line(") as t")?;
+                Ok(())
             }
         }
     }
@@ -544,16 +560,29 @@ impl Inserting {
     pub fn is_lite_weight(&self) -> bool {
         self.target.is_local()
     }
+    /// Собирает представления и функции, к которым обращается запрос.
+    pub fn collect_used_items(&self, items: &mut Vec<SyncRef<Item>>) {
+        self.target.collect_used_items(items);
+        match &self.source {
+            InsertingSource::ValueLists { properties: _, lists } => {
+                for expr in lists.iter().flat_map(|list| list.iter()) {
+                    expr.collect_used_items(items);
+                }
+            }
+            InsertingSource::Selection { properties: _, query } => query.collect_used_items(items),
+        }
+    }
     pub fn fmt(
         &self,
         mut f: BlockFormatter<impl fmt::Write>,
         context: &mut TSQLFunctionContext,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         f.write_line("INSERT INTO")?;
         let sub_f = f.sub_block();
         self.target.fmt(sub_f.clone(), context, false)?;
         self.source.fmt(sub_f, context)?;
-        f.write_line(";")
+        f.write_line(";")?;
+        Ok(())
     }
 }
 
@@ -606,11 +635,23 @@ impl Deleting {
     pub fn is_lite_weight(&self) -> bool {
         self.source.is_local()
     }
+    /// Собирает представления и функции, к которым обращается запрос.
+    pub fn collect_used_items(&self, items: &mut Vec<SyncRef<Item>>) {
+        self.source.collect_used_items(items);
+        if let Some(where_clause) = &self.where_clause {
+            where_clause.collect_used_items(items);
+        }
+        if let Some(order_by_clause) = &self.order_by_clause {
+            for item in order_by_clause.iter() {
+                item.expr.collect_used_items(items);
+            }
+        }
+    }
     pub fn fmt(
         &self,
         mut f: BlockFormatter<impl fmt::Write>,
         context: &mut TSQLFunctionContext,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         match &self.limit_clause {
             Some(limit) => f.write_line(format_args!("DELETE TOP({}) FROM", limit))?,
             None => f.write_line("DELETE FROM")?,
@@ -622,6 +663,7 @@ impl Deleting {
             line.write_str("WHERE ")?;
             where_clause.fmt(&mut line, context)?;
         }
-        sub_f.write_line(';')
+        sub_f.write_line(';')?;
+        Ok(())
     }
 }
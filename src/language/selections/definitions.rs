@@ -20,6 +20,9 @@ use lexeme_scanner::ItemPosition;
 use parser_basics::Identifier;
 use project_analysis::{
     FunctionVariableScope,
+    GenerateError,
+    GenerateResult,
+    Item,
     SemanticError,
 };
 use std::{
@@ -407,11 +410,34 @@ pub struct Selection {
 }
 
 impl Selection {
+    /// Собирает представления и функции, к которым обращается выборка.
+    pub fn collect_used_items(&self, items: &mut Vec<SyncRef<Item>>) {
+        for expression in self.result.iter() {
+            expression.expr.collect_used_items(items);
+        }
+        self.source.collect_used_items(items);
+        if let Some(where_clause) = &self.where_clause {
+            where_clause.collect_used_items(items);
+        }
+        if let Some(group_by_clause) = &self.group_by_clause {
+            for item in group_by_clause.sorting.iter() {
+                item.expr.collect_used_items(items);
+            }
+        }
+        if let Some(having_clause) = &self.having_clause {
+            having_clause.collect_used_items(items);
+        }
+        if let Some(order_by_clause) = &self.order_by_clause {
+            for item in order_by_clause.iter() {
+                item.expr.collect_used_items(items);
+            }
+        }
+    }
     pub fn fmt(
         &self,
         mut f: BlockFormatter<impl fmt::Write>,
         context: &mut TSQLFunctionContext,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         let mut offset_fetch_clause = None;
         {
             let mut line = f.line()?;
@@ -525,13 +551,12 @@ impl Selection {
                 None => &[][..],
             };
             if !order_by_clause.is_empty() || offset_fetch_clause.is_some() {
+                if order_by_clause.is_empty() {
+                    return Err(GenerateError::new(self.pos, "LIMIT clause with offset requires ORDER BY clause"));
+                }
                 {
                     let mut line = f.line()?;
                     line.write_str("ORDER BY ")?;
-                    if order_by_clause.is_empty() {
-                        // TODO Проброс ошибок из генератора
-                        unreachable!("LIMIT clause doesn't make sense without ORDER BY clause");
-                    }
                     let mut items = order_by_clause.iter().peekable();
                     while let Some(expr) = items.next() {
                         expr.expr.fmt(&mut line, context)?;
@@ -14,6 +14,8 @@ use language::{
     DeletingAST,
     Expression,
     ExpressionAST,
+    ExpressionBody,
+    FunctionDefinition,
     Inserting,
     InsertingAST,
     ItemPath,
@@ -28,8 +30,11 @@ use parser_basics::Identifier;
 use project_analysis::{
     FunctionVariable,
     FunctionVariableScope,
+    GenerateError,
+    GenerateResult,
     Item,
     SemanticError,
+    SemanticWarning,
     StatementFlowControlJumping,
     StatementFlowControlPosition,
 };
@@ -102,6 +107,65 @@ impl StatementSource {
             StatementSource::Selection(query) => &query.result_data_type,
         }
     }
+    /// Собирает представления и функции, к которым обращается источник значения.
+    pub fn collect_used_items(&self, items: &mut Vec<SyncRef<Item>>) {
+        match self {
+            StatementSource::Expression(expr) => expr.collect_used_items(items),
+            StatementSource::Selection(query) => query.collect_used_items(items),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DestructuringPatternAST<'source> {
+    Tuple(Vec<Identifier<'source>>),
+    Structure(Vec<Identifier<'source>>),
+}
+
+impl<'source> DestructuringPatternAST<'source> {
+    /// Возвращает пары "имя переменной - путь к свойству источника".
+    pub fn bindings(&self) -> Vec<(Identifier<'source>, String)> {
+        match self {
+            DestructuringPatternAST::Tuple(names) => names.iter()
+                .enumerate()
+                .map(|(i, name)| (*name, format!("component{}", i)))
+                .collect(),
+            DestructuringPatternAST::Structure(names) => names.iter()
+                .map(|name| (*name, name.to_string()))
+                .collect(),
+        }
+    }
+    /**
+        Разрешает шаблон относительно типа источника.
+        Возвращает для каждого имени путь к свойству источника и тип этого свойства.
+    */
+    pub fn check(&self, pos: ItemPosition, source_type: &DataType) -> Result<Vec<(Identifier<'source>, PathBuf, DataType)>, Vec<SemanticError>> {
+        if source_type.as_array().is_some() {
+            return SemanticError::not_allowed_here(pos, "destructuring of array")
+                .into_err_vec();
+        }
+        if let DestructuringPatternAST::Tuple(names) = self {
+            let expected = source_type.field_len();
+            if names.len() != expected {
+                return SemanticError::value_list_with_wrong_length(pos, expected, names.len())
+                    .into_err_vec();
+            }
+        }
+        let mut result = Vec::new();
+        let mut errors = Vec::new();
+        for (name, property) in self.bindings() {
+            let mut path = PathBuf::new("#");
+            path.push(property.as_str());
+            match source_type.property_type(name.item_pos(), path.as_path()) {
+                Ok(data_type) => result.push((name, path, data_type)),
+                Err(error) => errors.push(error),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(result)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -115,6 +179,14 @@ pub enum StatementASTBody<'source> {
         path: ItemPath,
         source: StatementSourceAST<'source>,
     },
+    VariableDestructuring {
+        pattern: DestructuringPatternAST<'source>,
+        source: StatementSourceAST<'source>,
+    },
+    DestructuringAssignment {
+        pattern: DestructuringPatternAST<'source>,
+        source: StatementSourceAST<'source>,
+    },
     Condition {
         condition: ExpressionAST<'source>,
         then_body: Box<StatementAST<'source>>,
@@ -162,7 +234,7 @@ impl<'source> Resolve<SyncRef<FunctionVariableScope>> for StatementAST<'source>
             StatementASTBody::VariableDefinition { name, data_type, default_value } => {
                 let data_type = data_type.resolve(&ctx.context().module())?;
                 let default_value: Option<StatementSource> = default_value.resolve(ctx)?;
-                let var = ctx.new_variable(name.item_pos(), name.to_string(), data_type)?;
+                let var = ctx.declare_variable(name.item_pos(), name.to_string(), data_type)?;
                 match default_value {
                     Some(source) => {
                         let target = AssignmentTarget::new(
@@ -192,6 +264,35 @@ impl<'source> Resolve<SyncRef<FunctionVariableScope>> for StatementAST<'source>
                     source,
                 }
             }
+            StatementASTBody::VariableDestructuring { pattern, source } => {
+                let source: StatementSource = source.resolve(ctx)?;
+                let mut targets = Vec::new();
+                for (name, path, data_type) in pattern.check(self.pos, source.type_of())? {
+                    let var = ctx.declare_variable(name.item_pos(), name.to_string(), Some(data_type))?;
+                    targets.push((AssignmentTarget::new(var, name.item_pos(), PathBuf::empty()), path));
+                }
+                StatementBody::Destructuring {
+                    targets,
+                    source,
+                }
+            }
+            StatementASTBody::DestructuringAssignment { pattern, source } => {
+                let source: StatementSource = source.resolve(ctx)?;
+                let mut targets = Vec::new();
+                for (name, path, data_type) in pattern.check(self.pos, source.type_of())? {
+                    let target = AssignmentTarget::new_in_scope(
+                        ctx,
+                        name.item_pos(),
+                        Path::new(name.text(), "::"),
+                    )?;
+                    target.check_source_type(&data_type)?;
+                    targets.push((target, path));
+                }
+                StatementBody::Destructuring {
+                    targets,
+                    source,
+                }
+            }
             StatementASTBody::Condition { condition, then_body, else_body } => {
                 let mut errors = Vec::new();
                 let condition = condition.accumulative_resolve(ctx, &mut errors);
@@ -324,11 +425,19 @@ impl AssignmentTarget {
         if property.is_empty() {
             let var = self.var.read();
             match var.data_type() {
-                Some(var_type) => source_type.should_cast_to(self.pos, var_type)?,
+                Some(var_type) => source_type.should_cast_to(self.pos, var_type)
+                    .map_err(|error| error.with_label(
+                        var.pos(),
+                        format!("variable {} declared here with type {}", var.name(), var_type),
+                    ))?,
                 None => self.var.replace_data_type(source_type.clone()),
             }
         } else {
-            source_type.should_cast_to(self.pos, &self.var.property_type(self.pos, property)?)?;
+            source_type.should_cast_to(self.pos, &self.var.property_type(self.pos, property)?)
+                .map_err(|error| {
+                    let var = self.var.read();
+                    error.with_label(var.pos(), format!("variable {} declared here", var.name()))
+                })?;
         }
         Ok(())
     }
@@ -341,6 +450,11 @@ pub enum StatementBody {
         target: AssignmentTarget,
         source: StatementSource,
     },
+    /// Присваивание свойств источника нескольким целям. Пути свойств разделены символом `#`.
+    Destructuring {
+        targets: Vec<(AssignmentTarget, PathBuf)>,
+        source: StatementSource,
+    },
     Condition {
         condition: Expression,
         then_body: Box<Statement>,
@@ -380,10 +494,49 @@ pub struct Statement {
 }
 
 impl Statement {
+    /// Собирает представления и функции, к которым обращается высказывание.
+    pub fn collect_used_items(&self, items: &mut Vec<SyncRef<Item>>) {
+        match &self.body {
+            StatementBody::Nothing |
+            StatementBody::CycleControl { operator: _ } => {}
+            StatementBody::VariableAssignment { target: _, source } |
+            StatementBody::Destructuring { targets: _, source } => source.collect_used_items(items),
+            StatementBody::Condition { condition, then_body, else_body } => {
+                condition.collect_used_items(items);
+                then_body.collect_used_items(items);
+                if let Some(else_body) = else_body {
+                    else_body.collect_used_items(items);
+                }
+            }
+            StatementBody::Cycle { cycle_type, body } => {
+                match cycle_type {
+                    CycleType::Simple => {}
+                    CycleType::PrePredicated(predicate) |
+                    CycleType::PostPredicated(predicate) => predicate.collect_used_items(items),
+                }
+                body.collect_used_items(items);
+            }
+            StatementBody::Return { value } => {
+                if let Some(value) = value {
+                    value.collect_used_items(items);
+                }
+            }
+            StatementBody::Block { statements } => {
+                for statement in statements.iter() {
+                    statement.collect_used_items(items);
+                }
+            }
+            StatementBody::DeletingRequest { request } => request.collect_used_items(items),
+            StatementBody::InsertingRequest { request } => request.collect_used_items(items),
+            StatementBody::UpdatingRequest { request } => request.collect_used_items(items),
+        }
+    }
     pub fn is_lite_weight(&self) -> bool {
         match &self.body {
             StatementBody::Nothing => true,
             StatementBody::VariableAssignment { target: _, source: _ } => true,
+            StatementBody::Destructuring { targets: _, source: StatementSource::Expression(expr) } => expr.is_lite_weight(),
+            StatementBody::Destructuring { targets: _, source: StatementSource::Selection(_) } => true,
             StatementBody::Condition { condition, then_body, else_body } => {
                 let is_else_body_lite_weight = match else_body {
                     Some(body) => body.is_lite_weight(),
@@ -417,21 +570,22 @@ impl Statement {
         }
     }
     //TODO Выражения типа, отличного от Void, должны сохранять результат своего выполнения.
-    pub fn jumping_check(&self, pos: StatementFlowControlPosition, return_data_type: &DataType) -> Result<StatementFlowControlJumping, Vec<SemanticError>> {
+    pub fn jumping_check(&self, pos: StatementFlowControlPosition, return_data_type: &DataType, warnings: &mut Vec<SemanticWarning>) -> Result<StatementFlowControlJumping, Vec<SemanticError>> {
         match &self.body {
-            StatementBody::VariableAssignment { target: _, source: _ } => Ok(StatementFlowControlJumping::Nothing),
+            StatementBody::VariableAssignment { target: _, source: _ } |
+            StatementBody::Destructuring { targets: _, source: _ } => Ok(StatementFlowControlJumping::Nothing),
             StatementBody::Condition { condition: _, then_body, else_body } => {
-                match then_body.jumping_check(pos, return_data_type) {
+                match then_body.jumping_check(pos, return_data_type, warnings) {
                     Ok(then_body_jumping) => {
                         let else_body_jumping = match else_body {
-                            Some(else_body) => else_body.jumping_check(pos, return_data_type)?,
+                            Some(else_body) => else_body.jumping_check(pos, return_data_type, warnings)?,
                             None => StatementFlowControlJumping::Nothing,
                         };
                         Ok(then_body_jumping + else_body_jumping)
                     }
                     Err(mut then_body_errors) => {
                         if let Some(else_body) = else_body {
-                            if let Err(mut else_body_errors) = else_body.jumping_check(pos, return_data_type) {
+                            if let Err(mut else_body_errors) = else_body.jumping_check(pos, return_data_type, warnings) {
                                 then_body_errors.append(&mut else_body_errors);
                             }
                         }
@@ -440,7 +594,7 @@ impl Statement {
                 }
             }
             StatementBody::Cycle { cycle_type: _, body } => {
-                body.jumping_check(pos.in_cycle(), return_data_type)
+                body.jumping_check(pos.in_cycle(), return_data_type, warnings)
             }
             StatementBody::CycleControl { operator } => {
                 if !pos.is_in_cycle() {
@@ -464,14 +618,20 @@ impl Statement {
                 let mut errors = Vec::new();
                 let mut statements_iter = statements.iter();
                 while let Some(statement) = statements_iter.next() {
-                    match statement.jumping_check(pos, return_data_type) {
+                    match statement.jumping_check(pos, return_data_type, warnings) {
                         Ok(local_result) => match local_result {
                             StatementFlowControlJumping::AlwaysReturns |
                             StatementFlowControlJumping::AlwaysBreaks |
-                            StatementFlowControlJumping::AlwaysContinues => return match statements_iter.next() {
-                                Some(statement) => SemanticError::unreachable_statement(statement.pos).into_err_vec(),
-                                None => Ok(local_result),
-                            },
+                            StatementFlowControlJumping::AlwaysContinues => {
+                                if let Some(statement) = statements_iter.next() {
+                                    warnings.push(SemanticWarning::unreachable_statement(statement.pos));
+                                }
+                                return if errors.is_empty() {
+                                    Ok(local_result)
+                                } else {
+                                    Err(errors)
+                                };
+                            }
                             local_result => if errors.is_empty() {
                                 result += local_result;
                             }
@@ -503,14 +663,17 @@ impl Statement {
     }
     pub fn fmt_pre_call(
         mut f: BlockFormatter<impl fmt::Write>,
+        pos: ItemPosition,
         target: Option<&SyncRef<FunctionVariable>>,
         function: &SyncRef<Item>,
         arguments: &[Expression],
         context: &mut TSQLFunctionContext,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         let function_guard = function.read();
-        let function_def = function_guard.get_function()
-            .expect("item argument of Statement::fmt_pre_call is not a function!");
+        let function_def = match function_guard.get_function() {
+            Some(function_def) => function_def,
+            None => return Err(GenerateError::new(pos, format!("{} is not a function", function_guard.get_path()?))),
+        };
         let mut sub_f = f.sub_block();
         let mut sub_sub_f = sub_f.sub_block();
         if function_def.is_lite_weight {
@@ -518,11 +681,12 @@ impl Statement {
                 target.read()
             } else {
                 let mut line = sub_sub_f.line()?;
-                Expression::fmt_function_call(&mut line, function, arguments, context)?;
-                return line.write_char(';');
+                Expression::fmt_function_call(&mut line, pos, function, arguments, context)?;
+                line.write_char(';')?;
+                return Ok(());
             };
             let var_data_type = var_guard.data_type()
-                .expect("Variables cannot have unknown data-type at generate-time");
+                .ok_or_else(|| GenerateError::new(var_guard.pos(), format!("data type of variable {} is unknown", var_guard.name())))?;
             let (is_table, primitives) = if let Some(sub_type) = var_data_type.as_array() {
                 (true, sub_type.primitives(PathBuf::new("#")))
             } else {
@@ -557,22 +721,26 @@ impl Statement {
 
             sub_f.write_line("FROM")?;
 
-            Expression::fmt_function_call(&mut sub_sub_f.line()?, function, arguments, context)?;
+            Expression::fmt_function_call(&mut sub_sub_f.line()?, pos, function, arguments, context)?;
 
             sub_f.write_line("AS t;")?;
         } else {
             let var_guard = target.map(|var| var.read());
 
-            f.write_line(format_args!("EXECUTE dbo.[{}]", function_guard.get_path()))?;
+            {
+                let mut line = f.line()?;
+                line.write_str("EXECUTE ")?;
+                function_guard.fmt_object_name(&mut line)?;
+            }
 
             let mut arguments = arguments.into_iter().enumerate().peekable();
             while let Some((i, argument)) = arguments.next() {
                 let (_, argument_target) = function_def.arguments.get_index(i)
-                    .expect("Arguments should not have different count at generate-time.");
+                    .ok_or_else(|| GenerateError::new(argument.pos, "function has less arguments than passed"))?;
                 let argument_target_guard = argument_target.read();
 
                 let argument_target_data_type = argument_target_guard.data_type()
-                    .expect("Arguments cannot have unknown data-type at generate-time");
+                    .ok_or_else(|| GenerateError::new(argument_target_guard.pos(), "data type of argument is unknown"))?;
 
                 let mut primitives = argument_target_data_type.primitives(PathBuf::new("#"))
                     .into_iter()
@@ -613,8 +781,8 @@ impl Statement {
         mut f: BlockFormatter<impl fmt::Write>,
         buffer: &mut String,
         context: &mut TSQLFunctionContext,
-        action: impl Fn(BlockFormatter<String>, &mut TSQLFunctionContext) -> fmt::Result,
-    ) -> fmt::Result {
+        action: impl Fn(BlockFormatter<String>, &mut TSQLFunctionContext) -> GenerateResult,
+    ) -> GenerateResult {
         buffer.clear();
         {
             let buffer_f = {
@@ -639,7 +807,7 @@ impl Statement {
         f: BlockFormatter<impl fmt::Write>,
         context: &mut TSQLFunctionContext,
         statements: &[Statement],
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         let mut buffer = String::new();
         for statement in statements {
             Statement::fmt_something_with_pre_calls(
@@ -655,7 +823,7 @@ impl Statement {
         mut f: BlockFormatter<impl fmt::Write>,
         context: &mut TSQLFunctionContext,
         statements: &[Statement],
-    ) -> fmt::Result
+    ) -> GenerateResult
     {
         f.write_line("BEGIN")?;
         Statement::fmt_block_without_parens(
@@ -663,7 +831,8 @@ impl Statement {
             context,
             statements,
         )?;
-        f.write_line("END")
+        f.write_line("END")?;
+        Ok(())
     }
     pub fn fmt_assignment(
         mut f: BlockFormatter<impl fmt::Write>,
@@ -672,7 +841,7 @@ impl Statement {
         is_can_be_table: bool,
         source: &StatementSource,
         context: &mut TSQLFunctionContext,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         let target_data_type_as_complex = if is_can_be_table {
             match target_data_type.as_table_type(PathBuf::new("#")) {
                 Some(primitives) => Ok(primitives),
@@ -715,20 +884,21 @@ impl Statement {
                             let mut line = sub_f.line()?;
                             expr.fmt(&mut line, context)?;
                         }
-                        source_f.write_line("AS t;")
+                        source_f.write_line("AS t;")?;
                     }
                     StatementSource::Selection(query) => {
-                        source_f.write_line("(")?;
                         if query.result_data_type == *target_data_type {
+                            source_f.write_line("(")?;
                             query.fmt(source_f.sub_block(), context)?;
-                            source_f.write_line(");")
+                            source_f.write_line(");")?;
                         } else {
-                            source_f.write_line(select_wrapper)?;
+                            source_f.write_line(format_args!("{} (", select_wrapper))?;
                             query.fmt(source_f.sub_block(), context)?;
-                            source_f.write_line(") as t;")
+                            source_f.write_line(") as t;")?;
                         }
                     }
                 }
+                Ok(())
             }
             Err(Some(_)) => {
                 match source {
@@ -768,30 +938,136 @@ impl Statement {
                     StatementSource::Expression(expr) => {
                         sub_f.write_line("FROM")?;
                         expr.fmt(&mut sub_sub_f.line()?, context)?;
-                        sub_f.write_line(" as t;")
+                        sub_f.write_line(" as t;")?;
                     }
                     StatementSource::Selection(query) => {
                         sub_f.write_line("FROM (")?;
                         query.fmt(sub_sub_f, context)?;
-                        sub_f.write_line(") as t;")
+                        sub_f.write_line(") as t;")?;
                     }
                 }
+                Ok(())
             }
         }
     }
+    /**
+        Выводит деструктурирующее присваивание.
+        Если свойства источника доступны напрямую (переменная или набор значений), каждое из них присваивается отдельно.
+        Если источник читает одну из целей, он предварительно сохраняется во временную переменную.
+        Иначе все цели заполняются одним запросом `SELECT` из источника.
+    */
+    pub fn fmt_destructuring(
+        mut f: BlockFormatter<impl fmt::Write>,
+        targets: &[(AssignmentTarget, PathBuf)],
+        source: &StatementSource,
+        context: &mut TSQLFunctionContext,
+    ) -> GenerateResult {
+        let mut assignments = Vec::new();
+        for (target, source_path) in targets {
+            let var_guard = target.var.read();
+            let data_type = var_guard.data_type()
+                .ok_or_else(|| GenerateError::new(target.pos, format!("data type of variable {} is unknown", var_guard.name())))?;
+            let data_type = data_type.property_type(target.pos, target.property.as_path())?;
+            let mut target_path = target.property.as_path().into_new_buf("#");
+            target_path.push_front(var_guard.name());
+            let target_primitives = data_type.primitives(target_path);
+            let source_primitives = data_type.primitives(source_path.clone());
+            for (target_primitive, source_primitive) in target_primitives.into_iter().zip(source_primitives) {
+                assignments.push((target_primitive.path, source_primitive.path));
+            }
+        }
+
+        if let StatementSource::Expression(expr) = source {
+            let is_direct = match &expr.body {
+                ExpressionBody::Variable(_) => true,
+                _ => assignments.iter()
+                    .all(|(_, source_path)| expr.get_property(source_path.as_path()).is_some()),
+            };
+            if is_direct {
+                let is_overlapped = targets.len() > 1 && targets.iter()
+                    .any(|(target, _)| expr.uses_variable(&target.var));
+                let expr = if is_overlapped {
+                    let temp_var = context.add_temp_variable(expr.data_type.clone())?;
+                    let temp_var_guard = temp_var.read();
+                    FunctionDefinition::fmt_variable(f.clone(), context, &*temp_var_guard)?;
+                    Statement::fmt_assignment(
+                        f.clone(),
+                        temp_var_guard.name(),
+                        &expr.data_type,
+                        false,
+                        source,
+                        context,
+                    )?;
+                    Expression {
+                        body: ExpressionBody::Variable(temp_var.clone()),
+                        data_type: expr.data_type.clone(),
+                        pos: expr.pos,
+                    }
+                } else {
+                    expr.clone()
+                };
+                for (target_path, source_path) in assignments.iter() {
+                    let sub_expr = expr.get_property_or_wrap(source_path.as_path())
+                        .ok_or_else(|| GenerateError::new(expr.pos, format!("property {} does not exist", source_path)))?;
+                    let mut line = f.line()?;
+                    write!(line, "SET @{} = ", target_path)?;
+                    sub_expr.fmt(&mut line, context)?;
+                    line.write_char(';')?;
+                }
+                return Ok(());
+            }
+        }
+
+        f.write_line("SELECT")?;
+        let mut sub_f = f.sub_block();
+        let mut sub_sub_f = sub_f.sub_block();
+        {
+            let mut assignments = assignments.iter().peekable();
+            while let Some((target_path, source_path)) = assignments.next() {
+                let mut line = sub_sub_f.line()?;
+                write!(line, "@{} = t.[{}]", target_path, source_path)?;
+                if assignments.peek().is_some() {
+                    line.write_char(',')?;
+                }
+            }
+        }
+        match source {
+            StatementSource::Expression(expr) => {
+                sub_f.write_line("FROM")?;
+                {
+                    let mut line = sub_sub_f.line()?;
+                    match &expr.body {
+                        ExpressionBody::FunctionCall(function, arguments) => {
+                            Expression::fmt_function_call(&mut line, expr.pos, function, arguments, context)?;
+                        }
+                        _ => expr.fmt(&mut line, context)?,
+                    }
+                }
+                sub_f.write_line("AS t;")?;
+            }
+            StatementSource::Selection(query) => {
+                sub_f.write_line("FROM (")?;
+                query.fmt(sub_sub_f, context)?;
+                sub_f.write_line(") AS t;")?;
+            }
+        }
+        Ok(())
+    }
     pub fn fmt(
         &self,
         mut f: BlockFormatter<impl fmt::Write>,
         context: &mut TSQLFunctionContext,
-    ) -> fmt::Result {
+    ) -> GenerateResult {
         match &self.body {
             StatementBody::Nothing => Ok(()),
+            StatementBody::Destructuring { targets, source } => {
+                Statement::fmt_destructuring(f, targets, source, context)
+            }
             StatementBody::VariableAssignment { target, source } => {
                 let var_guard = target.var.read();
                 let data_type = var_guard.data_type()
-                    .expect("Variable cannot have undefined data-type at generate-time");
-                let data_type = data_type.property_type(self.pos, target.property.as_path())
-                    .expect("Property path should be checked at semantic-check-time");
+                    .ok_or_else(|| GenerateError::new(target.pos, format!("data type of variable {} is unknown", var_guard.name())))?;
+                let data_type = data_type.property_type(self.pos, target.property.as_path())?;
                 let mut var_path = target.property.as_path().into_new_buf("#");
                 var_path.push_front(var_guard.name());
                 Statement::fmt_assignment(
@@ -841,11 +1117,12 @@ impl Statement {
                 )?;
                 body.fmt(sub_f.clone(), context)?;
                 sub_f.write_line("ELSE BREAK;")?;
-                f.write_line("END")
+                f.write_line("END")?;
+                Ok(())
             }
             StatementBody::Cycle { cycle_type: CycleType::PostPredicated(predicate), body } => {
                 f.write_line("WHILE 1 = 1 BEGIN")?;
-                let mut sub_f = f.sub_block();
+                let sub_f = f.sub_block();
                 body.fmt(sub_f.clone(), context)?;
                 let mut buffer = String::new();
                 Statement::fmt_something_with_pre_calls(
@@ -856,16 +1133,19 @@ impl Statement {
                         let mut predicate_line = buffer_f.line()?;
                         predicate_line.write_str("IF NOT ")?;
                         predicate.fmt(&mut predicate_line, context)?;
-                        predicate_line.write_str(" BREAK;")
+                        predicate_line.write_str(" BREAK;")?;
+                        Ok(())
                     },
                 )?;
-                f.write_line("END")
+                f.write_line("END")?;
+                Ok(())
             }
             StatementBody::CycleControl { operator } => {
                 f.write_line(match operator {
                     CycleControlOperator::Break => "BREAK;",
                     CycleControlOperator::Continue => "CONTINUE;",
-                })
+                })?;
+                Ok(())
             }
             StatementBody::Return { value } => {
                 if let Some(value) = value {
@@ -886,7 +1166,7 @@ impl Statement {
                         }
                     } else {
                         let result_var_name = context.function.result_var_name.as_ref()
-                            .expect("Result-variable name should be known when result is not void");
+                            .ok_or_else(|| GenerateError::new(self.pos, "result variable is not declared"))?;
                         Statement::fmt_assignment(
                             f.clone(),
                             result_var_name,
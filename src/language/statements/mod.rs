@@ -8,6 +8,14 @@
 
     variable_assignment = identifier ":=" expression
 
+    destructuring_pattern =
+        | "(" comma_list(identifier) ")"
+        | "{" comma_list(identifier) "}"
+
+    variable_destructuring = "let" destructuring_pattern ":=" expression
+
+    destructuring_assignment = destructuring_pattern ":=" expression
+
     condition = "if" expression block ["else" block]
 
     simple_cycle = "loop" block
@@ -25,7 +33,9 @@
     block = "{" list(statement, ";") "}"
 
     statement =
+        | variable_destructuring
         | variable_definition
+        | destructuring_assignment
         | variable_assignment
         | condition
         | simple_cycle
@@ -37,7 +47,7 @@
         | expression
     ```
 
-    Правила `list` и `identifier` определены в модуле `parser_basics`.
+    Правила `list`, `comma_list` и `identifier` определены в модуле `parser_basics`.
 
     Правило `expression` определено в модуле `language::expressions`.
 */
@@ -1,3 +1,4 @@
+use helpers::Group;
 use language::{
     data_type,
     deleting,
@@ -8,12 +9,19 @@ use language::{
     updating,
 };
 use lexeme_scanner::Token;
+use nom::{
+    ErrorKind,
+    IResult,
+};
 use parser_basics::{
+    comma_list,
+    extract_parser_error,
+    furthest_errors,
     identifier,
     item_position,
     keyword,
-    list,
     ParserResult,
+    skip_to_boundary,
     symbol_position,
     symbols,
 };
@@ -48,6 +56,48 @@ parser_rule!(variable_definition(i) -> StatementASTBody<'source> {
     )
 });
 
+parser_rule!(destructuring_pattern(i) -> DestructuringPatternAST<'source> {
+    alt!(i,
+        do_parse!(
+            apply!(symbols, "(") >>
+            names: apply!(comma_list, identifier) >>
+            apply!(symbols, ")") >>
+            (DestructuringPatternAST::Tuple(names))
+        )
+        | do_parse!(
+            apply!(symbols, "{") >>
+            names: apply!(comma_list, identifier) >>
+            apply!(symbols, "}") >>
+            (DestructuringPatternAST::Structure(names))
+        )
+    )
+});
+
+parser_rule!(variable_destructuring(i) -> StatementASTBody<'source> {
+    do_parse!(i,
+        apply!(keyword, "let") >>
+        pattern: destructuring_pattern >>
+        apply!(symbols, ":=") >>
+        source: stmt_source >>
+        (StatementASTBody::VariableDestructuring {
+            pattern,
+            source,
+        })
+    )
+});
+
+parser_rule!(destructuring_assignment(i) -> StatementASTBody<'source> {
+    do_parse!(i,
+        pattern: destructuring_pattern >>
+        apply!(symbols, ":=") >>
+        source: stmt_source >>
+        (StatementASTBody::DestructuringAssignment {
+            pattern,
+            source,
+        })
+    )
+});
+
 parser_rule!(variable_assignment(i) -> StatementASTBody<'source> {
     do_parse!(i,
         path: property_path >>
@@ -138,11 +188,63 @@ parser_rule!(return_stmt(i) -> StatementASTBody<'source> {
     )
 });
 
+/**
+    Разбирает высказывания блока, разделённые `;`.
+
+    После ошибки разбора продолжает со следующей границы высказывания (`;` или `}`),
+    чтобы сообщить обо всех ошибках блока сразу.
+*/
+fn statement_list<'token, 'source>(mut input: &'token [Token<'source>]) -> ParserResult<'token, 'source, Vec<StatementAST<'source>>> {
+    let mut result = Vec::new();
+    let mut errors = Group::None;
+    loop {
+        if let IResult::Done(_, _) = symbols(input, "}") {
+            break;
+        }
+        if !symbol_position(input).is_done() {
+            break;
+        }
+        match statement(input) {
+            IResult::Done(new_input, output) => {
+                input = new_input;
+                result.push(output);
+                match symbols(input, ";") {
+                    IResult::Done(new_input, _) => {
+                        input = new_input;
+                        continue;
+                    }
+                    IResult::Incomplete(n) => return IResult::Incomplete(n),
+                    IResult::Error(e) => match symbols(input, "}") {
+                        IResult::Done(_, _) => break,
+                        IResult::Incomplete(n) => return IResult::Incomplete(n),
+                        IResult::Error(f) => {
+                            let mut error = extract_parser_error(e);
+                            error.append_group(extract_parser_error(f));
+                            errors.append_group(error);
+                        }
+                    },
+                }
+            }
+            IResult::Incomplete(n) => return IResult::Incomplete(n),
+            IResult::Error(e) => errors.append_group(furthest_errors(extract_parser_error(e), input)),
+        }
+        let rest = skip_to_boundary(input, false);
+        if rest.len() == input.len() {
+            break;
+        }
+        input = rest;
+    }
+    match errors {
+        Group::None => IResult::Done(input, result),
+        errors => IResult::Error(ErrorKind::Custom(errors)),
+    }
+}
+
 parser_rule!(pub block(i) -> StatementAST<'source> {
     do_parse!(i,
         begin: symbol_position >>
         apply!(symbols, "{") >>
-        statements: apply!(list, statement, prepare!(symbols(";"))) >>
+        statements: statement_list >>
         apply!(symbols, "}") >>
         pos: apply!(item_position, begin) >>
         (StatementAST { body: StatementASTBody::Block { statements }, pos })
@@ -172,7 +274,9 @@ pub fn statement<'token, 'source>(input: &'token [Token<'source>]) -> ParserResu
         begin: symbol_position >>
         body: alt!(
             request
+            | variable_destructuring
             | variable_definition
+            | destructuring_assignment
             | variable_assignment
             | condition
             | simple_cycle
@@ -0,0 +1,163 @@
+use helpers::{
+    Resolve,
+    SyncRef,
+};
+use language::{
+    AttributeAST,
+    BinaryOperator,
+    DataType,
+    DataTypeAST,
+    find_attribute_ast,
+    FunctionBodyAST,
+    FunctionDefinitionAST,
+    PostfixUnaryOperator,
+    PrefixUnaryOperator,
+};
+use lexeme_scanner::ItemPosition;
+use parser_basics::Identifier;
+use project_analysis::{
+    Module,
+    SemanticError,
+    StdLibBinaryOperation,
+    StdLibElement,
+    StdLibFunction,
+    StdLibPostfixUnaryOperation,
+    StdLibPrefixUnaryOperation,
+};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StdLibOperatorSymbol {
+    Binary(BinaryOperator),
+    Prefix(PrefixUnaryOperator),
+    Postfix(PostfixUnaryOperator),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StdLibOperatorAST<'source> {
+    pub operator: StdLibOperatorSymbol,
+    pub arguments: Vec<(Identifier<'source>, DataTypeAST<'source>)>,
+    pub result: DataTypeAST<'source>,
+    pub pos: ItemPosition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StdLibItemASTBody<'source> {
+    Function(FunctionDefinitionAST<'source>),
+    Operator(StdLibOperatorAST<'source>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StdLibItemAST<'source> {
+    pub attributes: Vec<AttributeAST<'source>>,
+    pub body: StdLibItemASTBody<'source>,
+    pub pos: ItemPosition,
+}
+
+fn resolve_arguments<'source>(arguments: &[(Identifier<'source>, DataTypeAST<'source>)], ctx: &SyncRef<Module>) -> Result<Vec<DataType>, Vec<SemanticError>> {
+    let mut result = Vec::with_capacity(arguments.len());
+    let mut errors = Vec::new();
+    for (_, data_type) in arguments.iter() {
+        match data_type.resolve(ctx) {
+            Ok(data_type) => result.push(data_type),
+            Err(mut sub_errors) => errors.append(&mut sub_errors),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(result)
+}
+
+impl<'source> StdLibItemAST<'source> {
+    fn resolve_function(&self, function: &FunctionDefinitionAST<'source>, ctx: &SyncRef<Module>) -> Result<StdLibFunction, Vec<SemanticError>> {
+        if function.body != FunctionBodyAST::External {
+            return SemanticError::not_allowed_inside(function.pos, "function implementation", "standard library")
+                .into_err_vec();
+        }
+        let arguments = resolve_arguments(&function.arguments, ctx)?;
+        let output = match &function.result {
+            Some(data_type) => data_type.resolve(ctx)?,
+            None => DataType::Void,
+        };
+        let mut result = StdLibFunction::new(function.name.to_string())
+            .gets(arguments)
+            .returns(output);
+        if find_attribute_ast(&self.attributes, "aggregate").is_some() {
+            result = result.aggregate();
+        }
+        if find_attribute_ast(&self.attributes, "lite_weight").is_some() {
+            result = result.lite_weight();
+        }
+        if let Some(attribute) = find_attribute_ast(&self.attributes, "sql_name") {
+            match &attribute.arguments {
+                Some(arguments) if arguments.len() == 1 => {
+                    result = result.sql_name(arguments[0].to_string());
+                }
+                Some(arguments) => {
+                    return SemanticError::wrong_arguments_count(attribute.name.item_pos(), 1, arguments.len())
+                        .into_err_vec();
+                }
+                None => {
+                    return SemanticError::wrong_arguments_count(attribute.name.item_pos(), 1, 0)
+                        .into_err_vec();
+                }
+            }
+        }
+        Ok(result)
+    }
+    fn resolve_operator(&self, operator: &StdLibOperatorAST<'source>, ctx: &SyncRef<Module>) -> Result<StdLibElement, Vec<SemanticError>> {
+        let mut arguments = resolve_arguments(&operator.arguments, ctx)?;
+        let output = operator.result.resolve(ctx)?;
+        let expected = match operator.operator {
+            StdLibOperatorSymbol::Binary(BinaryOperator::Plus) |
+            StdLibOperatorSymbol::Binary(BinaryOperator::Minus) if arguments.len() == 1 => 1,
+            StdLibOperatorSymbol::Binary(_) => 2,
+            _ => 1,
+        };
+        if arguments.len() != expected {
+            return SemanticError::wrong_arguments_count(operator.pos, expected, arguments.len())
+                .into_err_vec();
+        }
+        let result = match operator.operator {
+            StdLibOperatorSymbol::Binary(binary_operator) => {
+                let input = arguments.remove(0);
+                if arguments.is_empty() {
+                    let prefix_operator = match binary_operator {
+                        BinaryOperator::Plus => PrefixUnaryOperator::Plus,
+                        _ => PrefixUnaryOperator::Minus,
+                    };
+                    StdLibElement::PrefixUnaryOperation(Arc::new(
+                        StdLibPrefixUnaryOperation::new(prefix_operator, input, output)
+                    ))
+                } else {
+                    let right = arguments.remove(0);
+                    StdLibElement::BinaryOperation(Arc::new(
+                        StdLibBinaryOperation::new(binary_operator, input, right, output)
+                    ))
+                }
+            }
+            StdLibOperatorSymbol::Prefix(prefix_operator) => StdLibElement::PrefixUnaryOperation(Arc::new(
+                StdLibPrefixUnaryOperation::new(prefix_operator, arguments.remove(0), output)
+            )),
+            StdLibOperatorSymbol::Postfix(postfix_operator) => StdLibElement::PostfixUnaryOperation(Arc::new(
+                StdLibPostfixUnaryOperation::new(postfix_operator, arguments.remove(0), output)
+            )),
+        };
+        Ok(result)
+    }
+}
+
+impl<'source> Resolve<SyncRef<Module>> for StdLibItemAST<'source> {
+    type Result = StdLibElement;
+    type Error = SemanticError;
+    fn resolve(&self, ctx: &SyncRef<Module>) -> Result<Self::Result, Vec<Self::Error>> {
+        match &self.body {
+            StdLibItemASTBody::Function(function) => {
+                let function = self.resolve_function(function, ctx)?;
+                Ok(StdLibElement::Function(Arc::new(function)))
+            }
+            StdLibItemASTBody::Operator(operator) => self.resolve_operator(operator, ctx),
+        }
+    }
+}
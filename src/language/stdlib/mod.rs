@@ -0,0 +1,33 @@
+/*!
+    Модуль "Объявления стандартной библиотеки".
+
+    # Грамматика
+
+    ```md
+    stdlib_operator_symbol =
+        | postfix_unary_operator
+        | binary_operator
+        | prefix_unary_operator
+
+    stdlib_operator = "extern" "operator" stdlib_operator_symbol arguments result_type_of
+
+    stdlib_item = attributes (stdlib_operator | function_definition)
+
+    stdlib_items = stdlib_item*
+    ```
+
+    Оператор с одним аргументом считается унарным, с двумя - бинарным.
+    Символы `+` и `-` с одним аргументом означают префиксные операторы.
+
+    Правила `postfix_unary_operator`, `binary_operator` и `prefix_unary_operator` определены в модуле `language::expressions`.
+
+    Правила `arguments`, `result_type_of` и `function_definition` определены в модуле `language::functions`.
+
+    Правило `attributes` определено в модуле `language::data_types`.
+*/
+
+pub use self::definitions::*;
+pub use self::rules::*;
+
+pub mod definitions;
+pub mod rules;
@@ -0,0 +1,71 @@
+use language::{
+    arguments,
+    attributes,
+    binary_operator,
+    function_definition,
+    postfix_unary_operator,
+    prefix_unary_operator,
+    result_type_of,
+};
+use lexeme_scanner::Token;
+use nom::IResult;
+use parser_basics::{
+    end_of_input,
+    item_position,
+    keyword,
+    ParserResult,
+    symbol_position,
+};
+use super::*;
+
+parser_rule!(stdlib_operator_symbol(i) -> StdLibOperatorSymbol {
+    alt!(i,
+        postfix_unary_operator => { |op| StdLibOperatorSymbol::Postfix(op) }
+        | binary_operator => { |op| StdLibOperatorSymbol::Binary(op) }
+        | prefix_unary_operator => { |(op, _)| StdLibOperatorSymbol::Prefix(op) }
+    )
+});
+
+parser_rule!(stdlib_operator(i) -> StdLibOperatorAST<'source> {
+    do_parse!(i,
+        begin: symbol_position >>
+        apply!(keyword, "extern") >>
+        apply!(keyword, "operator") >>
+        operator: stdlib_operator_symbol >>
+        arguments: arguments >>
+        result: result_type_of >>
+        pos: apply!(item_position, begin) >>
+        (StdLibOperatorAST { operator, arguments, result, pos })
+    )
+});
+
+parser_rule!(stdlib_item(i) -> StdLibItemAST<'source> {
+    do_parse!(i,
+        begin: symbol_position >>
+        attributes: attributes >>
+        body: alt!(
+            stdlib_operator => { |x| StdLibItemASTBody::Operator(x) }
+            | function_definition => { |x| StdLibItemASTBody::Function(x) }
+        ) >>
+        pos: apply!(item_position, begin) >>
+        (StdLibItemAST { attributes, body, pos })
+    )
+});
+
+/// Выполняет разбор файла объявлений стандартной библиотеки
+pub fn stdlib_items<'token, 'source>(mut input: &'token [Token<'source>]) -> ParserResult<'token, 'source, Vec<StdLibItemAST<'source>>> {
+    let mut result = Vec::new();
+    loop {
+        if let IResult::Done(new_input, _) = end_of_input(input) {
+            return IResult::Done(new_input, result);
+        }
+        match stdlib_item(input) {
+            IResult::Done(new_input, output) => {
+                input = new_input;
+                result.push(output);
+            }
+            IResult::Incomplete(n) => return IResult::Incomplete(n),
+            IResult::Error(e) => return IResult::Error(e),
+        }
+    }
+}
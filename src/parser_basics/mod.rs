@@ -10,10 +10,7 @@
 */
 
 use lexeme_scanner::Token;
-use nom::{
-    ErrorKind,
-    IResult,
-};
+use nom::IResult;
 pub use self::basic_rules::{
     braced_expression_literal,
     end_of_input,
@@ -41,6 +38,12 @@ pub use self::parser_error::{
     ParserErrorKind,
     ParserErrorTokenInfo,
 };
+pub use self::recovery::{
+    extract_parser_error,
+    furthest_errors,
+    is_error_after_start,
+    skip_to_boundary,
+};
 pub use self::templates::{
     comma_list,
     list,
@@ -58,6 +61,7 @@ pub use self::token::{
 pub mod basic_rules;
 pub mod input;
 pub mod parser_error;
+pub mod recovery;
 #[macro_use]
 pub mod parse_macro;
 #[macro_use]
@@ -77,13 +81,6 @@ pub fn parse<'token, 'source, O>(input: &'token [Token<'source>], parser: Parser
             let kind = ParserErrorKind::unexpected_end();
             Err(new_error_without_pos(kind))
         }
-        IResult::Error(e) => match e {
-            ErrorKind::Custom(e) => Err(e),
-            other => {
-                let msg = other.description();
-                let kind = ParserErrorKind::custom_error(msg);
-                Err(new_error_without_pos(kind))
-            }
-        },
+        IResult::Error(e) => Err(extract_parser_error(e)),
     }
 }
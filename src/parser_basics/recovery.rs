@@ -0,0 +1,115 @@
+//! Примитивы восстановления разбора после ошибки
+
+use helpers::Group;
+use lexeme_scanner::{
+    Token,
+    TokenKind,
+};
+use nom::ErrorKind;
+use super::{
+    new_error_without_pos,
+    ParserError,
+    ParserErrorItem,
+    ParserErrorKind,
+};
+
+/// Извлекает ошибку разбора из ошибки комбинатора парсеров.
+pub fn extract_parser_error<'source>(error: ErrorKind<ParserError<'source>>) -> ParserError<'source> {
+    match error {
+        ErrorKind::Custom(error) => error,
+        other => {
+            let msg = other.description();
+            let kind = ParserErrorKind::custom_error(msg);
+            new_error_without_pos(kind)
+        }
+    }
+}
+
+/// Проверяет, что ошибка найдена дальше первой лексемы `input`, то есть разбор успел продвинуться.
+pub fn is_error_after_start<'source>(error: &ErrorKind<ParserError<'source>>, input: &[Token<'source>]) -> bool {
+    let (error, start) = match (error, input.first()) {
+        (ErrorKind::Custom(error), Some(token)) => (error, token.pos.offset),
+        _ => return false,
+    };
+    error.extract_into_vec()
+        .iter()
+        .any(|item| match &item.pos {
+            Some(pos) => pos.offset > start,
+            None => false,
+        })
+}
+
+/// Проверяет, что лексема является символом `text`.
+fn is_symbol(token: &Token, text: &str) -> bool {
+    token.kind == TokenKind::SymbolGroup && token.text == text
+}
+
+/**
+    Оставляет из ошибок элемента, разбор которого начался с `input`, только относящиеся к делу.
+
+    Альтернативы, отказавшиеся от разбора раньше других, обычно не имеют отношения к тексту
+    и только мешают увидеть настоящую причину, поэтому из ошибок, найденных между соседними `;`,
+    остаются только найденные дальше всех.
+    Если разбор дошёл до блока элемента (до его первой `{`), то и ошибки, найденные до блока, отбрасываются.
+    Так у элемента остаются ошибки всех высказываний, после которых разбор был восстановлен.
+
+    Вызывается только на границах элементов модуля и высказываний.
+*/
+pub fn furthest_errors<'source>(error: ParserError<'source>, input: &[Token<'source>]) -> ParserError<'source> {
+    let offset = |item: &ParserErrorItem| item.pos.as_ref().map(|pos| pos.offset);
+    let mut items: Vec<_> = error.extract_into_vec()
+        .into_iter()
+        .filter(|item| offset(item).is_some())
+        .collect();
+    if items.is_empty() {
+        return error;
+    }
+    let block = input.iter()
+        .find(|token| is_symbol(token, "{"))
+        .map(|token| token.pos.offset);
+    if let Some(block) = block {
+        if items.iter().any(|item| offset(item) > Some(block)) {
+            items.retain(|item| offset(item) > Some(block));
+        }
+    }
+    let delimiters: Vec<_> = input.iter()
+        .filter(|token| is_symbol(token, ";"))
+        .map(|token| token.pos.offset)
+        .collect();
+    let segment = |item: &ParserErrorItem| {
+        delimiters.iter()
+            .take_while(|&&delimiter| Some(delimiter) < offset(item))
+            .count()
+    };
+    let furthest: Vec<_> = items.iter()
+        .filter(|item| !items.iter().any(|other| segment(other) == segment(item) && offset(other) > offset(item)))
+        .cloned()
+        .collect();
+    Group::new(furthest)
+}
+
+/**
+    Пропускает лексемы до ближайшей границы элемента.
+
+    Границей считается `;` вне скобок (она пропускается) и `}`, закрывающая внешний блок (она не пропускается).
+    Если `stop_after_block` установлен, границей также считается `}`, закрывающая блок самого элемента.
+    Конец ввода не пропускается никогда.
+*/
+pub fn skip_to_boundary<'token, 'source>(input: &'token [Token<'source>], stop_after_block: bool) -> &'token [Token<'source>] {
+    let mut depth = 0usize;
+    for (i, token) in input.iter().enumerate() {
+        match token.kind {
+            TokenKind::EndOfInput => return &input[i..],
+            TokenKind::SymbolGroup => match token.text {
+                "{" | "(" | "[" | "#[" => depth += 1,
+                ";" if depth == 0 => return &input[i + 1..],
+                "}" if depth == 0 => return &input[i..],
+                "}" if depth == 1 && stop_after_block => return &input[i + 1..],
+                "}" | ")" | "]" => depth = depth.saturating_sub(1),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    &input[input.len()..]
+}
@@ -5,6 +5,7 @@
 use lexeme_scanner::Token;
 use nom::IResult;
 use super::{
+    is_error_after_start,
     ParserInput,
     ParserResult,
     symbols,
@@ -47,7 +48,12 @@ macro_rules! prepare {
 
     В конце списка `delimiter` является опциональным.
     Возвращает вектор успешно разобранных значений (`Vec<ElementOutput>`).
-    Никогда не возвращает ошибку.
+
+    Список заканчивается на первом элементе, разбор которого не смог начаться.
+    Если же очередной элемент был разобран частично (его ошибка найдена дальше его первой лексемы),
+    возвращается ошибка этого элемента: она точнее указывает на причину,
+    чем ошибка правила, ожидавшего конец списка.
+    Поэтому `element` не должен частично совпадать с тем, что может следовать за списком.
 */
 #[inline]
 pub fn list<
@@ -70,6 +76,12 @@ pub fn list<
                 input = new_input;
                 result.push(element_result);
             }
+            IResult::Error(error) => {
+                if is_error_after_start(&error, input) {
+                    return IResult::Error(error);
+                }
+                break 'parse_cycle;
+            }
             _ => { break 'parse_cycle; }
         }
         match delimiter(input) {
@@ -86,7 +98,7 @@ pub fn list<
     Шаблон "Список через запяную".
     Используется для разбора списка `element`, разделённых `,`.
 
-    Является частным случаем шаблона "Список".
+    Является частным случаем шаблона "Список" и так же возвращает ошибку частично разобранного элемента.
 */
 #[inline]
 pub fn comma_list<
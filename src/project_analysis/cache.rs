@@ -0,0 +1,55 @@
+use helpers::{
+    Path,
+    PathBuf,
+    StableHasher,
+};
+use project_analysis::Text;
+use std::hash::Hasher;
+
+/**
+    Интерфейс модуля, сохранённый после его успешного разрешения.
+    По нему граф зависимостей строится без повторного разбора текста модуля.
+    Отпечаток `fingerprint` учитывает текст модуля и отпечатки всех модулей, от которых он зависит.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleInterface {
+    pub text_hash: u64,
+    pub fingerprint: u64,
+    pub imports: Vec<PathBuf>,
+    /// Имена объектов базы данных, созданных модулем в его схеме. Известны только после разрешения модуля.
+    pub objects: Vec<String>,
+}
+
+/// Источник интерфейсов модулей, разрешённых при предыдущих запусках.
+pub trait ModuleCache {
+    fn get_interface(&self, path: Path) -> Option<&ModuleInterface>;
+}
+
+/// Кеш без записей: все модули разбираются и разрешаются заново.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EmptyModuleCache;
+
+impl ModuleCache for EmptyModuleCache {
+    #[inline]
+    fn get_interface(&self, _path: Path) -> Option<&ModuleInterface> {
+        None
+    }
+}
+
+/// Хеш содержимого текста. Совпадает только в пределах одной сборки компилятора.
+pub fn hash_text(text: &Text) -> u64 {
+    let mut hasher = StableHasher::new();
+    hasher.write(text.text.as_bytes());
+    hasher.finish()
+}
+
+/// Отпечаток модуля: путь модуля, хеш его текста и отпечатки зависимостей в порядке импортов.
+pub fn module_fingerprint(path: Path, text_hash: u64, dependencies: &[u64]) -> u64 {
+    let mut hasher = StableHasher::new();
+    hasher.write_str(path.data);
+    hasher.write_u64(text_hash);
+    for dependency in dependencies {
+        hasher.write_u64(*dependency);
+    }
+    hasher.finish()
+}
@@ -0,0 +1,94 @@
+use lexeme_scanner::ItemPosition;
+
+/// Зависимость модуля от другого модуля, возникающая из-за импорта.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dependency {
+    pub module: usize,
+    pub pos: ItemPosition,
+}
+
+/**
+    Цикл импортов.
+    `modules` содержит путь по циклу, начинающийся и заканчивающийся одним и тем же модулем,
+    а `pos` указывает на импорт в модуле `importer`, замыкающий цикл.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyCycle {
+    pub importer: usize,
+    pub pos: ItemPosition,
+    pub modules: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VisitState {
+    NotVisited,
+    InProgress,
+    Done,
+}
+
+/// Граф зависимостей модулей проекта. Модули обозначаются индексами.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyGraph {
+    edges: Vec<Vec<Dependency>>,
+}
+
+impl DependencyGraph {
+    #[inline]
+    pub fn new(size: usize) -> Self {
+        DependencyGraph { edges: vec![Vec::new(); size] }
+    }
+    #[inline]
+    pub fn add_dependency(&mut self, module: usize, dependency: usize, pos: ItemPosition) {
+        self.edges[module].push(Dependency { module: dependency, pos });
+    }
+    #[inline]
+    pub fn dependencies(&self, module: usize) -> &[Dependency] {
+        &self.edges[module]
+    }
+    /**
+        Упорядочивает модули так, что зависимости модуля идут раньше него самого.
+        Модули обходятся в порядке `roots`, что делает результат независимым от порядка добавления модулей.
+        Найденные циклы возвращаются вместе с порядком; модули цикла в порядке тоже присутствуют.
+    */
+    pub fn sort(&self, roots: &[usize]) -> (Vec<usize>, Vec<DependencyCycle>) {
+        let mut states = vec![VisitState::NotVisited; self.edges.len()];
+        let mut stack = Vec::new();
+        let mut order = Vec::with_capacity(self.edges.len());
+        let mut cycles = Vec::new();
+        for &root in roots {
+            self.visit(root, &mut states, &mut stack, &mut order, &mut cycles);
+        }
+        (order, cycles)
+    }
+    fn visit(
+        &self,
+        module: usize,
+        states: &mut [VisitState],
+        stack: &mut Vec<usize>,
+        order: &mut Vec<usize>,
+        cycles: &mut Vec<DependencyCycle>,
+    ) {
+        if states[module] != VisitState::NotVisited {
+            return;
+        }
+        states[module] = VisitState::InProgress;
+        stack.push(module);
+        for dependency in self.edges[module].iter() {
+            match states[dependency.module] {
+                VisitState::NotVisited => self.visit(dependency.module, states, stack, order, cycles),
+                VisitState::InProgress => {
+                    let begin = stack.iter()
+                        .position(|&item| item == dependency.module)
+                        .unwrap_or(0);
+                    let mut modules = stack[begin..].to_vec();
+                    modules.push(dependency.module);
+                    cycles.push(DependencyCycle { importer: module, pos: dependency.pos, modules });
+                }
+                VisitState::Done => {}
+            }
+        }
+        stack.pop();
+        states[module] = VisitState::Done;
+        order.push(module);
+    }
+}
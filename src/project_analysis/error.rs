@@ -2,12 +2,17 @@ use helpers::{
     decimal_unsigned_length,
     IntoStatic,
     PathBuf,
+    similar_names,
+    write_json_optional_string,
+    write_json_string,
+    write_labelled_pointer_line,
     write_line_numbers_columns_row,
     write_pointer_line,
 };
 use language::{
     BinaryOperator,
     DataType,
+    JoinType,
     PostfixUnaryOperator,
     PrefixUnaryOperator,
 };
@@ -74,6 +79,10 @@ pub enum SemanticErrorKind {
         source: DataType,
         target: DataType,
     },
+    CannotConvertType {
+        source: DataType,
+        target: DataType,
+    },
     BinaryOperationCannotBePerformed {
         operator: BinaryOperator,
         left: DataType,
@@ -101,7 +110,6 @@ pub enum SemanticErrorKind {
     CannotModifyReadOnlyVariable {
         name: String,
     },
-    UnreachableStatement,
     NotAllBranchesReturns,
     CannotDoWithDataSource {
         action: &'static str,
@@ -114,6 +122,27 @@ pub enum SemanticErrorKind {
         expected: usize,
         got: usize,
     },
+    AmbiguousOverload {
+        subject: String,
+        arguments: Vec<DataType>,
+        candidates: Vec<String>,
+    },
+    JoinConditionRequired {
+        join_type: JoinType,
+    },
+    NonConstantExpression,
+    ImportCycle {
+        cycle: Vec<PathBuf>,
+    },
+    ConstantOutOfRange {
+        value: String,
+        data_type: DataType,
+    },
+    DivisionByZero,
+    TriggerSchemaMismatch {
+        trigger_schema: Option<String>,
+        table_schema: Option<String>,
+    },
 }
 
 impl Default for SemanticErrorKind {
@@ -122,6 +151,48 @@ impl Default for SemanticErrorKind {
     }
 }
 
+impl SemanticErrorKind {
+    /// Стабильный код ошибки, по которому её можно опознать вне компилятора.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SemanticErrorKind::Empty => "E0001",
+            SemanticErrorKind::UnresolvedItem { .. } => "E0002",
+            SemanticErrorKind::SuperOfRoot => "E0003",
+            SemanticErrorKind::ItemNameNotSpecified => "E0004",
+            SemanticErrorKind::DuplicateDefinition { .. } => "E0005",
+            SemanticErrorKind::ScannerError { .. } => "E0006",
+            SemanticErrorKind::ParserError { .. } => "E0007",
+            SemanticErrorKind::ExpectedItemOfAnotherType { .. } => "E0008",
+            SemanticErrorKind::EmptyPrimaryKey => "E0009",
+            SemanticErrorKind::NotInScope { .. } => "E0010",
+            SemanticErrorKind::WrongProperty { .. } => "E0011",
+            SemanticErrorKind::VariableTypeIsUnknown { .. } => "E0012",
+            SemanticErrorKind::NotSupportedYet { .. } => "E0013",
+            SemanticErrorKind::WrongArgumentsCount { .. } => "E0014",
+            SemanticErrorKind::CannotCastType { .. } => "E0015",
+            SemanticErrorKind::CannotConvertType { .. } => "E0016",
+            SemanticErrorKind::BinaryOperationCannotBePerformed { .. } => "E0017",
+            SemanticErrorKind::PostfixUnaryOperationCannotBePerformed { .. } => "E0018",
+            SemanticErrorKind::PrefixUnaryOperationCannotBePerformed { .. } => "E0019",
+            SemanticErrorKind::NotAllowedHere { .. } => "E0020",
+            SemanticErrorKind::NotAllowedInside { .. } => "E0021",
+            SemanticErrorKind::ExpectedExpressionOfAnotherType { .. } => "E0022",
+            SemanticErrorKind::CannotModifyReadOnlyVariable { .. } => "E0023",
+            SemanticErrorKind::NotAllBranchesReturns => "E0024",
+            SemanticErrorKind::CannotDoWithDataSource { .. } => "E0025",
+            SemanticErrorKind::ValueListWithWrongLength { .. } => "E0026",
+            SemanticErrorKind::SelectWithWrongColumnCount { .. } => "E0027",
+            SemanticErrorKind::AmbiguousOverload { .. } => "E0028",
+            SemanticErrorKind::JoinConditionRequired { .. } => "E0029",
+            SemanticErrorKind::NonConstantExpression => "E0030",
+            SemanticErrorKind::ImportCycle { .. } => "E0031",
+            SemanticErrorKind::ConstantOutOfRange { .. } => "E0032",
+            SemanticErrorKind::DivisionByZero => "E0033",
+            SemanticErrorKind::TriggerSchemaMismatch { .. } => "E0034",
+        }
+    }
+}
+
 impl fmt::Display for SemanticErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -140,6 +211,7 @@ impl fmt::Display for SemanticErrorKind {
             SemanticErrorKind::NotSupportedYet { feature } => write!(f, "{} is not supported yet", feature),
             SemanticErrorKind::WrongArgumentsCount { expected, got } => write!(f, "expected {} arguments, got {}", expected, got),
             SemanticErrorKind::CannotCastType { source, target } => write!(f, "cannot cast type {} to {}", source, target),
+            SemanticErrorKind::CannotConvertType { source, target } => write!(f, "cannot convert type {} to {}", source, target),
             SemanticErrorKind::BinaryOperationCannotBePerformed { operator, left, right } => write!(f, "operation \"{}\" cannot be performed on {} and {}", operator, left, right),
             SemanticErrorKind::PostfixUnaryOperationCannotBePerformed { operator, input } => write!(f, "operation \"{}\" cannot be performed on {}", operator, input),
             SemanticErrorKind::PrefixUnaryOperationCannotBePerformed { operator, input } => write!(f, "operation \"{}\" cannot be performed on {}", operator, input),
@@ -147,46 +219,86 @@ impl fmt::Display for SemanticErrorKind {
             SemanticErrorKind::NotAllowedInside { feature, output_feature } => write!(f, "{} is not allowed inside {}", feature, output_feature),
             SemanticErrorKind::ExpectedExpressionOfAnotherType { expected, got } => write!(f, "expected expression of type {}, got {}", expected, got),
             SemanticErrorKind::CannotModifyReadOnlyVariable { name } => write!(f, "can't modify read-only variable {}", name),
-            SemanticErrorKind::UnreachableStatement => write!(f, "unreachable statement"),
             SemanticErrorKind::NotAllBranchesReturns => write!(f, "not all branches of code return a value"),
             SemanticErrorKind::CannotDoWithDataSource { action } => write!(f, "can't {} this data-source", action),
             SemanticErrorKind::ValueListWithWrongLength { expected, got } => write!(f, "expected value list of {} elements, got {}", expected, got),
             SemanticErrorKind::SelectWithWrongColumnCount { expected, got } => write!(f, "expected selection with {} columns, got with {}", expected, got),
+            SemanticErrorKind::AmbiguousOverload { subject, arguments, candidates } => {
+                write!(f, "{} is ambiguous for arguments (", subject)?;
+                let mut arguments = arguments.iter().peekable();
+                while let Some(argument) = arguments.next() {
+                    write!(f, "{}", argument)?;
+                    if arguments.peek().is_some() {
+                        f.write_str(", ")?;
+                    }
+                }
+                write!(f, "), candidates are: {}", candidates.join("; "))
+            }
+            SemanticErrorKind::JoinConditionRequired { join_type } => write!(f, "{} requires \"on\" condition", join_type),
+            SemanticErrorKind::NonConstantExpression => write!(f, "expected constant expression"),
+            SemanticErrorKind::ImportCycle { cycle } => {
+                f.write_str("import cycle detected: ")?;
+                let mut modules = cycle.iter().peekable();
+                while let Some(module) = modules.next() {
+                    write!(f, "{}", module)?;
+                    if modules.peek().is_some() {
+                        f.write_str(" -> ")?;
+                    }
+                }
+                Ok(())
+            }
+            SemanticErrorKind::ConstantOutOfRange { value, data_type } => write!(f, "constant value {} is out of range of type {}", value, data_type),
+            SemanticErrorKind::DivisionByZero => write!(f, "division by zero in constant expression"),
+            SemanticErrorKind::TriggerSchemaMismatch { trigger_schema, table_schema } => write!(
+                f,
+                "trigger should be in schema {} of its table, but it is in schema {}",
+                table_schema.as_ref().map(String::as_str).unwrap_or("dbo"),
+                trigger_schema.as_ref().map(String::as_str).unwrap_or("dbo"),
+            ),
         }
     }
 }
 
+/// Дополнительный участок исходного текста, поясняющий ошибку, например место первого определения.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemanticLabel {
+    pub pos: ItemPosition,
+    pub message: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct SemanticError {
     pub pos: ItemPosition,
     pub kind: SemanticErrorKind,
     pub text: Option<Arc<Text>>,
+    pub help: Option<String>,
+    pub labels: Vec<SemanticLabel>,
 }
 
 impl SemanticError {
     #[inline]
     pub fn new(pos: ItemPosition, kind: SemanticErrorKind) -> Self {
-        SemanticError { pos, kind, text: None }
+        SemanticError { pos, kind, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn empty(pos: ItemPosition) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::Empty, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::Empty, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn unresolved_item(pos: ItemPosition, path: PathBuf) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::UnresolvedItem { path }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::UnresolvedItem { path }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn super_of_root(pos: ItemPosition) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::SuperOfRoot, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::SuperOfRoot, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn item_name_not_specified(pos: ItemPosition) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::ItemNameNotSpecified, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::ItemNameNotSpecified, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn duplicate_definition(pos: ItemPosition, name: String, item_type: SemanticItemType) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::DuplicateDefinition { name, item_type }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::DuplicateDefinition { name, item_type }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn scanner_error(error: ScannerError) -> Self {
@@ -195,6 +307,8 @@ impl SemanticError {
             pos: pos.into_item_pos(" "),
             kind: SemanticErrorKind::ScannerError { kind },
             text: None,
+            help: None,
+            labels: Vec::new(),
         }
     }
     #[inline]
@@ -207,87 +321,117 @@ impl SemanticError {
             ),
             kind: SemanticErrorKind::ParserError { kind: kind.into_static() },
             text: None,
+            help: None,
+            labels: Vec::new(),
         }
     }
     #[inline]
     pub fn expected_item_of_another_type(pos: ItemPosition, expected: SemanticItemType, got: SemanticItemType) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::ExpectedItemOfAnotherType { expected, got }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::ExpectedItemOfAnotherType { expected, got }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn empty_primary_key(pos: ItemPosition) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::EmptyPrimaryKey, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::EmptyPrimaryKey, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn not_in_scope(pos: ItemPosition, name: String) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::NotInScope { name }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::NotInScope { name }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn wrong_property(pos: ItemPosition, property: String) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::WrongProperty { property }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::WrongProperty { property }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn variable_type_is_unknown(pos: ItemPosition, name: String) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::VariableTypeIsUnknown { name }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::VariableTypeIsUnknown { name }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn not_supported_yet(pos: ItemPosition, feature: &'static str) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::NotSupportedYet { feature }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::NotSupportedYet { feature }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn wrong_arguments_count(pos: ItemPosition, expected: usize, got: usize) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::WrongArgumentsCount { expected, got }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::WrongArgumentsCount { expected, got }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn cannot_cast_type(pos: ItemPosition, source: DataType, target: DataType) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::CannotCastType { source, target }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::CannotCastType { source, target }, text: None, help: None, labels: Vec::new() }
+    }
+    #[inline]
+    pub fn cannot_convert_type(pos: ItemPosition, source: DataType, target: DataType) -> Self {
+        SemanticError { pos, kind: SemanticErrorKind::CannotConvertType { source, target }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn binary_operation_cannot_be_performed(pos: ItemPosition, operator: BinaryOperator, left: DataType, right: DataType) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::BinaryOperationCannotBePerformed { operator, left, right }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::BinaryOperationCannotBePerformed { operator, left, right }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn postfix_unary_operation_cannot_be_performed(pos: ItemPosition, operator: PostfixUnaryOperator, input: DataType) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::PostfixUnaryOperationCannotBePerformed { operator, input }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::PostfixUnaryOperationCannotBePerformed { operator, input }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn prefix_unary_operation_cannot_be_performed(pos: ItemPosition, operator: PrefixUnaryOperator, input: DataType) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::PrefixUnaryOperationCannotBePerformed { operator, input }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::PrefixUnaryOperationCannotBePerformed { operator, input }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn not_allowed_here(pos: ItemPosition, feature: &'static str) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::NotAllowedHere { feature }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::NotAllowedHere { feature }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn not_allowed_inside(pos: ItemPosition, feature: &'static str, output_feature: &'static str) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::NotAllowedInside { feature, output_feature }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::NotAllowedInside { feature, output_feature }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn expected_expression_of_another_type(pos: ItemPosition, expected: DataType, got: DataType) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::ExpectedExpressionOfAnotherType { expected, got }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::ExpectedExpressionOfAnotherType { expected, got }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn cannot_modify_readonly_variable(pos: ItemPosition, name: String) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::CannotModifyReadOnlyVariable { name }, text: None }
-    }
-    #[inline]
-    pub fn unreachable_statement(pos: ItemPosition) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::UnreachableStatement, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::CannotModifyReadOnlyVariable { name }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn not_all_branches_returns(pos: ItemPosition) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::NotAllBranchesReturns, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::NotAllBranchesReturns, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn cannot_do_with_datasource(pos: ItemPosition, action: &'static str) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::CannotDoWithDataSource { action }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::CannotDoWithDataSource { action }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn value_list_with_wrong_length(pos: ItemPosition, expected: usize, got: usize) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::ValueListWithWrongLength { expected, got }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::ValueListWithWrongLength { expected, got }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn select_with_wrong_column_count(pos: ItemPosition, expected: usize, got: usize) -> Self {
-        SemanticError { pos, kind: SemanticErrorKind::SelectWithWrongColumnCount { expected, got }, text: None }
+        SemanticError { pos, kind: SemanticErrorKind::SelectWithWrongColumnCount { expected, got }, text: None, help: None, labels: Vec::new() }
+    }
+    #[inline]
+    pub fn ambiguous_overload(pos: ItemPosition, subject: String, arguments: Vec<DataType>, candidates: Vec<String>) -> Self {
+        SemanticError { pos, kind: SemanticErrorKind::AmbiguousOverload { subject, arguments, candidates }, text: None, help: None, labels: Vec::new() }
+    }
+    #[inline]
+    pub fn join_condition_required(pos: ItemPosition, join_type: JoinType) -> Self {
+        SemanticError { pos, kind: SemanticErrorKind::JoinConditionRequired { join_type }, text: None, help: None, labels: Vec::new() }
+    }
+    #[inline]
+    pub fn non_constant_expression(pos: ItemPosition) -> Self {
+        SemanticError { pos, kind: SemanticErrorKind::NonConstantExpression, text: None, help: None, labels: Vec::new() }
+    }
+    #[inline]
+    pub fn import_cycle(pos: ItemPosition, cycle: Vec<PathBuf>) -> Self {
+        SemanticError { pos, kind: SemanticErrorKind::ImportCycle { cycle }, text: None, help: None, labels: Vec::new() }
+    }
+    #[inline]
+    pub fn constant_out_of_range(pos: ItemPosition, value: String, data_type: DataType) -> Self {
+        SemanticError { pos, kind: SemanticErrorKind::ConstantOutOfRange { value, data_type }, text: None, help: None, labels: Vec::new() }
+    }
+    #[inline]
+    pub fn division_by_zero(pos: ItemPosition) -> Self {
+        SemanticError { pos, kind: SemanticErrorKind::DivisionByZero, text: None, help: None, labels: Vec::new() }
+    }
+    #[inline]
+    pub fn trigger_schema_mismatch(pos: ItemPosition, trigger_schema: Option<String>, table_schema: Option<String>) -> Self {
+        SemanticError { pos, kind: SemanticErrorKind::TriggerSchemaMismatch { trigger_schema, table_schema }, text: None, help: None, labels: Vec::new() }
     }
     #[inline]
     pub fn set_text(&mut self, text: Arc<Text>) {
@@ -297,57 +441,141 @@ impl SemanticError {
     pub fn into_err_vec<T>(self) -> Result<T, Vec<Self>> {
         Err(vec![self])
     }
+    /// Добавляет к ошибке подсказку с именами из `candidates`, похожими на `name`.
+    pub fn with_suggestions<'a>(mut self, name: &str, candidates: impl IntoIterator<Item=&'a str>) -> Self {
+        let suggestions = similar_names(name, candidates);
+        self.help = match suggestions.len() {
+            0 => return self,
+            1 => Some(format!("did you mean {}?", suggestions[0])),
+            _ => Some(format!("did you mean one of {}?", suggestions.join(", "))),
+        };
+        self
+    }
+    /// Добавляет к ошибке подписанный участок исходного текста.
+    #[inline]
+    pub fn with_label<S: Into<String>>(mut self, pos: ItemPosition, message: S) -> Self {
+        self.labels.push(SemanticLabel { pos, message: message.into() });
+        self
+    }
+}
+
+impl SemanticError {
+    /// Записывает ошибку в виде одного объекта JSON.
+    pub fn write_json<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        write_json_diagnostic(w, self.kind.code(), &self.kind.to_string(), Severity::Error, self.pos, &self.text, &self.labels, &self.help)
+    }
 }
 
 impl fmt::Display for SemanticError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match &self.text {
-            Some(arc) => writeln!(f, "  in {} on {}", &arc.name, self.pos.begin)?,
-            None => writeln!(f, "  on {}", self.pos.begin)?,
-        }
+        fmt_location(f, self.pos, &self.text)?;
         writeln!(f, "  error: {}", self.kind)?;
-        let text = match &self.text {
-            Some(arc) => arc,
-            None => return writeln!(f, "   | text is unspecified."),
-        };
-        let lines = match self.pos.lines() {
-            0 => return writeln!(f, "   | text is unspecified."),
-            line_count => text.text.lines()
-                .skip(self.pos.begin.line - 1)
-                .take(line_count)
-                .enumerate(),
-        };
-        let mut no_lines = true;
-        let max_line_num_length = max(3, decimal_unsigned_length(self.pos.end.line));
-        for (i, line) in lines {
-            if no_lines {
-                no_lines = false;
-                write_line_numbers_columns_row(f, max_line_num_length, None)?;
-                writeln!(f, "")?;
-            }
-            let line_number = self.pos.begin.line + i;
-            write_line_numbers_columns_row(f, max_line_num_length, Some(line_number))?;
-            writeln!(f, "{}", line)?;
-            if i == 0 {
-                if self.pos.begin.line != self.pos.end.line {
-                    write_pointer_line(f, line, max_line_num_length, self.pos.begin.column, line.len())?;
-                } else {
-                    write_pointer_line(f, line, max_line_num_length, self.pos.begin.column, self.pos.end.column)?;
-                }
-            } else {
-                if line_number == self.pos.end.line {
-                    write_pointer_line(f, line, max_line_num_length, 0, self.pos.end.column)?;
-                } else {
-                    write_pointer_line(f, line, max_line_num_length, 0, line.len())?;
-                }
-            }
+        fmt_source_snippet(f, self.pos, &self.text, &self.labels, &self.help)
+    }
+}
+
+fn write_json_position<W: fmt::Write>(w: &mut W, pos: ItemPosition, text: &Option<Arc<Text>>) -> fmt::Result {
+    write!(w, "\"file\":")?;
+    write_json_optional_string(w, text.as_ref().map(|text| text.name.as_str()))?;
+    write!(w, ",\"begin\":{{\"line\":{},\"column\":{}}}", pos.begin.line, pos.begin.column)?;
+    write!(w, ",\"end\":{{\"line\":{},\"column\":{}}}", pos.end.line, pos.end.column)
+}
+
+fn write_json_diagnostic<W: fmt::Write>(w: &mut W, code: &str, message: &str, severity: Severity, pos: ItemPosition, text: &Option<Arc<Text>>, labels: &[SemanticLabel], help: &Option<String>) -> fmt::Result {
+    write!(w, "{{\"code\":")?;
+    write_json_string(w, code)?;
+    write!(w, ",\"message\":")?;
+    write_json_string(w, message)?;
+    write!(w, ",\"severity\":\"{}\",", severity)?;
+    write_json_position(w, pos, text)?;
+    write!(w, ",\"help\":")?;
+    write_json_optional_string(w, help.as_ref().map(|help| help.as_str()))?;
+    write!(w, ",\"related\":[")?;
+    for (i, label) in labels.iter().enumerate() {
+        if i != 0 {
+            write!(w, ",")?;
+        }
+        write!(w, "{{\"message\":")?;
+        write_json_string(w, &label.message)?;
+        write!(w, ",")?;
+        write_json_position(w, label.pos, text)?;
+        write!(w, "}}")?;
+    }
+    write!(w, "]}}")
+}
+
+fn fmt_location(f: &mut fmt::Formatter, pos: ItemPosition, text: &Option<Arc<Text>>) -> fmt::Result {
+    match text {
+        Some(arc) => writeln!(f, "  in {} on {}", &arc.name, pos.begin),
+        None => writeln!(f, "  on {}", pos.begin),
+    }
+}
+
+fn fmt_help(f: &mut fmt::Formatter, help: &Option<String>) -> fmt::Result {
+    match help {
+        Some(help) => writeln!(f, "  help: {}", help),
+        None => Ok(()),
+    }
+}
+
+fn fmt_source_snippet(f: &mut fmt::Formatter, pos: ItemPosition, text: &Option<Arc<Text>>, labels: &[SemanticLabel], help: &Option<String>) -> fmt::Result {
+    let text = match text {
+        Some(arc) => arc,
+        None => {
+            writeln!(f, "   | text is unspecified.")?;
+            return fmt_help(f, help);
         }
+    };
+    let max_line_num_length = labels.iter()
+        .fold(
+            max(3, decimal_unsigned_length(pos.end.line)),
+            |length, label| max(length, decimal_unsigned_length(label.pos.end.line)),
+        );
+    if !fmt_source_lines(f, pos, text, max_line_num_length, None)? {
+        writeln!(f, "   | text is unspecified.")?;
+        return fmt_help(f, help);
+    }
+    for label in labels {
+        fmt_source_lines(f, label.pos, text, max_line_num_length, Some(label.message.as_str()))?;
+    }
+    fmt_help(f, help)?;
+    writeln!(f, "")
+}
+
+/**
+    Выводит строки текста, занимаемые участком, подчёркивая его.
+    Участок с подписью подчёркивается дефисами, а подпись выводится после последней его строки.
+    Возвращает `false`, если ни одной строки вывести не удалось.
+*/
+fn fmt_source_lines(f: &mut fmt::Formatter, pos: ItemPosition, text: &Text, max_line_num_length: usize, label: Option<&str>) -> Result<bool, fmt::Error> {
+    let lines = match pos.lines() {
+        0 => return Ok(false),
+        line_count => text.text.lines()
+            .skip(pos.begin.line - 1)
+            .take(line_count)
+            .enumerate(),
+    };
+    let mut no_lines = true;
+    for (i, line) in lines {
         if no_lines {
-            writeln!(f, "   | text is unspecified.")
-        } else {
-            writeln!(f, "")
+            no_lines = false;
+            write_line_numbers_columns_row(f, max_line_num_length, None)?;
+            writeln!(f, "")?;
+        }
+        let line_number = pos.begin.line + i;
+        write_line_numbers_columns_row(f, max_line_num_length, Some(line_number))?;
+        writeln!(f, "{}", line)?;
+        let begin = if i == 0 { pos.begin.column } else { 0 };
+        let end = if line_number == pos.end.line { pos.end.column } else { line.len() };
+        match label {
+            Some(label) => {
+                let label = if line_number == pos.end.line { Some(label) } else { None };
+                write_labelled_pointer_line(f, line, max_line_num_length, begin, end, label)?;
+            }
+            None => write_pointer_line(f, line, max_line_num_length, begin, end)?,
         }
     }
+    Ok(!no_lines)
 }
 
 impl From<SemanticError> for Vec<SemanticError> {
@@ -374,6 +602,17 @@ pub struct SemanticErrors {
     pub errors: Vec<SemanticError>,
 }
 
+impl SemanticErrors {
+    /// Записывает ошибки в виде объектов JSON, по одному на строку.
+    pub fn write_json_lines<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        for error in &self.errors {
+            error.write_json(w)?;
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+}
+
 impl fmt::Display for SemanticErrors {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "Found some errors:")?;
@@ -412,3 +651,265 @@ impl From<Vec<SemanticError>> for SemanticErrors {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SemanticWarningKind {
+    UnusedVariable {
+        name: String,
+    },
+    UnusedArgument {
+        name: String,
+    },
+    UnusedImport {
+        name: String,
+    },
+    ShadowedVariable {
+        name: String,
+    },
+    UnreachableStatement,
+}
+
+impl SemanticWarningKind {
+    /// Стабильный код предупреждения, по которому его можно опознать вне компилятора.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SemanticWarningKind::UnusedVariable { .. } => "W0001",
+            SemanticWarningKind::UnusedArgument { .. } => "W0002",
+            SemanticWarningKind::UnusedImport { .. } => "W0003",
+            SemanticWarningKind::ShadowedVariable { .. } => "W0004",
+            SemanticWarningKind::UnreachableStatement => "W0005",
+        }
+    }
+}
+
+impl fmt::Display for SemanticWarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SemanticWarningKind::UnusedVariable { name } => write!(f, "unused variable {}", name),
+            SemanticWarningKind::UnusedArgument { name } => write!(f, "unused argument {}", name),
+            SemanticWarningKind::UnusedImport { name } => write!(f, "unused import {}", name),
+            SemanticWarningKind::ShadowedVariable { name } => write!(f, "variable {} shadows variable of outer scope", name),
+            SemanticWarningKind::UnreachableStatement => write!(f, "unreachable statement"),
+        }
+    }
+}
+
+/**
+    Предупреждение семантического анализа.
+    В отличие от ошибки не мешает генерации кода, если только его важность не была повышена до ошибки.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemanticWarning {
+    pub pos: ItemPosition,
+    pub kind: SemanticWarningKind,
+    pub severity: Severity,
+    pub text: Option<Arc<Text>>,
+}
+
+impl SemanticWarning {
+    #[inline]
+    pub fn new(pos: ItemPosition, kind: SemanticWarningKind) -> Self {
+        SemanticWarning { pos, kind, severity: Severity::Warning, text: None }
+    }
+    #[inline]
+    pub fn unused_variable(pos: ItemPosition, name: String) -> Self {
+        SemanticWarning::new(pos, SemanticWarningKind::UnusedVariable { name })
+    }
+    #[inline]
+    pub fn unused_argument(pos: ItemPosition, name: String) -> Self {
+        SemanticWarning::new(pos, SemanticWarningKind::UnusedArgument { name })
+    }
+    #[inline]
+    pub fn unused_import(pos: ItemPosition, name: String) -> Self {
+        SemanticWarning::new(pos, SemanticWarningKind::UnusedImport { name })
+    }
+    #[inline]
+    pub fn shadowed_variable(pos: ItemPosition, name: String) -> Self {
+        SemanticWarning::new(pos, SemanticWarningKind::ShadowedVariable { name })
+    }
+    #[inline]
+    pub fn unreachable_statement(pos: ItemPosition) -> Self {
+        SemanticWarning::new(pos, SemanticWarningKind::UnreachableStatement)
+    }
+    #[inline]
+    pub fn set_text(&mut self, text: Arc<Text>) {
+        self.text = Some(text);
+    }
+    /// Повышает важность предупреждения до ошибки.
+    #[inline]
+    pub fn deny(&mut self) {
+        self.severity = Severity::Error;
+    }
+}
+
+impl SemanticWarning {
+    /// Записывает предупреждение в виде одного объекта JSON.
+    pub fn write_json<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        write_json_diagnostic(w, self.kind.code(), &self.kind.to_string(), self.severity, self.pos, &self.text, &[], &None)
+    }
+}
+
+impl fmt::Display for SemanticWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_location(f, self.pos, &self.text)?;
+        writeln!(f, "  {}: {}", self.severity, self.kind)?;
+        fmt_source_snippet(f, self.pos, &self.text, &[], &None)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SemanticWarnings {
+    pub warnings: Vec<SemanticWarning>,
+}
+
+impl SemanticWarnings {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+    /// Повышает важность всех предупреждений до ошибки.
+    pub fn deny(&mut self) {
+        for warning in self.warnings.iter_mut() {
+            warning.deny();
+        }
+    }
+    /// Записывает предупреждения в виде объектов JSON, по одному на строку.
+    pub fn write_json_lines<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        for warning in &self.warnings {
+            warning.write_json(w)?;
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SemanticWarnings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Found some warnings:")?;
+        for warning in &self.warnings {
+            writeln!(f, "{}", warning)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for SemanticWarnings {
+    #[inline]
+    fn description(&self) -> &str {
+        "Semantic warnings"
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&dyn Error> {
+        None
+    }
+}
+
+impl From<Vec<SemanticWarning>> for SemanticWarnings {
+    #[inline]
+    fn from(warnings: Vec<SemanticWarning>) -> Self {
+        Self {
+            warnings,
+        }
+    }
+}
+
+/**
+    Ошибка генерации кода.
+    Возникает, если проанализированный проект невозможно выразить на целевом языке.
+    Если положение ошибки неизвестно там, где она возникла, его уточняет генератор объемлющего элемента.
+*/
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GenerateError {
+    pub pos: ItemPosition,
+    pub message: String,
+    pub text: Option<Arc<Text>>,
+}
+
+pub type GenerateResult<T = ()> = Result<T, GenerateError>;
+
+impl GenerateError {
+    #[inline]
+    pub fn new<S: Into<String>>(pos: ItemPosition, message: S) -> Self {
+        GenerateError { pos, message: message.into(), text: None }
+    }
+    #[inline]
+    pub fn without_pos<S: Into<String>>(message: S) -> Self {
+        GenerateError::new(ItemPosition::default(), message)
+    }
+    /// Задаёт положение ошибки, если оно ещё не было известно.
+    #[inline]
+    pub fn locate(mut self, pos: ItemPosition) -> Self {
+        if self.pos == ItemPosition::default() {
+            self.pos = pos;
+        }
+        self
+    }
+    #[inline]
+    pub fn set_text(&mut self, text: Arc<Text>) {
+        if self.text.is_none() {
+            self.text = Some(text);
+        }
+    }
+    /// Задаёт положение элемента и текст модуля, в котором он определён, если они ещё не были известны.
+    #[inline]
+    pub fn locate_in(mut self, pos: ItemPosition, text: Option<&Arc<Text>>) -> Self {
+        if let Some(text) = text {
+            self.set_text(text.clone());
+        }
+        self.locate(pos)
+    }
+    /// Записывает ошибку в виде одного объекта JSON.
+    pub fn write_json<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        write_json_diagnostic(w, "G0001", &self.message, Severity::Error, self.pos, &self.text, &[], &None)
+    }
+}
+
+impl From<fmt::Error> for GenerateError {
+    #[inline]
+    fn from(_: fmt::Error) -> Self {
+        GenerateError::without_pos("cannot write generated code")
+    }
+}
+
+impl From<SemanticError> for GenerateError {
+    #[inline]
+    fn from(error: SemanticError) -> Self {
+        GenerateError { pos: error.pos, message: error.kind.to_string(), text: error.text }
+    }
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_location(f, self.pos, &self.text)?;
+        writeln!(f, "  error: {}", self.message)?;
+        fmt_source_snippet(f, self.pos, &self.text, &[], &None)
+    }
+}
+
+impl Error for GenerateError {
+    #[inline]
+    fn description(&self) -> &str {
+        "Code generation error"
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&dyn Error> {
+        None
+    }
+}
@@ -12,6 +12,7 @@ use project_analysis::{
     ProjectContext,
     SemanticError,
     SemanticItemType,
+    SemanticWarning,
 };
 use std::{
     fmt,
@@ -26,6 +27,7 @@ pub struct FunctionVariable {
     is_read_only: bool,
     is_argument: bool,
     is_automatic: bool,
+    is_used: bool,
 }
 
 impl FunctionVariable {
@@ -38,6 +40,7 @@ impl FunctionVariable {
             is_read_only: false,
             is_argument: false,
             is_automatic: false,
+            is_used: false,
         })
     }
     #[inline]
@@ -79,6 +82,18 @@ impl FunctionVariable {
     pub fn mark_as_automatic(&mut self) {
         self.is_automatic = true
     }
+    #[inline]
+    pub fn pos(&self) -> ItemPosition {
+        self.pos
+    }
+    #[inline]
+    pub fn is_used(&self) -> bool {
+        self.is_used
+    }
+    #[inline]
+    pub fn mark_as_used(&mut self) {
+        self.is_used = true
+    }
 }
 
 impl SyncRef<FunctionVariable> {
@@ -122,6 +137,14 @@ impl SyncRef<FunctionVariable> {
     pub fn mark_as_automatic(&self) {
         self.write().mark_as_automatic()
     }
+    #[inline]
+    pub fn is_used(&self) -> bool {
+        self.read().is_used()
+    }
+    #[inline]
+    pub fn mark_as_used(&self) {
+        self.write().mark_as_used()
+    }
 }
 
 #[derive(Clone, PartialEq)]
@@ -190,20 +213,51 @@ impl SyncRef<FunctionVariableScope> {
         self.parent()?
             .get_variable(name)
     }
+    /// Имена всех переменных, видимых из данной области.
+    pub fn visible_variable_names(&self) -> Vec<String> {
+        let mut result: Vec<String> = self.read().variables()
+            .iter()
+            .map(|var| var.read().name().to_string())
+            .collect();
+        if let Some(parent) = self.parent() {
+            result.append(&mut parent.visible_variable_names());
+        }
+        result
+    }
     pub fn access_to_variable(&self, pos: ItemPosition, name: &str) -> Result<SyncRef<FunctionVariable>, SemanticError> {
         match self.get_variable(name) {
             Some(var) => Ok(var),
-            None => Err(SemanticError::not_in_scope(pos, name.to_string())),
+            None => {
+                let candidates = self.visible_variable_names();
+                Err(
+                    SemanticError::not_in_scope(pos, name.to_string())
+                        .with_suggestions(name, candidates.iter().map(String::as_str))
+                )
+            }
         }
     }
     pub fn new_variable(&self, pos: ItemPosition, name: String, data_type: Option<DataType>) -> Result<SyncRef<FunctionVariable>, SemanticError> {
-        if self.read().find_variable(name.as_str()).is_some() {
-            return Err(SemanticError::duplicate_definition(pos, name, SemanticItemType::Variable));
+        if let Some(first) = self.read().find_variable(name.as_str()) {
+            return Err(
+                SemanticError::duplicate_definition(pos, name, SemanticItemType::Variable)
+                    .with_label(first.read().pos(), "first defined here")
+            );
         }
         let var = FunctionVariable::new(pos, name, data_type);
         self.write().variables.push(var.clone());
         Ok(var)
     }
+    /// Объявляет переменную пользователя, предупреждая о сокрытии одноимённой переменной внешней области видимости.
+    pub fn declare_variable(&self, pos: ItemPosition, name: String, data_type: Option<DataType>) -> Result<SyncRef<FunctionVariable>, SemanticError> {
+        let shadowed = self.parent()
+            .and_then(|parent| parent.get_variable(name.as_str()));
+        if let Some(shadowed) = shadowed {
+            if !shadowed.is_automatic() {
+                self.module().add_warning(SemanticWarning::shadowed_variable(pos, name.clone()));
+            }
+        }
+        self.new_variable(pos, name, data_type)
+    }
     pub fn new_temp_variable(&self, pos: ItemPosition, data_type: DataType) -> SyncRef<FunctionVariable> {
         let guard = self.read();
         let name = generate_name(
@@ -211,6 +265,7 @@ impl SyncRef<FunctionVariableScope> {
             "t".to_string(),
         );
         let var = FunctionVariable::new(pos, name, Some(data_type));
+        var.mark_as_used();
         self.write().variables.push(var.clone());
         var
     }
@@ -305,6 +360,22 @@ impl SyncRef<FunctionContext> {
     }
     #[inline]
     pub fn project(&self) -> SyncRef<ProjectContext> { self.module().project() }
+    /// Предупреждает о переменных и аргументах, значение которых ни разу не было прочитано.
+    pub fn check_unused_variables(&self) {
+        let module = self.module();
+        for variable in self.get_all_variables() {
+            let variable = variable.read();
+            if variable.is_used() || variable.is_automatic() {
+                continue;
+            }
+            let name = variable.name().to_string();
+            module.add_warning(if variable.is_argument() {
+                SemanticWarning::unused_argument(variable.pos(), name)
+            } else {
+                SemanticWarning::unused_variable(variable.pos(), name)
+            });
+        }
+    }
     pub fn get_all_variables(&self) -> Vec<SyncRef<FunctionVariable>> {
         let mut result = Vec::new();
         let self_guard = self.read();
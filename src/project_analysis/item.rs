@@ -4,11 +4,18 @@ use helpers::{
     SyncRef,
 };
 use language::{
+    ConstDefinition,
     DataTypeDefinition,
     FunctionDefinition,
     TableDefinition,
+    TriggerDefinition,
+    ViewDefinition,
+};
+use project_analysis::{
+    GenerateError,
+    GenerateResult,
+    Module,
 };
-use project_analysis::Module;
 use std::fmt;
 
 #[derive(Clone)]
@@ -33,6 +40,15 @@ pub enum ItemBody {
     Function {
         def: FunctionDefinition,
     },
+    View {
+        def: ViewDefinition,
+    },
+    Trigger {
+        def: TriggerDefinition,
+    },
+    Constant {
+        def: ConstDefinition,
+    },
 }
 
 impl Item {
@@ -62,10 +78,12 @@ impl Item {
         let entity = SyncRef::new(Item::data_type(parent.clone(), DataTypeDefinition {
             name: format!("{}::entity", def.name),
             body: def.entity.clone(),
+            nominal: false,
         }));
         let primary_key = SyncRef::new(Item::data_type(parent.clone(), DataTypeDefinition {
             name: format!("{}::primary_key", def.name),
             body: def.primary_key.clone(),
+            nominal: false,
         }));
         Item {
             parent,
@@ -73,12 +91,36 @@ impl Item {
         }
     }
     #[inline]
+    pub fn view(parent: SyncRef<Module>, def: ViewDefinition) -> Self {
+        Item {
+            parent,
+            body: ItemBody::View { def },
+        }
+    }
+    #[inline]
+    pub fn trigger(parent: SyncRef<Module>, def: TriggerDefinition) -> Self {
+        Item {
+            parent,
+            body: ItemBody::Trigger { def },
+        }
+    }
+    #[inline]
+    pub fn constant(parent: SyncRef<Module>, def: ConstDefinition) -> Self {
+        Item {
+            parent,
+            body: ItemBody::Constant { def },
+        }
+    }
+    #[inline]
     pub fn get_type(&self) -> SemanticItemType {
         match &self.body {
             ItemBody::DataType { def: _ } => SemanticItemType::DataType,
             ItemBody::ModuleReference { module: _ } => SemanticItemType::Module,
             ItemBody::Table { def: _, entity: _, primary_key: _ } => SemanticItemType::Table,
             ItemBody::Function { def: _ } => SemanticItemType::Function,
+            ItemBody::View { def: _ } => SemanticItemType::View,
+            ItemBody::Trigger { def: _ } => SemanticItemType::Trigger,
+            ItemBody::Constant { def: _ } => SemanticItemType::Constant,
         }
     }
     #[inline]
@@ -109,22 +151,62 @@ impl Item {
             _ => None,
         }
     }
-    pub fn get_path(&self) -> PathBuf {
+    #[inline]
+    pub fn get_view(&self) -> Option<&ViewDefinition> {
+        match &self.body {
+            ItemBody::View { def } => Some(def),
+            _ => None,
+        }
+    }
+    #[inline]
+    pub fn get_trigger(&self) -> Option<&TriggerDefinition> {
+        match &self.body {
+            ItemBody::Trigger { def } => Some(def),
+            _ => None,
+        }
+    }
+    #[inline]
+    pub fn get_constant(&self) -> Option<&ConstDefinition> {
+        match &self.body {
+            ItemBody::Constant { def } => Some(def),
+            _ => None,
+        }
+    }
+    fn get_name(&self) -> Option<&str> {
         let name = match &self.body {
             ItemBody::DataType { def } => def.name.as_str(),
-            ItemBody::ModuleReference { module } => {
-                let module = module.read();
-                let path = module.path().read();
-                return path.clone();
-            }
+            ItemBody::ModuleReference { module: _ } => return None,
             ItemBody::Table { def, .. } => def.name.as_str(),
             ItemBody::Function { def } => def.name.as_str(),
+            ItemBody::View { def } => def.name.as_str(),
+            ItemBody::Trigger { def } => def.name.as_str(),
+            ItemBody::Constant { def } => def.name.as_str(),
+        };
+        Some(name)
+    }
+    pub fn get_path(&self) -> GenerateResult<PathBuf> {
+        let name = match self.get_name() {
+            Some(name) => name,
+            None => {
+                let module = self.get_module_ref()
+                    .ok_or_else(|| GenerateError::without_pos("only module references has no name"))?
+                    .read();
+                let path = module.path().read();
+                return Ok(path.clone());
+            }
         };
         let parent = self.parent.read();
         let path = parent.path().read();
         let mut result = path.clone();
         result.push(name);
-        result
+        Ok(result)
+    }
+    /// Выводит имя объекта базы данных, соответствующего элементу.
+    pub fn fmt_object_name(&self, f: &mut impl fmt::Write) -> GenerateResult {
+        let name = self.get_name()
+            .ok_or_else(|| GenerateError::without_pos("module references cannot be database objects"))?;
+        self.parent.read().fmt_object_name(f, name, self.get_function().is_some())?;
+        Ok(())
     }
     #[inline]
     pub fn body(&self) -> &ItemBody {
@@ -152,6 +234,9 @@ impl SyncRef<Item> {
                 return module.get_item(path, search_route);
             }
             ItemBody::Function { def: _ } => {}
+            ItemBody::View { def: _ } => {}
+            ItemBody::Trigger { def: _ } => {}
+            ItemBody::Constant { def: _ } => {}
             ItemBody::Table { def: _, entity, primary_key } => if let Some(name) = path.the_only() {
                 match name {
                     "entity" => return Some(entity.clone()),
@@ -195,6 +280,9 @@ pub enum SemanticItemType {
     Table,
     Variable,
     Function,
+    View,
+    Trigger,
+    Constant,
 }
 
 impl SemanticItemType {
@@ -207,6 +295,9 @@ impl SemanticItemType {
             &SemanticItemType::Table => "table",
             &SemanticItemType::Variable => "variable",
             &SemanticItemType::Function => "function",
+            &SemanticItemType::View => "view",
+            &SemanticItemType::Trigger => "trigger",
+            &SemanticItemType::Constant => "constant",
         }
     }
 }
@@ -15,6 +15,8 @@
 
 */
 
+pub use self::cache::*;
+pub use self::dependency_graph::*;
 pub use self::error::*;
 pub use self::function::*;
 pub use self::insert_source::*;
@@ -27,6 +29,10 @@ pub use self::stdlib::*;
 
 pub mod project;
 
+pub mod dependency_graph;
+
+pub mod cache;
+
 pub mod error;
 
 pub mod source;
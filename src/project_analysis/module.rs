@@ -1,22 +1,40 @@
 use helpers::{
+    fmt_tsql_object_name,
     Path,
     PathBuf,
     Resolve,
     SyncRef,
 };
 use indexmap::IndexMap;
-use language::modules::{
-    module,
-    ModuleDefinitionItem,
-    ModuleDefinitionItemAST,
+use language::{
+    AttributeAST,
+    find_attribute_ast,
+    FunctionBody,
+    modules::{
+        module,
+        ExternalItemTailAST,
+        ModuleAST,
+        ModuleDefinitionItem,
+        ModuleDefinitionItemAST,
+        ModuleDefinitionValueAST,
+        resolve_grant_roles,
+    },
+};
+use lexeme_scanner::{
+    ItemPosition,
+    Scanner,
+};
+use parser_basics::{
+    parse,
+    ParserError,
 };
-use lexeme_scanner::Scanner;
-use parser_basics::parse;
 use project_analysis::{
     Item,
     ProjectContext,
     SemanticError,
+    SemanticErrorKind,
     SemanticItemType,
+    SemanticWarning,
     Text,
 };
 use std::{
@@ -29,7 +47,10 @@ use std::{
 pub struct UnresolvedModule {
     text: Arc<Text>,
     source: &'static str,
+    attributes: Vec<AttributeAST<'static>>,
     items: Vec<ModuleDefinitionItemAST<'static>>,
+    parser_errors: Vec<SemanticError>,
+    failed_items: Vec<String>,
 }
 
 impl UnresolvedModule {
@@ -39,29 +60,55 @@ impl UnresolvedModule {
             Ok(tokens) => tokens,
             Err(error) => return SemanticError::scanner_error(error).into_err_vec(),
         };
-        let items = match parse(tokens.as_slice(), module) {
-            Ok(items) => items,
-            Err(errors) => return Err(
-                errors.extract_into_vec()
-                    .into_iter()
-                    .map(|error| {
-                        let mut error = SemanticError::parser_error(error);
-                        error.set_text(text.clone());
-                        error
-                    })
-                    .collect()
-            ),
+        let ModuleAST { attributes, items, errors, failed_items } = match parse(tokens.as_slice(), module) {
+            Ok(module) => module,
+            Err(errors) => return Err(convert_parser_errors(errors, &text)),
         };
+        let parser_errors = convert_parser_errors(errors, &text);
         Ok(UnresolvedModule {
             text,
             source,
+            attributes,
             items,
+            parser_errors,
+            failed_items: failed_items.iter()
+                .map(|name| name.text().to_string())
+                .collect(),
         })
     }
     #[inline]
     pub fn from_text(text: Text) -> Result<Self, Vec<SemanticError>> {
         UnresolvedModule::new(Arc::new(text))
     }
+    /// Ошибки разбора элементов, пропущенных при восстановлении разбора.
+    #[inline]
+    pub fn parser_errors(&self) -> &[SemanticError] {
+        &self.parser_errors
+    }
+    #[inline]
+    pub fn text(&self) -> &Arc<Text> {
+        &self.text
+    }
+    /// Пути импортируемых элементов вместе с положениями импортов.
+    pub fn imports<'a>(&'a self) -> Vec<(Path<'a>, ItemPosition)> {
+        self.items.iter()
+            .filter_map(|item| match &item.value {
+                ModuleDefinitionValueAST::Import(import) => Some((import.path.path.as_path(), import.path.pos)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn convert_parser_errors(errors: ParserError, text: &Arc<Text>) -> Vec<SemanticError> {
+    errors.extract_into_vec()
+        .into_iter()
+        .map(|error| {
+            let mut error = SemanticError::parser_error(error);
+            error.set_text(text.clone());
+            error
+        })
+        .collect()
 }
 
 impl Drop for UnresolvedModule {
@@ -72,37 +119,85 @@ impl Drop for UnresolvedModule {
     }
 }
 
+impl UnresolvedModule {
+    /// Читает схему базы данных, заданную атрибутом модуля `#![schema(name)]`.
+    fn resolve_schema(&self) -> Result<Option<String>, SemanticError> {
+        let attribute = match find_attribute_ast(&self.attributes, "schema") {
+            Some(attribute) => attribute,
+            None => return Ok(None),
+        };
+        match &attribute.arguments {
+            Some(arguments) if arguments.len() == 1 => Ok(Some(arguments[0].to_string())),
+            Some(arguments) => Err(SemanticError::wrong_arguments_count(attribute.name.item_pos(), 1, arguments.len())),
+            None => Err(SemanticError::wrong_arguments_count(attribute.name.item_pos(), 1, 0)),
+        }
+    }
+}
+
+impl UnresolvedModule {
+    /**
+        Проверяет, что ошибка лишь следует из ошибки разбора:
+        она сообщает о неразрешённом элементе, определение которого не удалось разобрать.
+    */
+    fn is_follow_on_error(&self, error: &SemanticError) -> bool {
+        match &error.kind {
+            SemanticErrorKind::UnresolvedItem { path } => match path.the_only() {
+                Some(name) => self.failed_items.iter().any(|item| item == name),
+                None => false,
+            },
+            _ => false,
+        }
+    }
+    /// Предупреждает о непубличных импортах, к которым ни разу не обращались внутри модуля.
+    fn check_unused_imports(&self, context: &SyncRef<Module>) {
+        for item in self.items.iter() {
+            if item.public {
+                continue;
+            }
+            let import = match &item.value {
+                ModuleDefinitionValueAST::Import(import) => import,
+                _ => continue,
+            };
+            if import.tail == ExternalItemTailAST::Asterisk {
+                continue;
+            }
+            let name = item.value.name();
+            if !context.read().is_item_used(name) {
+                context.add_warning(SemanticWarning::unused_import(import.path.pos, name.to_string()));
+            }
+        }
+    }
+}
+
 impl Resolve<(SyncRef<PathBuf>, SyncRef<ProjectContext>)> for UnresolvedModule {
     type Result = SyncRef<Module>;
     type Error = SemanticError;
     fn resolve(&self, ctx: &(SyncRef<PathBuf>, SyncRef<ProjectContext>)) -> Result<Self::Result, Vec<Self::Error>> {
         let context = SyncRef::new(Module::new(ctx.0.clone(), ctx.1.clone()));
         {
-            let mut errors = match self.items.resolve(&context) {
-                Ok(_) => Vec::new(),
-                Err(mut errors) => {
-                    for error in errors.iter_mut() {
-                        error.set_text(self.text.clone());
-                    }
-                    errors
+            let mut errors = match self.resolve_schema() {
+                Ok(schema) => {
+                    context.write().schema = schema;
+                    Vec::new()
                 }
+                Err(error) => vec![error],
             };
-            let context = context.read();
-            let mut item_names = Vec::new();
-            for (name, item) in context.items.iter() {
-                let borrowed_name = name.as_str();
-                match item_names.iter().find(|&&name| name == borrowed_name) {
-                    Some(_) => errors.push({
-                        let mut error = SemanticError::duplicate_definition(
-                            item.position,
-                            name.clone(),
-                            SemanticItemType::Definition,
-                        );
-                        error.set_text(self.text.clone());
-                        error
-                    }),
-                    None => item_names.push(borrowed_name),
-                }
+            match resolve_grant_roles(&self.attributes) {
+                Ok(grants) => context.write().grants = grants,
+                Err(error) => errors.push(error),
+            }
+            context.write().text = Some(self.text.clone());
+            if let Err(mut sub_errors) = self.items.resolve(&context) {
+                errors.append(&mut sub_errors);
+            }
+            errors.retain(|error| !self.is_follow_on_error(error));
+            for error in errors.iter_mut() {
+                error.set_text(self.text.clone());
+            }
+            self.check_unused_imports(&context);
+            let mut context = context.write();
+            for warning in context.warnings.iter_mut() {
+                warning.set_text(self.text.clone());
             }
             if !errors.is_empty() {
                 return Err(errors);
@@ -118,6 +213,11 @@ pub struct Module {
     path: SyncRef<PathBuf>,
     project: SyncRef<ProjectContext>,
     imported: Vec<SyncRef<Module>>,
+    schema: Option<String>,
+    grants: Vec<String>,
+    used_items: Vec<String>,
+    warnings: Vec<SemanticWarning>,
+    text: Option<Arc<Text>>,
 }
 
 impl Module {
@@ -128,6 +228,11 @@ impl Module {
             path,
             project,
             imported: Vec::new(),
+            schema: None,
+            grants: Vec::new(),
+            used_items: Vec::new(),
+            warnings: Vec::new(),
+            text: None,
         }
     }
     #[inline]
@@ -170,6 +275,57 @@ impl Module {
     pub fn path(&self) -> &SyncRef<PathBuf> {
         &self.path
     }
+    /// Схема базы данных, в которой создаются объекты модуля.
+    #[inline]
+    pub fn schema(&self) -> Option<&str> {
+        match &self.schema {
+            Some(schema) => Some(schema.as_str()),
+            None => None,
+        }
+    }
+    /// Роли, получающие доступ ко всем объектам модуля.
+    #[inline]
+    pub fn grants(&self) -> &[String] {
+        &self.grants
+    }
+    /// Имена элементов, доступных в модуле, включая элементы модулей, импортированных целиком.
+    pub fn visible_item_names(&self) -> Vec<String> {
+        let mut result: Vec<String> = self.items.keys()
+            .cloned()
+            .collect();
+        for module in self.imported.iter() {
+            result.extend(module.read().items.keys().cloned());
+        }
+        result
+    }
+    /// Исходный текст модуля.
+    #[inline]
+    pub fn text(&self) -> Option<&Arc<Text>> {
+        self.text.as_ref()
+    }
+    /// Предупреждения, найденные при разрешении модуля.
+    #[inline]
+    pub fn warnings(&self) -> &[SemanticWarning] {
+        &self.warnings
+    }
+    #[inline]
+    pub fn add_warning(&mut self, warning: SemanticWarning) {
+        self.warnings.push(warning);
+    }
+    #[inline]
+    fn mark_item_as_used(&mut self, name: &str) {
+        if self.items.contains_key(name) && !self.used_items.iter().any(|used| used == name) {
+            self.used_items.push(name.to_string());
+        }
+    }
+    #[inline]
+    fn is_item_used(&self, name: &str) -> bool {
+        self.used_items.iter().any(|used| used == name)
+    }
+    pub fn fmt_object_name(&self, f: &mut impl fmt::Write, name: &str, is_routine: bool) -> fmt::Result {
+        let path = self.path.read();
+        fmt_tsql_object_name(f, self.schema(), path.as_path(), name, is_routine)
+    }
 }
 
 impl SyncRef<Module> {
@@ -185,8 +341,41 @@ impl SyncRef<Module> {
         if path.is_empty() {
             return Some(SyncRef::new(Item::module_ref(self.clone())));
         }
+        if let Some(name) = path.clone().pop_left() {
+            self.write().mark_item_as_used(name);
+        }
         self.read().get_item(path, search_route)
     }
+    /**
+        Объекты базы данных, которые модуль создаёт в заданной им схеме, вместе с положениями их имён.
+        У модуля без схемы таких объектов нет: имена его объектов содержат путь модуля и не пересекаются с другими.
+    */
+    pub fn schema_objects(&self) -> Vec<(String, ItemPosition, SemanticItemType)> {
+        let module = self.read();
+        let mut result = Vec::new();
+        if module.schema().is_none() {
+            return result;
+        }
+        for (item_name, item_def) in module.items().iter() {
+            let item = item_def.value.read();
+            if !item.is_belongs_to(self) {
+                continue;
+            }
+            let is_object = match item.get_function() {
+                Some(function) => match function.body {
+                    FunctionBody::External => false,
+                    FunctionBody::Implementation(_) => true,
+                },
+                None => item.get_table().is_some() || item.get_view().is_some() || item.get_trigger().is_some(),
+            };
+            let mut name = String::new();
+            if !is_object || module.fmt_object_name(&mut name, item_name, false).is_err() {
+                continue;
+            }
+            result.push((name, item_def.name_position, item.get_type()));
+        }
+        result
+    }
     #[inline]
     pub fn resolve_import(&self, path: Path) -> Option<SyncRef<Item>> {
         self.read().resolve_import(path)
@@ -196,6 +385,40 @@ impl SyncRef<Module> {
         self.write().inject_import_module(module)
     }
     #[inline]
+    pub fn add_warning(&self, warning: SemanticWarning) {
+        self.write().add_warning(warning)
+    }
+    /// Имена элементов, среди которых мог бы находиться элемент `path`.
+    pub fn sibling_item_names(&self, path: Path) -> Vec<String> {
+        let mut prefix = path;
+        if prefix.pop_right().is_none() {
+            return Vec::new();
+        }
+        if prefix.is_empty() {
+            return self.read().visible_item_names();
+        }
+        let item = match self.get_item(prefix, &mut Vec::new()) {
+            Some(item) => Some(item),
+            None => self.resolve_import(prefix),
+        };
+        let module = item.and_then(|item| item.read().get_module_ref().cloned());
+        match module {
+            Some(module) => module.read().visible_item_names(),
+            None => Vec::new(),
+        }
+    }
+    /// Конструирует ошибку неразрешённого элемента `path` с подсказкой похожих имён.
+    pub fn unresolved_item_error(&self, pos: ItemPosition, path: &PathBuf) -> SemanticError {
+        let error = SemanticError::unresolved_item(pos, path.clone());
+        let mut item_path = path.as_path();
+        let name = match item_path.pop_right() {
+            Some(name) => name,
+            None => return error,
+        };
+        let candidates = self.sibling_item_names(path.as_path());
+        error.with_suggestions(name, candidates.iter().map(String::as_str))
+    }
+    #[inline]
     pub fn project(&self) -> SyncRef<ProjectContext> { self.read().project.clone() }
 }
 
@@ -204,6 +427,8 @@ impl fmt::Debug for Module {
         f.debug_struct("Module")
             .field("items", &self.items)
             .field("path", &self.path)
+            .field("schema", &self.schema)
+            .field("grants", &self.grants)
             .finish()
     }
 }
@@ -214,6 +439,10 @@ impl cmp::PartialEq for Module {
             &&
             self.path == other.path
             &&
+            self.schema == other.schema
+            &&
+            self.grants == other.grants
+            &&
             self.items == other.items
     }
     fn ne(&self, other: &Module) -> bool {
@@ -221,6 +450,10 @@ impl cmp::PartialEq for Module {
             ||
             self.path != other.path
             ||
+            self.schema != other.schema
+            ||
+            self.grants != other.grants
+            ||
             self.items != other.items
     }
 }
@@ -15,27 +15,41 @@ use language::{
 };
 use lexeme_scanner::ItemPosition;
 use project_analysis::{
+    DependencyCycle,
+    DependencyGraph,
+    EmptyModuleCache,
+    hash_text,
     Item,
     Module,
+    module_fingerprint,
+    ModuleCache,
+    ModuleInterface,
+    OverloadResolution,
     SemanticError,
+    SemanticItemType,
+    SemanticWarning,
     StdLib,
     StdLibBinaryOperation,
     StdLibFunction,
+    StdLibOverload,
     StdLibPostfixUnaryOperation,
     StdLibPrefixUnaryOperation,
+    Text,
     TextSource,
     UnresolvedModule,
 };
 use std::{
-    mem::replace,
+    collections::{
+        HashMap,
+        HashSet,
+    },
     sync::Arc,
 };
 
 #[derive(Debug)]
 pub struct ProjectContext {
     modules: IndexMap<SyncRef<PathBuf>, ResolutionModuleState>,
-    new_module_requested: bool,
-    new_module_resolved: bool,
+    parser_errors: Vec<SemanticError>,
     stdlib: SyncRef<StdLib>,
 }
 
@@ -45,28 +59,139 @@ pub enum ResolutionModuleState {
     LoadFailed,
     ParseFailed(Vec<SemanticError>),
     Unresolved(UnresolvedModule),
+    Cached(Arc<Text>, ModuleInterface),
     Resolved(SyncRef<Module>),
 }
 
+/// Результат разрешения проекта с учётом кеша модулей.
+#[derive(Debug, Clone)]
+pub struct ResolvedProject {
+    /// Разрешённые модули в порядке зависимостей.
+    pub modules: IndexMap<SyncRef<PathBuf>, SyncRef<Module>>,
+    pub warnings: Vec<SemanticWarning>,
+    /// Интерфейсы всех модулей проекта, кроме входящих в циклы импортов.
+    pub interfaces: HashMap<PathBuf, ModuleInterface>,
+    /// Модули, разрешение которых пропущено, так как ни они, ни их зависимости не изменились.
+    pub reused_modules: Vec<PathBuf>,
+    /// Пути всех модулей проекта, включая переиспользованные, в порядке зависимостей.
+    pub order: Vec<PathBuf>,
+}
+
 impl ProjectContext {
     #[inline]
     pub fn new(stdlib: SyncRef<StdLib>) -> SyncRef<Self> {
         SyncRef::new(ProjectContext {
             modules: IndexMap::new(),
-            new_module_requested: false,
-            new_module_resolved: false,
+            parser_errors: Vec::new(),
             stdlib,
         })
     }
     pub fn get_module(&self, path: Path) -> Option<&ResolutionModuleState> {
-        for (item_path, item) in self.modules.iter() {
-            let item_path = item_path.read();
-            if item_path.as_path() == path {
-                return Some(item);
+        let index = self.get_module_index(path)?;
+        self.modules.get_index(index)
+            .map(|(_, module)| module)
+    }
+    fn get_module_index(&self, path: Path) -> Option<usize> {
+        self.modules.keys()
+            .position(|item_path| item_path.read().as_path() == path)
+    }
+    /// Индекс модуля, которому принадлежит элемент `path`: модуля с самым длинным путём, с которого начинается `path`.
+    fn find_owner_module_index(&self, mut path: Path) -> Option<usize> {
+        while !path.is_empty() {
+            if let Some(index) = self.get_module_index(path) {
+                return Some(index);
             }
+            path.pop_right();
         }
         None
     }
+    /// Строит граф зависимостей модулей по их импортам.
+    fn dependency_graph(&self) -> DependencyGraph {
+        let mut graph = DependencyGraph::new(self.modules.len());
+        for (index, module) in self.modules.values().enumerate() {
+            let imports = match module {
+                ResolutionModuleState::Unresolved(module) => module.imports(),
+                ResolutionModuleState::Cached(_, interface) => interface.imports.iter()
+                    .map(|path| (path.as_path(), ItemPosition::default()))
+                    .collect(),
+                _ => continue,
+            };
+            for (path, pos) in imports {
+                if let Some(dependency) = self.find_owner_module_index(path) {
+                    graph.add_dependency(index, dependency, pos);
+                }
+            }
+        }
+        graph
+    }
+    /// Упорядочивает модули по зависимостям, начиная обход с модулей, отсортированных по пути.
+    fn sort_modules(&self, graph: &DependencyGraph) -> (Vec<usize>, Vec<DependencyCycle>) {
+        let mut roots: Vec<usize> = (0..self.modules.len()).collect();
+        roots.sort_by_key(|&index| self.modules.get_index(index).map(|(path, _)| path.read().clone()));
+        graph.sort(&roots)
+    }
+    /**
+        Вычисляет интерфейсы модулей в порядке зависимостей.
+        Модули цикла импортов и модули, зависящие от них или от незагруженных модулей, интерфейса не получают.
+    */
+    fn module_interfaces(&self, graph: &DependencyGraph) -> Vec<Option<ModuleInterface>> {
+        let (order, cycles) = self.sort_modules(graph);
+        let mut in_cycle = vec![false; self.modules.len()];
+        for cycle in cycles.iter() {
+            for &module in cycle.modules.iter() {
+                in_cycle[module] = true;
+            }
+        }
+        let mut interfaces: Vec<Option<ModuleInterface>> = vec![None; self.modules.len()];
+        for index in order {
+            if in_cycle[index] {
+                continue;
+            }
+            let (path, module) = match self.modules.get_index(index) {
+                Some(module) => module,
+                None => continue,
+            };
+            let (text_hash, imports, objects) = match module {
+                ResolutionModuleState::Unresolved(module) => (
+                    hash_text(module.text()),
+                    module.imports().into_iter()
+                        .map(|(path, _)| PathBuf::from_path(path))
+                        .collect(),
+                    Vec::new(),
+                ),
+                ResolutionModuleState::Cached(_, interface) => (interface.text_hash, interface.imports.clone(), interface.objects.clone()),
+                _ => continue,
+            };
+            let dependencies: Option<Vec<u64>> = graph.dependencies(index).iter()
+                .map(|dependency| interfaces[dependency.module].as_ref().map(|interface| interface.fingerprint))
+                .collect();
+            if let Some(dependencies) = dependencies {
+                let fingerprint = module_fingerprint(path.read().as_path(), text_hash, &dependencies);
+                interfaces[index] = Some(ModuleInterface { text_hash, fingerprint, imports, objects });
+            }
+        }
+        interfaces
+    }
+    fn is_resolved(&self, index: usize) -> bool {
+        match self.modules.get_index(index) {
+            Some((_, ResolutionModuleState::Resolved(_))) => true,
+            _ => false,
+        }
+    }
+    /// Формирует ошибку цикла импортов в модуле, импорт которого замыкает цикл.
+    fn import_cycle_error(&self, cycle: &DependencyCycle) -> SemanticError {
+        let paths = cycle.modules.iter()
+            .filter_map(|&index| self.modules.get_index(index))
+            .map(|(path, _)| path.read().clone())
+            .collect();
+        let mut error = SemanticError::import_cycle(cycle.pos, paths);
+        match self.modules.get_index(cycle.importer) {
+            Some((_, ResolutionModuleState::Unresolved(module))) => error.set_text(module.text().clone()),
+            Some((_, ResolutionModuleState::Cached(text, _))) => error.set_text(text.clone()),
+            _ => {}
+        }
+        error
+    }
 }
 
 impl SyncRef<ProjectContext> {
@@ -74,80 +199,146 @@ impl SyncRef<ProjectContext> {
         if self.read().get_module(path).is_none() {
             let mut project = self.write();
             project.modules.insert(SyncRef::new(path.into()), ResolutionModuleState::Requested);
-            project.new_module_requested = true;
         }
     }
-    fn load_requested_modules<S: TextSource>(&self, source: &S) -> bool {
+    /// Запрашивает модуль, которому принадлежит импортируемый элемент `path`, если у источника есть его текст.
+    fn request_imported_module<S: TextSource>(&self, mut path: Path, source: &S) {
+        while !path.is_empty() {
+            if self.read().get_module(path).is_some() {
+                return;
+            }
+            if source.get_text(path).is_some() {
+                self.request_resolving_module(path);
+                return;
+            }
+            path.pop_right();
+        }
+    }
+    /**
+        Загружает запрошенные модули.
+        Модуль, текст которого не изменился с момента сохранения кеша, не разбирается:
+        его импорты берутся из сохранённого интерфейса.
+    */
+    fn load_requested_modules<S: TextSource, C: ModuleCache>(&self, source: &S, cache: &C) -> bool {
         let mut new_modules_loaded = false;
-        let mut project = self.write();
-        for (module_path, module) in project.modules.iter_mut() {
-            let new_state = match module {
-                &mut ResolutionModuleState::Requested => {
-                    let module_path = module_path.read();
-                    match source.get_text(module_path.as_path()) {
-                        Some(text) => match UnresolvedModule::new(text) {
-                            Ok(module) => {
+        let mut imports = Vec::new();
+        {
+            let mut parser_errors = Vec::new();
+            let mut project = self.write();
+            for (module_path, module) in project.modules.iter_mut() {
+                let new_state = match module {
+                    &mut ResolutionModuleState::Requested => {
+                        let module_path = module_path.read();
+                        match source.get_text(module_path.as_path()) {
+                            Some(text) => {
                                 new_modules_loaded = true;
-                                ResolutionModuleState::Unresolved(module)
+                                match cache.get_interface(module_path.as_path()) {
+                                    Some(interface) if interface.text_hash == hash_text(&text) => {
+                                        imports.extend(interface.imports.iter().cloned());
+                                        ResolutionModuleState::Cached(text, interface.clone())
+                                    }
+                                    _ => parse_module(text, &mut parser_errors, &mut imports),
+                                }
                             }
-                            Err(errors) => ResolutionModuleState::ParseFailed(errors),
-                        },
-                        None => ResolutionModuleState::LoadFailed,
+                            None => ResolutionModuleState::LoadFailed,
+                        }
                     }
-                }
-                _ => continue,
-            };
-            replace(module, new_state);
+                    _ => continue,
+                };
+                *module = new_state;
+            }
+            project.parser_errors.append(&mut parser_errors);
+        }
+        for import in imports.iter() {
+            self.request_imported_module(import.as_path(), source);
         }
         new_modules_loaded
     }
-    fn resolution_step(&self) -> Vec<SemanticError> {
+    /**
+        Решает, какие модули из кеша можно не разрешать.
+        Модуль переиспользуется, если его отпечаток совпадает с сохранённым
+        и ни один модуль, который нужно разрешить, от него не зависит.
+        Остальные модули из кеша разбираются, чтобы быть разрешёнными вместе с изменёнными.
+    */
+    fn reuse_cached_modules(&self) -> Vec<Option<ModuleInterface>> {
         let mut project = self.write();
-        project.new_module_requested = false;
-        let mut new_module_resolved = false;
-        let mut result = Vec::new();
-        for (module_path, module) in project.modules.iter_mut() {
+        let graph = project.dependency_graph();
+        let interfaces = project.module_interfaces(&graph);
+        let mut required = vec![false; project.modules.len()];
+        for (index, module) in project.modules.values().enumerate() {
+            let is_reusable = match (module, &interfaces[index]) {
+                (ResolutionModuleState::Cached(_, cached), Some(interface)) => cached.fingerprint == interface.fingerprint,
+                _ => false,
+            };
+            if !is_reusable {
+                require_module(&graph, index, &mut required);
+            }
+        }
+        let mut parser_errors = Vec::new();
+        for (index, (_, module)) in project.modules.iter_mut().enumerate() {
+            if !required[index] {
+                continue;
+            }
             let new_state = match module {
-                ResolutionModuleState::Unresolved(module) => {
-                    let mut project_context = (module_path.clone(), self.clone());
-                    match module.resolve(&mut project_context) {
-                        Ok(module) => {
-                            new_module_resolved = true;
-                            ResolutionModuleState::Resolved(module)
-                        }
-                        Err(mut errors) => {
-                            result.append(&mut errors);
-                            continue;
-                        }
-                    }
-                }
+                ResolutionModuleState::Cached(text, _) => parse_module(text.clone(), &mut parser_errors, &mut Vec::new()),
                 _ => continue,
             };
-            replace(module, new_state);
+            *module = new_state;
         }
-        project.new_module_resolved = new_module_resolved;
-        result
+        project.parser_errors.append(&mut parser_errors);
+        interfaces
     }
-    #[inline]
-    fn need_more_resolution_steps(&self) -> bool {
-        let project = self.read();
-        project.new_module_resolved || project.new_module_requested
+    fn resolve_module(&self, index: usize) -> Vec<SemanticError> {
+        let mut project = self.write();
+        let (module_path, module) = match project.modules.get_index_mut(index) {
+            Some(module) => module,
+            None => return Vec::new(),
+        };
+        let new_state = match module {
+            ResolutionModuleState::Unresolved(module) => {
+                let project_context = (module_path.clone(), self.clone());
+                match module.resolve(&project_context) {
+                    Ok(module) => ResolutionModuleState::Resolved(module),
+                    Err(errors) => return errors,
+                }
+            }
+            _ => return Vec::new(),
+        };
+        *module = new_state;
+        Vec::new()
     }
-    pub fn get_module(&self, path: Path) -> Option<SyncRef<Module>> {
-        {
+    /**
+        Разрешает загруженные модули в порядке зависимостей, каждый не более одного раза.
+        Модули, входящие в цикл импортов или зависящие от неразрешённых модулей, пропускаются:
+        об их проблеме сообщает ошибка цикла или ошибки модуля, от которого они зависят.
+    */
+    fn resolve_modules(&self) -> Vec<SemanticError> {
+        let (graph, order, mut errors) = {
             let project = self.read();
-            match project.get_module(path) {
-                Some(module_state) => match module_state {
-                    ResolutionModuleState::Resolved(module) => return Some(module.clone()),
-                    _ => return None,
-                }
-                _ => {}
+            let graph = project.dependency_graph();
+            let (order, cycles) = project.sort_modules(&graph);
+            let errors: Vec<SemanticError> = cycles.iter()
+                .map(|cycle| project.import_cycle_error(cycle))
+                .collect();
+            (graph, order, errors)
+        };
+        for index in order {
+            let is_dependencies_resolved = {
+                let project = self.read();
+                graph.dependencies(index).iter()
+                    .all(|dependency| project.is_resolved(dependency.module))
+            };
+            if is_dependencies_resolved {
+                errors.append(&mut self.resolve_module(index));
             }
         }
-        let mut project = self.write();
-        project.modules.insert(SyncRef::new(path.into()), ResolutionModuleState::Requested);
-        project.new_module_requested = true;
-        None
+        errors
+    }
+    pub fn get_module(&self, path: Path) -> Option<SyncRef<Module>> {
+        match self.read().get_module(path) {
+            Some(ResolutionModuleState::Resolved(module)) => Some(module.clone()),
+            _ => None,
+        }
     }
     pub fn resolve_item(&self, mut path: Path) -> Option<SyncRef<Item>> {
         let mut module_path = path;
@@ -167,45 +358,147 @@ impl SyncRef<ProjectContext> {
     }
     pub fn resolve_binary_operation(&self, pos: ItemPosition, operator: BinaryOperator, left: &DataType, right: &DataType) -> Result<Arc<StdLibBinaryOperation>, SemanticError> {
         match self.read().stdlib.resolve_binary_operation(operator, left, right) {
-            Some(op) => Ok(op),
-            None => Err(SemanticError::binary_operation_cannot_be_performed(pos, operator, left.clone(), right.clone())),
+            OverloadResolution::Found(op) => Ok(op),
+            OverloadResolution::NotFound => Err(SemanticError::binary_operation_cannot_be_performed(pos, operator, left.clone(), right.clone())),
+            OverloadResolution::Ambiguous(candidates) => Err(ambiguous_overload(
+                pos,
+                format!("operation \"{}\"", operator),
+                &[left, right],
+                &candidates,
+            )),
         }
     }
     pub fn resolve_postfix_unary_operation(&self, pos: ItemPosition, operator: PostfixUnaryOperator, input: &DataType) -> Result<Arc<StdLibPostfixUnaryOperation>, SemanticError> {
         match self.read().stdlib.resolve_postfix_unary_operation(operator, input) {
-            Some(op) => Ok(op),
-            None => Err(SemanticError::postfix_unary_operation_cannot_be_performed(pos, operator, input.clone())),
+            OverloadResolution::Found(op) => Ok(op),
+            OverloadResolution::NotFound => Err(SemanticError::postfix_unary_operation_cannot_be_performed(pos, operator, input.clone())),
+            OverloadResolution::Ambiguous(candidates) => Err(ambiguous_overload(
+                pos,
+                format!("operation \"{}\"", operator),
+                &[input],
+                &candidates,
+            )),
         }
     }
     pub fn resolve_prefix_unary_operation(&self, pos: ItemPosition, operator: PrefixUnaryOperator, input: &DataType) -> Result<Arc<StdLibPrefixUnaryOperation>, SemanticError> {
         match self.read().stdlib.resolve_prefix_unary_operation(operator, input) {
-            Some(op) => Ok(op),
-            None => Err(SemanticError::prefix_unary_operation_cannot_be_performed(pos, operator, input.clone())),
+            OverloadResolution::Found(op) => Ok(op),
+            OverloadResolution::NotFound => Err(SemanticError::prefix_unary_operation_cannot_be_performed(pos, operator, input.clone())),
+            OverloadResolution::Ambiguous(candidates) => Err(ambiguous_overload(
+                pos,
+                format!("operation \"{}\"", operator),
+                &[input],
+                &candidates,
+            )),
         }
     }
     #[inline]
-    pub fn resolve_stdlib_function(&self, name: &str) -> Option<Arc<StdLibFunction>> {
-        self.read().stdlib.resolve_function(name)
+    pub fn stdlib_function_names(&self) -> Vec<String> {
+        self.read().stdlib.read().function_names()
+    }
+    /**
+        Выбирает перегрузку функции стандартной библиотеки.
+        Если ни одна перегрузка не подходит к данным аргументам, возвращает первую из них,
+        чтобы вызывающая сторона могла сообщить, что именно не так с аргументами.
+    */
+    pub fn resolve_stdlib_function(&self, pos: ItemPosition, name: &str, arguments: &[&DataType]) -> Result<Option<Arc<StdLibFunction>>, SemanticError> {
+        let stdlib = self.read().stdlib.clone();
+        match stdlib.resolve_function(name, arguments) {
+            OverloadResolution::Found(function) => Ok(Some(function)),
+            OverloadResolution::NotFound => Ok(stdlib.get_first_function(name)),
+            OverloadResolution::Ambiguous(candidates) => Err(ambiguous_overload(
+                pos,
+                format!("function \"{}\"", name),
+                arguments,
+                &candidates,
+            )),
+        }
     }
 }
 
-impl<S: TextSource> Resolve<S> for SyncRef<ProjectContext> {
-    type Result = IndexMap<SyncRef<PathBuf>, SyncRef<Module>>;
-    type Error = SemanticError;
-    fn resolve(&self, ctx: &S) -> Result<Self::Result, Vec<Self::Error>> {
-        let mut errors = Vec::new();
-        loop {
-            if !(
-                self.load_requested_modules(ctx)
-                    ||
-                    self.need_more_resolution_steps()
-            ) {
-                break;
+/// Разбирает текст модуля, собирая ошибки восстановленного разбора и пути импортов.
+fn parse_module(text: Arc<Text>, parser_errors: &mut Vec<SemanticError>, imports: &mut Vec<PathBuf>) -> ResolutionModuleState {
+    match UnresolvedModule::new(text) {
+        Ok(module) => {
+            parser_errors.extend(module.parser_errors().iter().cloned());
+            imports.extend(module.imports().into_iter().map(|(path, _)| PathBuf::from_path(path)));
+            ResolutionModuleState::Unresolved(module)
+        }
+        Err(errors) => ResolutionModuleState::ParseFailed(errors),
+    }
+}
+
+/**
+    Сообщает об объектах базы данных разных модулей, получивших одно и то же имя `[schema].[name]`.
+    Ошибка указывает на объект разрешённого модуля: переиспользованные модули были сохранены в кеш
+    после успешной компиляции, поэтому между собой не пересекаются.
+*/
+fn duplicate_object_errors(
+    mut names: HashSet<String>,
+    modules: &[(SyncRef<Module>, Vec<(String, ItemPosition, SemanticItemType)>)],
+) -> Vec<SemanticError> {
+    let mut errors = Vec::new();
+    for (module, objects) in modules.iter() {
+        let text = module.read().text().cloned();
+        for (name, pos, item_type) in objects.iter() {
+            if names.insert(name.clone()) {
+                continue;
+            }
+            let mut error = SemanticError::duplicate_definition(*pos, name.clone(), *item_type);
+            if let Some(text) = &text {
+                error.set_text(text.clone());
             }
-            errors = self.resolution_step();
+            errors.push(error);
         }
+    }
+    errors
+}
+
+/// Отмечает модуль и, транзитивно, его зависимости как требующие разрешения.
+fn require_module(graph: &DependencyGraph, index: usize, required: &mut [bool]) {
+    if required[index] {
+        return;
+    }
+    required[index] = true;
+    for dependency in graph.dependencies(index) {
+        require_module(graph, dependency.module, required);
+    }
+}
+
+fn ambiguous_overload<T: StdLibOverload>(pos: ItemPosition, subject: String, arguments: &[&DataType], candidates: &[Arc<T>]) -> SemanticError {
+    SemanticError::ambiguous_overload(
+        pos,
+        subject,
+        arguments.iter()
+            .map(|&argument| argument.clone())
+            .collect(),
+        candidates.iter()
+            .map(|candidate| candidate.signature())
+            .collect(),
+    )
+}
+
+impl SyncRef<ProjectContext> {
+    /// Разрешает проект, возвращая вместе с модулями предупреждения, найденные в них.
+    pub fn resolve_with_warnings<S: TextSource>(&self, source: &S) -> Result<(IndexMap<SyncRef<PathBuf>, SyncRef<Module>>, Vec<SemanticWarning>), Vec<SemanticError>> {
+        self.resolve_with_cache(source, &EmptyModuleCache)
+            .map(|project| (project.modules, project.warnings))
+    }
+    /// Разрешает проект, пропуская модули, которые не изменились с момента сохранения кеша `cache`.
+    pub fn resolve_with_cache<S: TextSource, C: ModuleCache>(&self, source: &S, cache: &C) -> Result<ResolvedProject, Vec<SemanticError>> {
+        while self.load_requested_modules(source, cache) {}
+        let interfaces = self.reuse_cached_modules();
+        let order = {
+            let project = self.read();
+            let graph = project.dependency_graph();
+            let (order, _) = project.sort_modules(&graph);
+            order
+        };
+        let mut errors = self.resolve_modules();
         {
             let mut project = self.write();
+            let mut parser_errors = project.parser_errors.clone();
+            errors.append(&mut parser_errors);
             for (_, module) in project.modules.iter_mut() {
                 match module {
                     ResolutionModuleState::ParseFailed(parse_errors) => {
@@ -217,14 +510,48 @@ impl<S: TextSource> Resolve<S> for SyncRef<ProjectContext> {
         }
         let project = self.read();
         if errors.is_empty() {
-            let mut result = IndexMap::new();
-            for (path, module) in project.modules.iter() {
+            let mut result = ResolvedProject {
+                modules: IndexMap::new(),
+                warnings: Vec::new(),
+                interfaces: HashMap::new(),
+                reused_modules: Vec::new(),
+                order: Vec::with_capacity(order.len()),
+            };
+            let mut interfaces = interfaces;
+            let mut cached_objects = HashSet::new();
+            let mut resolved_objects = Vec::new();
+            for index in order {
+                let (path, module) = match project.modules.get_index(index) {
+                    Some(module) => module,
+                    None => continue,
+                };
+                result.order.push(path.read().clone());
+                let mut interface = interfaces[index].take();
                 match module {
                     ResolutionModuleState::Resolved(module) => {
-                        result.insert(path.clone(), module.clone());
+                        let objects = module.schema_objects();
+                        if let Some(interface) = interface.as_mut() {
+                            interface.objects = objects.iter()
+                                .map(|(name, _, _)| name.clone())
+                                .collect();
+                        }
+                        resolved_objects.push((module.clone(), objects));
+                        result.warnings.extend(module.read().warnings().iter().cloned());
+                        result.modules.insert(path.clone(), module.clone());
+                    }
+                    ResolutionModuleState::Cached(_, cached) => {
+                        cached_objects.extend(cached.objects.iter().cloned());
+                        result.reused_modules.push(path.read().clone());
                     }
                     _ => {}
                 }
+                if let Some(interface) = interface {
+                    result.interfaces.insert(path.read().clone(), interface);
+                }
+            }
+            let errors = duplicate_object_errors(cached_objects, &resolved_objects);
+            if !errors.is_empty() {
+                return Err(errors);
             }
             Ok(result)
         } else {
@@ -232,3 +559,13 @@ impl<S: TextSource> Resolve<S> for SyncRef<ProjectContext> {
         }
     }
 }
+
+impl<S: TextSource> Resolve<S> for SyncRef<ProjectContext> {
+    type Result = IndexMap<SyncRef<PathBuf>, SyncRef<Module>>;
+    type Error = SemanticError;
+    #[inline]
+    fn resolve(&self, ctx: &S) -> Result<Self::Result, Vec<Self::Error>> {
+        self.resolve_with_warnings(ctx)
+            .map(|(modules, _)| modules)
+    }
+}
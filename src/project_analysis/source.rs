@@ -65,6 +65,8 @@ fn make_module_name(filename: &str) -> io::Result<Path> {
 
 pub const N_LANG_FILE_EXTENSIONS: &'static str = "n";
 
+pub const N_LANG_STDLIB_FILE_EXTENSION: &'static str = "nstd";
+
 impl HashMapSource {
     pub fn new() -> Self {
         HashMapSource {
@@ -89,14 +91,18 @@ impl HashMapSource {
             }),
         );
     }
+    #[inline]
     pub fn for_dir(path: &path::Path) -> io::Result<HashMapSource> {
+        HashMapSource::for_dir_with_extension(path, N_LANG_FILE_EXTENSIONS)
+    }
+    pub fn for_dir_with_extension(path: &path::Path, extension: &str) -> io::Result<HashMapSource> {
         let mut result = HashMapSource::new();
         for entry in read_dir(path)? {
             let path = entry?.path();
             if !path.is_file() { continue; }
             match path.extension() {
                 Some(ext) => match ext.to_str() {
-                    Some(ext) => if ext != N_LANG_FILE_EXTENSIONS { continue; }
+                    Some(ext) => if ext != extension { continue; }
                     None => continue,
                 },
                 None => continue,
@@ -1,28 +1,192 @@
-use helpers::SyncRef;
+use helpers::{
+    PathBuf,
+    Resolve,
+    SyncRef,
+};
+use indexmap::IndexMap;
 use language::{
     BinaryOperator,
     DataType,
+    NumberType,
     PostfixUnaryOperator,
     PrefixUnaryOperator,
+    PrimitiveDataType,
+    stdlib_items,
+};
+use lexeme_scanner::Scanner;
+use parser_basics::parse;
+use project_analysis::{
+    Module,
+    ProjectContext,
+    SemanticError,
+    Text,
+};
+use std::{
+    fmt,
+    sync::Arc,
 };
-use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct StdLib {
-    elements: Vec<StdLibElement>,
+    postfix_unary_operations: IndexMap<PostfixUnaryOperator, Vec<Arc<StdLibPostfixUnaryOperation>>>,
+    prefix_unary_operations: IndexMap<PrefixUnaryOperator, Vec<Arc<StdLibPrefixUnaryOperation>>>,
+    binary_operations: IndexMap<BinaryOperator, Vec<Arc<StdLibBinaryOperation>>>,
+    functions: IndexMap<String, Vec<Arc<StdLibFunction>>>,
+}
+
+/// Результат выбора перегрузки элемента стандартной библиотеки.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OverloadResolution<T> {
+    Found(Arc<T>),
+    NotFound,
+    Ambiguous(Vec<Arc<T>>),
+}
+
+impl<T> OverloadResolution<T> {
+    #[inline]
+    pub fn map(self, f: impl FnOnce(Arc<T>) -> Arc<T>) -> Self {
+        match self {
+            OverloadResolution::Found(x) => OverloadResolution::Found(f(x)),
+            other => other,
+        }
+    }
+}
+
+/// Общий интерфейс перегружаемых элементов стандартной библиотеки.
+pub trait StdLibOverload {
+    fn inputs(&self) -> Vec<&DataType>;
+    fn output(&self) -> &DataType;
+    fn fmt_signature(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_char('(')?;
+        let mut inputs = self.inputs().into_iter().peekable();
+        while let Some(input) = inputs.next() {
+            write!(f, "{}", input)?;
+            if inputs.peek().is_some() {
+                f.write_str(", ")?;
+            }
+        }
+        write!(f, ") -> {}", self.output())
+    }
+    fn signature(&self) -> String {
+        let mut result = String::new();
+        self.fmt_signature(&mut result)
+            .expect("Formatting into string should not fail");
+        result
+    }
+}
+
+/// Определяет, можно ли передать каждый из `arguments` на место соответствующего из `inputs`.
+fn can_cast_all(arguments: &[&DataType], inputs: &[&DataType]) -> bool {
+    (arguments.len() == inputs.len())
+        && arguments.iter()
+        .zip(inputs.iter())
+        .all(|(argument, input)| argument.can_cast(input))
+}
+
+/// Считает аргументы, тип которых совпадает с типом входа перегрузки по виду, знаку и заполнению нулями.
+fn count_matching_attributes(arguments: &[&DataType], inputs: &[&DataType]) -> usize {
+    arguments.iter()
+        .zip(inputs.iter())
+        .filter(|(argument, input)| match (argument, input) {
+            (
+                DataType::Primitive(PrimitiveDataType::Number(argument)),
+                DataType::Primitive(PrimitiveDataType::Number(input)),
+            ) => argument.has_same_attributes(input),
+            (argument, input) => argument == input,
+        })
+        .count()
+}
+
+/**
+    Выбирает наиболее подходящую перегрузку.
+    Предпочтение отдаётся точному совпадению типов, затем - наиболее узкому расширению.
+    Среди взаимозаменяемых наиболее узких перегрузок выбирается та, у которой больше входов совпадает с аргументами по знаку и заполнению нулями.
+    Если выбрать одну перегрузку так не удаётся, выбор считается неоднозначным.
+    Как и в T-SQL, элементы стандартной библиотеки принимают обнуляемые аргументы вместо их базовых типов.
+*/
+fn resolve_overload<T: StdLibOverload>(candidates: Option<&Vec<Arc<T>>>, arguments: &[&DataType]) -> OverloadResolution<T> {
+    let candidates = match candidates {
+        Some(candidates) => candidates,
+        None => return OverloadResolution::NotFound,
+    };
+    let arguments: Vec<&DataType> = arguments.iter()
+        .map(|argument| argument.without_nullable())
+        .collect();
+    let arguments = arguments.as_slice();
+    let viable: Vec<(&Arc<T>, Vec<&DataType>)> = candidates.iter()
+        .map(|candidate| (candidate, candidate.inputs()))
+        .filter(|(_, inputs)| can_cast_all(arguments, inputs))
+        .collect();
+    if viable.is_empty() {
+        return OverloadResolution::NotFound;
+    }
+    if let Some((exact, _)) = viable.iter().find(|(_, inputs)| inputs.as_slice() == arguments) {
+        return OverloadResolution::Found((*exact).clone());
+    }
+    let narrowest: Vec<&(&Arc<T>, Vec<&DataType>)> = viable.iter()
+        .filter(|(_, inputs)| !viable.iter().any(|(_, other)| {
+            can_cast_all(other, inputs) && !can_cast_all(inputs, other)
+        }))
+        .collect();
+    let ambiguous = |candidates: Vec<&(&Arc<T>, Vec<&DataType>)>| OverloadResolution::Ambiguous(
+        candidates.into_iter()
+            .map(|(candidate, _)| (*candidate).clone())
+            .collect()
+    );
+    let (_, first_inputs) = match narrowest.first() {
+        Some(first) => *first,
+        None => return ambiguous(viable.iter().collect()),
+    };
+    let interchangeable = narrowest.iter()
+        .all(|(_, inputs)| can_cast_all(inputs, first_inputs) && can_cast_all(first_inputs, inputs));
+    if !interchangeable {
+        return ambiguous(narrowest);
+    }
+    let best_score = narrowest.iter()
+        .map(|(_, inputs)| count_matching_attributes(arguments, inputs))
+        .max()
+        .unwrap_or(0);
+    let mut best: Vec<&(&Arc<T>, Vec<&DataType>)> = narrowest.into_iter()
+        .filter(|(_, inputs)| count_matching_attributes(arguments, inputs) == best_score)
+        .collect();
+    if best.len() == 1 {
+        let (candidate, _) = best.remove(0);
+        OverloadResolution::Found((*candidate).clone())
+    } else {
+        ambiguous(best)
+    }
 }
 
 impl StdLib {
     #[inline]
     pub fn new() -> Self {
         StdLib {
-            elements: Vec::new(),
+            postfix_unary_operations: IndexMap::new(),
+            prefix_unary_operations: IndexMap::new(),
+            binary_operations: IndexMap::new(),
+            functions: IndexMap::new(),
         }
     }
 
-    #[inline]
     pub fn reg_element(&mut self, element: StdLibElement) {
-        self.elements.push(element)
+        match element {
+            StdLibElement::PostfixUnaryOperation(op) => self.postfix_unary_operations
+                .entry(op.operator)
+                .or_insert_with(Vec::new)
+                .push(op),
+            StdLibElement::PrefixUnaryOperation(op) => self.prefix_unary_operations
+                .entry(op.operator)
+                .or_insert_with(Vec::new)
+                .push(op),
+            StdLibElement::BinaryOperation(op) => self.binary_operations
+                .entry(op.operator)
+                .or_insert_with(Vec::new)
+                .push(op),
+            StdLibElement::Function(function) => self.functions
+                .entry(function.name.clone())
+                .or_insert_with(Vec::new)
+                .push(function),
+        }
     }
     #[inline]
     pub fn reg_postfix_unary_operation(&mut self, operation: StdLibPostfixUnaryOperation) {
@@ -40,75 +204,171 @@ impl StdLib {
     pub fn reg_function(&mut self, function: StdLibFunction) {
         self.reg_element(StdLibElement::Function(Arc::new(function)))
     }
-    pub fn resolve_postfix_unary_operation(&self, operator: PostfixUnaryOperator, input: &DataType) -> Option<&Arc<StdLibPostfixUnaryOperation>> {
-        for element in self.elements.iter() {
-            if let StdLibElement::PostfixUnaryOperation(op) = element {
-                if (op.operator == operator) && input.can_cast(&op.input) {
-                    return Some(op);
+    /**
+        Выполняет разбор текста объявлений и регистрирует все объявленные в нём элементы.
+        Типы объявлений разрешаются в контексте уже зарегистрированных элементов.
+    */
+    pub fn reg_source(&mut self, text: Arc<Text>) -> Result<(), Vec<SemanticError>> {
+        let elements = self.resolve_source(text.as_ref())
+            .map_err(|mut errors| {
+                for error in errors.iter_mut() {
+                    error.set_text(text.clone());
                 }
-            }
+                errors
+            })?;
+        for element in elements {
+            self.reg_element(element);
         }
-        None
+        Ok(())
     }
-    pub fn resolve_prefix_unary_operation(&self, operator: PrefixUnaryOperator, input: &DataType) -> Option<&Arc<StdLibPrefixUnaryOperation>> {
-        for element in self.elements.iter() {
-            if let StdLibElement::PrefixUnaryOperation(op) = element {
-                if (op.operator == operator) && input.can_cast(&op.input) {
-                    return Some(op);
-                }
-            }
-        }
-        None
+    fn resolve_source(&self, text: &Text) -> Result<Vec<StdLibElement>, Vec<SemanticError>> {
+        let tokens = match Scanner::scan(text.text.as_str()) {
+            Ok(tokens) => tokens,
+            Err(error) => return SemanticError::scanner_error(error).into_err_vec(),
+        };
+        let items = parse(tokens.as_slice(), stdlib_items)
+            .map_err(|errors| errors.extract_into_vec()
+                .into_iter()
+                .map(SemanticError::parser_error)
+                .collect::<Vec<_>>()
+            )?;
+        let module = SyncRef::new(Module::new(
+            SyncRef::new(PathBuf::new("::")),
+            ProjectContext::new(SyncRef::new(self.clone())),
+        ));
+        items.resolve(&module)
     }
-    pub fn resolve_binary_operation(&self, operator: BinaryOperator, left: &DataType, right: &DataType) -> Option<&Arc<StdLibBinaryOperation>> {
-        for element in self.elements.iter() {
-            if let StdLibElement::BinaryOperation(op) = element {
-                if (op.operator == operator) && left.can_cast(&op.left) && right.can_cast(&op.right) {
-                    return Some(op);
-                }
-            }
-        }
-        None
+    #[inline]
+    pub fn resolve_postfix_unary_operation(&self, operator: PostfixUnaryOperator, input: &DataType) -> OverloadResolution<StdLibPostfixUnaryOperation> {
+        resolve_overload(self.postfix_unary_operations.get(&operator), &[input])
     }
-    pub fn resolve_function(&self, name: &str) -> Option<&Arc<StdLibFunction>> {
-        for element in self.elements.iter() {
-            if let StdLibElement::Function(function) = element {
-                if function.name == name {
-                    return Some(function);
-                }
-            }
+    #[inline]
+    pub fn resolve_prefix_unary_operation(&self, operator: PrefixUnaryOperator, input: &DataType) -> OverloadResolution<StdLibPrefixUnaryOperation> {
+        resolve_overload(self.prefix_unary_operations.get(&operator), &[input])
+    }
+    pub fn resolve_binary_operation(&self, operator: BinaryOperator, left: &DataType, right: &DataType) -> OverloadResolution<StdLibBinaryOperation> {
+        resolve_overload(self.binary_operations.get(&operator), &[left, right])
+            .map(|op| match left.promote(right) {
+                Some(widened) => widen_binary_operation(&op, left, right, widened),
+                None => op,
+            })
+    }
+    #[inline]
+    pub fn resolve_function(&self, name: &str, arguments: &[&DataType]) -> OverloadResolution<StdLibFunction> {
+        resolve_overload(self.functions.get(name), arguments)
+    }
+    /// Возвращает имена всех функций стандартной библиотеки.
+    #[inline]
+    pub fn function_names(&self) -> Vec<String> {
+        self.functions.keys()
+            .cloned()
+            .collect()
+    }
+    /// Возвращает все перегрузки функции с данным именем.
+    #[inline]
+    pub fn get_functions(&self, name: &str) -> &[Arc<StdLibFunction>] {
+        match self.functions.get(name) {
+            Some(functions) => functions.as_slice(),
+            None => &[],
         }
-        None
     }
 }
 
 impl SyncRef<StdLib> {
     #[inline]
-    pub fn resolve_postfix_unary_operation(&self, operator: PostfixUnaryOperator, input: &DataType) -> Option<Arc<StdLibPostfixUnaryOperation>> {
+    pub fn resolve_postfix_unary_operation(&self, operator: PostfixUnaryOperator, input: &DataType) -> OverloadResolution<StdLibPostfixUnaryOperation> {
         self.read()
             .resolve_postfix_unary_operation(operator, input)
-            .cloned()
     }
     #[inline]
-    pub fn resolve_prefix_unary_operation(&self, operator: PrefixUnaryOperator, input: &DataType) -> Option<Arc<StdLibPrefixUnaryOperation>> {
+    pub fn resolve_prefix_unary_operation(&self, operator: PrefixUnaryOperator, input: &DataType) -> OverloadResolution<StdLibPrefixUnaryOperation> {
         self.read()
             .resolve_prefix_unary_operation(operator, input)
-            .cloned()
     }
     #[inline]
-    pub fn resolve_binary_operation(&self, operator: BinaryOperator, left: &DataType, right: &DataType) -> Option<Arc<StdLibBinaryOperation>> {
+    pub fn resolve_binary_operation(&self, operator: BinaryOperator, left: &DataType, right: &DataType) -> OverloadResolution<StdLibBinaryOperation> {
         self.read()
             .resolve_binary_operation(operator, left, right)
-            .cloned()
     }
     #[inline]
-    pub fn resolve_function(&self, name: &str) -> Option<Arc<StdLibFunction>> {
+    pub fn resolve_function(&self, name: &str, arguments: &[&DataType]) -> OverloadResolution<StdLibFunction> {
         self.read()
-            .resolve_function(name)
+            .resolve_function(name, arguments)
+    }
+    #[inline]
+    pub fn get_first_function(&self, name: &str) -> Option<Arc<StdLibFunction>> {
+        self.read()
+            .get_functions(name)
+            .first()
             .cloned()
     }
 }
 
+/**
+    Подставляет расширенный тип операндов в найденную операцию.
+    Тип результата меняется только у операций, которые возвращают значение того же типа, что и их операнды.
+*/
+fn widen_binary_operation(
+    op: &Arc<StdLibBinaryOperation>,
+    left: &DataType,
+    right: &DataType,
+    widened: DataType,
+) -> Arc<StdLibBinaryOperation> {
+    if (op.left != op.output) || (op.right != op.output) || !widened.can_cast(&op.output) {
+        return op.clone();
+    }
+    let output = match arithmetic_decimal_output(op.operator, left, right, &widened) {
+        Some(output) => output,
+        None => widened.clone(),
+    };
+    Arc::new(StdLibBinaryOperation {
+        operator: op.operator,
+        left: widened.clone(),
+        right: widened,
+        output,
+    })
+}
+
+/// Вычисляет точность и масштаб результата арифметической операции над `decimal` по правилам T-SQL.
+fn arithmetic_decimal_output(
+    operator: BinaryOperator,
+    left: &DataType,
+    right: &DataType,
+    widened: &DataType,
+) -> Option<DataType> {
+    let unsigned = match widened.as_primitive()? {
+        PrimitiveDataType::Number(NumberType::Decimal { unsigned, .. }) => unsigned,
+        _ => return None,
+    };
+    let decimal_size = |data_type: &DataType| match data_type.as_primitive() {
+        Some(PrimitiveDataType::Number(number)) => number.decimal_size(),
+        _ => None,
+    };
+    let (p1, s1) = decimal_size(left)?;
+    let (p2, s2) = decimal_size(right)?;
+    let (i1, i2) = (p1.saturating_sub(s1), p2.saturating_sub(s2));
+    let (precision, scale) = match operator {
+        BinaryOperator::Plus |
+        BinaryOperator::Minus => {
+            let scale = s1.max(s2);
+            (i1.max(i2) + scale + 1, scale)
+        }
+        BinaryOperator::Times => (p1 + p2 + 1, s1 + s2),
+        BinaryOperator::Divide => {
+            let scale = 6.max(s1 + p2 + 1);
+            (i1 + s2 + scale, scale)
+        }
+        BinaryOperator::Mod => {
+            let scale = s1.max(s2);
+            (i1.min(i2) + scale, scale)
+        }
+        _ => return None,
+    };
+    Some(DataType::Primitive(PrimitiveDataType::Number(
+        NumberType::decimal(precision, scale, unsigned)
+    )))
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum StdLibElement {
     PostfixUnaryOperation(Arc<StdLibPostfixUnaryOperation>),
@@ -180,6 +440,7 @@ pub struct StdLibFunction {
     pub output: DataType,
     pub is_aggregate: bool,
     pub is_lite_weight: bool,
+    pub sql_name: Option<String>,
 }
 
 impl StdLibFunction {
@@ -191,6 +452,7 @@ impl StdLibFunction {
             output: DataType::Void,
             is_aggregate: false,
             is_lite_weight: false,
+            sql_name: None,
         }
     }
     #[inline]
@@ -213,4 +475,44 @@ impl StdLibFunction {
         self.is_aggregate = true;
         self.lite_weight()
     }
+    #[inline]
+    pub fn sql_name(mut self, sql_name: String) -> Self {
+        self.sql_name = Some(sql_name);
+        self
+    }
+    #[inline]
+    pub fn get_sql_name(&self) -> &str {
+        match &self.sql_name {
+            Some(sql_name) => sql_name.as_str(),
+            None => self.name.as_str(),
+        }
+    }
+}
+
+impl StdLibOverload for StdLibPostfixUnaryOperation {
+    #[inline]
+    fn inputs(&self) -> Vec<&DataType> { vec![&self.input] }
+    #[inline]
+    fn output(&self) -> &DataType { &self.output }
+}
+
+impl StdLibOverload for StdLibPrefixUnaryOperation {
+    #[inline]
+    fn inputs(&self) -> Vec<&DataType> { vec![&self.input] }
+    #[inline]
+    fn output(&self) -> &DataType { &self.output }
+}
+
+impl StdLibOverload for StdLibBinaryOperation {
+    #[inline]
+    fn inputs(&self) -> Vec<&DataType> { vec![&self.left, &self.right] }
+    #[inline]
+    fn output(&self) -> &DataType { &self.output }
+}
+
+impl StdLibOverload for StdLibFunction {
+    #[inline]
+    fn inputs(&self) -> Vec<&DataType> { self.arguments.iter().collect() }
+    #[inline]
+    fn output(&self) -> &DataType { &self.output }
 }
@@ -0,0 +1,144 @@
+use std::{
+    env,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::{
+        Command,
+        Output,
+    },
+};
+
+const WARNINGS_MODULE: &'static str = "
+    pub fn f(x: integer, y: integer): integer {
+        return x;
+    }
+";
+
+fn project_dir(name: &str, modules: &[(&str, &str)]) -> PathBuf {
+    let dir = env::temp_dir().join(format!("n_lang_cli_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("Cannot create project directory");
+    for &(name, text) in modules {
+        fs::write(dir.join(format!("{}.n", name)), text).expect("Cannot write module");
+    }
+    dir
+}
+
+fn run_cached(compiler: &Path, dir: &PathBuf) -> String {
+    let output = Command::new(compiler)
+        .arg(dir)
+        .arg(dir.join("out.ts"))
+        .arg(dir.join("out.sql"))
+        .output()
+        .expect("Cannot run compiler");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout));
+    fs::read_to_string(dir.join("out.sql")).expect("Cannot read output")
+}
+
+fn run(dir: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_n_lang"))
+        .arg(dir)
+        .arg(dir.join("out.ts"))
+        .arg(dir.join("out.sql"))
+        .arg("--no-cache")
+        .args(args)
+        .output()
+        .expect("Cannot run compiler")
+}
+
+#[test]
+fn warnings_are_printed_without_failing() {
+    let dir = project_dir("warnings", &[("a", WARNINGS_MODULE)]);
+    let output = run(&dir, &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("warning: unused argument y"), "{}", stdout);
+    assert!(dir.join("out.sql").exists());
+    fs::remove_dir_all(&dir).expect("Cannot remove project directory");
+}
+
+#[test]
+fn denied_warnings_fail_the_build() {
+    let dir = project_dir("deny_warnings", &[("a", WARNINGS_MODULE)]);
+    let output = run(&dir, &["--deny-warnings"]);
+    assert!(!output.status.success());
+    let text = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+    assert!(text.contains("error: unused argument y"), "{}", text);
+    assert!(!dir.join("out.sql").exists());
+    fs::remove_dir_all(&dir).expect("Cannot remove project directory");
+}
+
+#[test]
+fn errors_are_printed_as_json_lines() {
+    let dir = project_dir("json_errors", &[("a", "
+pub fn f(): integer { return x; }
+")]);
+    let output = run(&dir, &["--message-format", "json"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "{\"code\":\"E0010\",\"message\":\"x is not in the scope\",\"severity\":\"error\",\"file\":\"a.n\",\
+         \"begin\":{\"line\":2,\"column\":30},\"end\":{\"line\":2,\"column\":31},\"help\":null,\"related\":[]}\n"
+    );
+    fs::remove_dir_all(&dir).expect("Cannot remove project directory");
+}
+
+#[test]
+fn warnings_are_printed_as_json_lines() {
+    let dir = project_dir("json_warnings", &[("a", WARNINGS_MODULE)]);
+    let expected = |severity: &str| format!(
+        "{{\"code\":\"W0002\",\"message\":\"unused argument y\",\"severity\":\"{}\",\"file\":\"a.n\",\
+         \"begin\":{{\"line\":2,\"column\":26}},\"end\":{{\"line\":2,\"column\":27}},\"help\":null,\"related\":[]}}\n",
+        severity,
+    );
+    let output = run(&dir, &["--message-format", "json"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), expected("warning"));
+    let output = run(&dir, &["--message-format", "json", "--deny-warnings"]);
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), expected("error"));
+    fs::remove_dir_all(&dir).expect("Cannot remove project directory");
+}
+
+#[test]
+fn related_labels_are_printed_as_json() {
+    let dir = project_dir("json_related", &[("a", "
+struct A { x: integer }
+struct A { y: integer }
+")]);
+    let output = run(&dir, &["--message-format", "json"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "{\"code\":\"E0005\",\"message\":\"there is already declared definition name A\",\"severity\":\"error\",\"file\":\"a.n\",\
+         \"begin\":{\"line\":3,\"column\":8},\"end\":{\"line\":3,\"column\":9},\"help\":null,\
+         \"related\":[{\"message\":\"first defined here\",\"file\":\"a.n\",\"begin\":{\"line\":2,\"column\":8},\"end\":{\"line\":2,\"column\":9}}]}\n"
+    );
+    fs::remove_dir_all(&dir).expect("Cannot remove project directory");
+}
+
+#[test]
+fn cache_of_another_build_is_not_used() {
+    let dir = project_dir("cache_build", &[("a", "pub fn f(): integer { return 1; }")]);
+    let compiler = Path::new(env!("CARGO_BIN_EXE_n_lang"));
+    assert!(run_cached(compiler, &dir).contains("[a::f]"));
+    let cache_dir = dir.join(".n_lang_cache");
+    for entry in fs::read_dir(&cache_dir).expect("Cannot read cache directory") {
+        let path = entry.expect("Cannot read cache entry").path();
+        let text = fs::read_to_string(&path).expect("Cannot read cache file");
+        fs::write(&path, text.replace("[a::f]", "[a::stale]")).expect("Cannot write cache file");
+    }
+    assert!(run_cached(compiler, &dir).contains("[a::stale]"));
+    let mut another_build = fs::read(compiler).expect("Cannot read compiler");
+    another_build.push(0);
+    let another_compiler = dir.join("another_n_lang");
+    fs::write(&another_compiler, another_build).expect("Cannot write compiler");
+    fs::set_permissions(&another_compiler, fs::metadata(compiler).expect("Cannot read compiler").permissions())
+        .expect("Cannot make compiler executable");
+    let sql = run_cached(&another_compiler, &dir);
+    assert!(sql.contains("[a::f]") && !sql.contains("[a::stale]"), "{}", sql);
+    fs::remove_dir_all(&dir).expect("Cannot remove project directory");
+}
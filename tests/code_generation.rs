@@ -0,0 +1,1233 @@
+extern crate indexmap;
+extern crate n_lang;
+
+use indexmap::IndexMap;
+use n_lang::{
+    code_generation::{
+        DatabaseProject,
+        ProjectCache,
+        RPCModule,
+    },
+    helpers::{
+        Path,
+        PathBuf,
+        Resolve,
+        SyncRef,
+    },
+    language::{
+        DataType,
+        JoinType,
+    },
+    lexeme_scanner::ItemPosition,
+    project_analysis::{
+        HashMapSource,
+        Item,
+        Module,
+        ProjectContext,
+        SemanticError,
+        SemanticErrorKind,
+        SemanticItemType,
+        SemanticWarning,
+        SemanticWarningKind,
+        SemanticWarnings,
+        Severity,
+        StdLib,
+        Text,
+    },
+};
+use std::sync::Arc;
+
+const MS_SQL_STD_LIB_SOURCE: &'static str = include_str!("../src/cli/stdlib.n");
+
+fn get_stdlib() -> StdLib {
+    let mut stdlib = StdLib::new();
+    if let Err(errors) = stdlib.reg_source(Arc::new(Text::new("stdlib.n", MS_SQL_STD_LIB_SOURCE))) {
+        for error in errors {
+            println!("{}", error);
+        }
+        panic!("Cannot load stdlib");
+    }
+    stdlib
+}
+
+fn get_source(modules: &[(&str, &str)]) -> HashMapSource {
+    let mut source = HashMapSource::new();
+    for &(name, text) in modules {
+        source.simple_insert(Path::new(name, "::"), &format!("{}.n", name), text);
+    }
+    source
+}
+
+fn request_modules(modules: &[(&str, &str)]) -> SyncRef<ProjectContext> {
+    let project = ProjectContext::new(SyncRef::new(get_stdlib()));
+    for &(name, _) in modules {
+        project.request_resolving_module(Path::new(name, "::"));
+    }
+    project
+}
+
+fn resolve(modules: &[(&str, &str)]) -> Result<IndexMap<SyncRef<PathBuf>, SyncRef<Module>>, Vec<SemanticError>> {
+    request_modules(modules).resolve(&get_source(modules))
+}
+
+fn resolve_ok(modules: &[(&str, &str)]) -> IndexMap<SyncRef<PathBuf>, SyncRef<Module>> {
+    match resolve(modules) {
+        Ok(project) => project,
+        Err(errors) => {
+            for error in errors {
+                println!("{}", error);
+            }
+            panic!("Resolved some errors");
+        }
+    }
+}
+
+fn resolve_errors(modules: &[(&str, &str)]) -> Vec<SemanticError> {
+    match resolve(modules) {
+        Ok(_) => panic!("Project should not be resolved"),
+        Err(errors) => errors,
+    }
+}
+
+fn generate_sql(modules: &[(&str, &str)]) -> String {
+    DatabaseProject::new(&resolve_ok(modules))
+        .generate_string()
+        .expect("Cannot generate output for database")
+}
+
+fn generate_typescript(modules: &[(&str, &str)]) -> String {
+    RPCModule::top(&resolve_ok(modules))
+        .generate_string()
+        .expect("Cannot generate output for RPC")
+}
+
+#[test]
+fn math_operators_are_translated_to_tsql() {
+    let sql = generate_sql(&[("a", "
+        pub fn calc(x: integer, y: integer): integer {
+            return x ** 2 + (x div y) + (x & y);
+        }
+    ")]);
+    assert!(sql.contains("POWER(@x, 2)"), "{}", sql);
+    assert!(sql.contains("CAST(( @x / @y ) AS int)"), "{}", sql);
+    assert!(sql.contains("( @x & @y )"), "{}", sql);
+}
+
+#[test]
+fn regular_expressions_are_lowered_to_like_patterns() {
+    let sql = generate_sql(&[("a", r#"
+        pub fn matches(x: text): boolean {
+            return x regexp "^ab.c\\.d%.*$";
+        }
+    "#)]);
+    assert!(sql.contains("( @x LIKE 'ab_c.d[%]%' )"), "{}", sql);
+}
+
+#[test]
+fn escaped_regular_expressions_are_decoded() {
+    let sql = generate_sql(&[("a", r#"
+        pub fn quoted(x: text): boolean {
+            return x regexp "^\"a\\\\b\\.";
+        }
+        pub fn any(x: text): boolean {
+            return x regexp "a\.b";
+        }
+    "#)]);
+    assert!(sql.contains(r#"( @x LIKE '"a\b.%' )"#), "{}", sql);
+    assert!(sql.contains("( @x LIKE '%a_b%' )"), "{}", sql);
+}
+
+#[test]
+fn interval_and_sounds_like_are_translated_to_tsql() {
+    let sql = generate_sql(&[("a", "
+        pub fn check(x: integer, name: text, other: text): boolean {
+            return x is in 1..10 && name sounds like other;
+        }
+    ")]);
+    assert!(sql.contains("( @x BETWEEN 1 AND 10 )"), "{}", sql);
+    assert!(sql.contains("( SOUNDEX(@name) = SOUNDEX(@other) )"), "{}", sql);
+}
+
+const PRODUCTS_TABLE: &'static str = "
+    table Products {
+        #[primary_key]
+        id: integer,
+        price: integer,
+    }
+";
+
+fn subquery_module(subquery: &str) -> String {
+    format!("{}
+        pub fn expensive(): {{id: integer}}[] {{
+            return select p.id from Products p where p.price > {};
+        }}
+    ", PRODUCTS_TABLE, subquery)
+}
+
+#[test]
+fn scalar_subquery_is_generated_inside_expression() {
+    let sql = generate_sql(&[("a", &subquery_module("(select max(q.price) from Products q where q.id > 1)"))]);
+    let expected = "WHERE ( [p].price > ( SELECT max([q].price) AS component0 FROM [a::Products] AS [q] WHERE ( [q].id > 1 ) ) )";
+    assert!(sql.contains(expected), "{}", sql);
+}
+
+#[test]
+fn scalar_subquery_should_have_one_column() {
+    let errors = resolve_errors(&[("a", &subquery_module("(select q.id, q.price from Products q)"))]);
+    assert_eq!(errors.len(), 1);
+    match &errors[0].kind {
+        SemanticErrorKind::SelectWithWrongColumnCount { expected: 1, got: 2 } => {}
+        other => panic!("Unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn subquery_of_several_rows_is_not_scalar() {
+    let errors = resolve_errors(&[("a", &subquery_module("(select q.price from Products q where q.id = 1)"))]);
+    assert_eq!(errors.len(), 1);
+    match &errors[0].kind {
+        SemanticErrorKind::NotAllowedInside { .. } => {}
+        other => panic!("Unexpected error {:?}", other),
+    }
+    resolve_ok(&[("a", &subquery_module("(select q.price from Products q where q.id = 1 limit 1)"))]);
+}
+
+#[test]
+fn nullable_subquery_is_cast_to_base_type_explicitly() {
+    let errors = resolve_errors(&[("a", &format!("{}
+        pub fn first_price(): integer {{
+            return (select q.price from Products q limit 1);
+        }}
+    ", PRODUCTS_TABLE))]);
+    assert_eq!(errors.len(), 1);
+    match &errors[0].kind {
+        SemanticErrorKind::CannotCastType { .. } => {}
+        other => panic!("Unexpected error {:?}", other),
+    }
+    resolve_ok(&[("a", &format!("{}
+        pub fn first_price(): integer {{
+            return cast((select q.price from Products q limit 1), integer);
+        }}
+
+        pub fn max_price(): integer {{
+            return (select max(q.price) from Products q);
+        }}
+    ", PRODUCTS_TABLE))]);
+}
+
+const JOIN_TABLES: &'static str = "
+    table Users {
+        #[primary_key]
+        id: integer,
+        name: varchar(50),
+    }
+
+    table Orders {
+        #[primary_key]
+        id: integer,
+        user_id: integer,
+    }
+";
+
+fn join_module(query: &str) -> String {
+    format!("{}
+        pub fn names(): {{name: varchar(50)}}[] {{
+            return {};
+        }}
+    ", JOIN_TABLES, query)
+}
+
+#[test]
+fn inner_join_is_generated_with_on_condition() {
+    let sql = generate_sql(&[("a", &join_module("select u.name from Users u inner join Orders o on o.user_id = u.id"))]);
+    let expected = "
+                [a::Users] AS [u]
+                INNER JOIN
+                [a::Orders] AS [o]
+                ON ( [o].user_id = [u].id )
+";
+    assert!(sql.contains(expected), "{}", sql);
+}
+
+#[test]
+fn bare_join_is_inner_join() {
+    let sql = generate_sql(&[("a", &join_module("select u.name from Users u join Orders o on o.user_id = u.id"))]);
+    let expected = "
+                [a::Users] AS [u]
+                INNER JOIN
+                [a::Orders] AS [o]
+                ON ( [o].user_id = [u].id )
+";
+    assert!(sql.contains(expected), "{}", sql);
+    let errors = resolve_errors(&[("a", &join_module("select u.name from Users u join Orders o"))]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, SemanticErrorKind::JoinConditionRequired { join_type: JoinType::Inner });
+}
+
+#[test]
+fn outer_joins_require_on_condition() {
+    for &(join, join_type) in &[("left join", JoinType::Left), ("right join", JoinType::Right), ("inner join", JoinType::Inner), ("full join", JoinType::Full)] {
+        let errors = resolve_errors(&[("a", &join_module(&format!("select u.name from Users u {} Orders o", join)))]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, SemanticErrorKind::JoinConditionRequired { join_type });
+    }
+}
+
+#[test]
+fn cross_join_does_not_allow_on_condition() {
+    let errors = resolve_errors(&[("a", &join_module("select u.name from Users u cross join Orders o on o.user_id = u.id"))]);
+    assert_eq!(errors.len(), 1);
+    match &errors[0].kind {
+        SemanticErrorKind::NotAllowedInside { .. } => {}
+        other => panic!("Unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn join_condition_should_be_boolean() {
+    let errors = resolve_errors(&[("a", &join_module("select u.name from Users u inner join Orders o on o.user_id"))]);
+    assert_eq!(errors.len(), 1);
+    match &errors[0].kind {
+        SemanticErrorKind::ExpectedExpressionOfAnotherType { .. } => {}
+        other => panic!("Unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn table_valued_function_is_joined_as_data_source() {
+    let sql = generate_sql(&[("a", "
+        table Users {
+            #[primary_key]
+            id: integer,
+            age: integer,
+        }
+
+        #[lite_weight]
+        fn older(since: integer): {id: integer}[] {
+            return select u.id from Users u where u.age > since;
+        }
+
+        pub fn names(): {id: integer}[] {
+            return select o.id from older(18) as o inner join Users u on u.id = o.id;
+        }
+    ")]);
+    let expected = "
+                dbo.[a::older](18) AS [o]
+                INNER JOIN
+                [a::Users] AS [u]
+                ON ( [u].id = [o].id )
+";
+    assert!(sql.contains(expected), "{}", sql);
+}
+
+#[test]
+fn scalar_function_is_not_a_data_source() {
+    let errors = resolve_errors(&[("a", "
+        #[lite_weight]
+        fn one(x: integer): integer {
+            return x;
+        }
+
+        pub fn names(): {id: integer}[] {
+            return select o.id from one(18) as o;
+        }
+    ")]);
+    assert_eq!(errors.len(), 1);
+    match &errors[0].kind {
+        SemanticErrorKind::NotAllowedHere { feature } => assert_eq!(*feature, "function call without array result"),
+        other => panic!("Unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn view_is_created_from_its_query() {
+    let sql = generate_sql(&[("a", "
+        table Orders {
+            #[primary_key]
+            id: integer,
+            amount: integer,
+        }
+
+        pub view Expensive = select o.id from Orders o where o.amount > 100;
+    ")]);
+    let expected = "
+CREATE OR ALTER VIEW [a::Expensive] AS
+    SELECT
+        [o].id AS id
+    FROM
+        [a::Orders] AS [o]
+    WHERE ( [o].amount > 100 )
+GO
+";
+    assert!(sql.contains(expected), "{}", sql);
+}
+
+#[test]
+fn constants_are_inlined_into_functions() {
+    let sql = generate_sql(&[("a", "
+        const LIMIT: integer = 32;
+
+        pub fn limit(): integer {
+            return LIMIT;
+        }
+    ")]);
+    assert!(sql.contains("RETURN 32;"), "{}", sql);
+}
+
+const NOMINAL_MODULE: &'static str = "
+    #[nominal]
+    type UserId = integer;
+
+    #[nominal]
+    type OrderId = integer;
+
+    type Customer = UserId;
+
+    type Count = integer;
+";
+
+fn data_type(project: &IndexMap<SyncRef<PathBuf>, SyncRef<Module>>, name: &str) -> DataType {
+    let module = project.values().next().expect("Project should contain a module");
+    let item = module.get_item(Path::new(name, "::"), &mut Vec::new())
+        .unwrap_or_else(|| panic!("{} is not found", name));
+    DataType::Reference(item)
+}
+
+#[test]
+fn nominal_types_with_same_body_are_different() {
+    let project = resolve_ok(&[("a", NOMINAL_MODULE)]);
+    assert_ne!(data_type(&project, "UserId"), data_type(&project, "OrderId"));
+    assert_ne!(data_type(&project, "UserId"), data_type(&project, "Count"));
+}
+
+#[test]
+fn nominal_arguments_should_have_the_same_type() {
+    let functions = "
+        fn user_name(id: UserId): integer {
+            return 1;
+        }
+    ";
+    resolve_ok(&[("a", &format!("{}{}
+        pub fn customer_name(id: Customer): integer {{
+            return user_name(id);
+        }}
+    ", NOMINAL_MODULE, functions))]);
+    let errors = resolve_errors(&[("a", &format!("{}{}
+        pub fn order_name(id: OrderId): integer {{
+            return user_name(id);
+        }}
+    ", NOMINAL_MODULE, functions))]);
+    assert_eq!(errors.len(), 1);
+}
+
+const AUDIT_STRUCT: &'static str = "
+    struct Audit {
+        #[auto_increment]
+        version: integer,
+        created_by: varchar(20),
+    }
+";
+
+#[test]
+fn spread_fields_are_embedded_with_attributes() {
+    let sql = generate_sql(&[("a", &format!("{}
+        table Orders {{
+            #[primary_key]
+            id: integer,
+            ..Audit,
+        }}
+    ", AUDIT_STRUCT))]);
+    let expected = "CREATE TABLE [a::Orders] (
+    [id] int,
+    [version] int IDENTITY,
+    [created_by] nvarchar(20),
+    PRIMARY KEY ([id])
+)
+";
+    assert!(sql.contains(expected), "{}", sql);
+}
+
+#[test]
+fn spread_field_duplicates_are_reported() {
+    let errors = resolve_errors(&[("a", &format!("{}
+        table Orders {{
+            #[primary_key]
+            version: integer,
+            ..Audit,
+        }}
+    ", AUDIT_STRUCT))]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, SemanticErrorKind::DuplicateDefinition {
+        name: "version".to_string(),
+        item_type: SemanticItemType::Field,
+    });
+}
+
+fn destructuring_module(statements: &str) -> String {
+    format!("
+        fn pair(): (integer, varchar(10)) {{
+            return (1, \"a\");
+        }}
+
+        pub fn use_them(p: {{id: integer, name: varchar(10)}}): integer {{
+            {}
+            return a + id;
+        }}
+    ", statements)
+}
+
+#[test]
+fn destructured_values_are_assigned_directly() {
+    let sql = generate_sql(&[("a", &destructuring_module("
+            let (a, b) := pair();
+            let { id, name } := p;
+    "))]);
+    let expected = "
+    SELECT
+            @a = t.[component0],
+            @b = t.[component1]
+        FROM
+            dbo.[a::pair]()
+        AS t;
+    SET @id = @p#id;
+    SET @name = @p#name;
+";
+    assert!(sql.contains(expected), "{}", sql);
+}
+
+#[test]
+fn overlapped_destructuring_assignment_uses_temp_variables() {
+    let sql = generate_sql(&[("a", &destructuring_module("
+            let (a, b) := pair();
+            let { id, name } := p;
+            (a, id) := (id, a);
+    "))]);
+    let expected = "
+    SET @a = @t#component0;
+    SET @id = @t#component1;
+";
+    assert!(sql.contains(expected), "{}", sql);
+}
+
+#[test]
+fn tuple_pattern_should_match_tuple_length() {
+    let errors = resolve_errors(&[("a", &destructuring_module("
+            let (a, b, c) := pair();
+            let { id, name } := p;
+    "))]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, SemanticErrorKind::ValueListWithWrongLength { expected: 2, got: 3 });
+}
+
+#[test]
+fn structure_pattern_should_use_existing_fields() {
+    let errors = resolve_errors(&[("a", &destructuring_module("
+            let (a, b) := pair();
+            let { id, age } := p;
+    "))]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, SemanticErrorKind::WrongProperty { property: "age".to_string() });
+}
+
+#[test]
+fn trigger_is_created_on_its_table() {
+    let sql = generate_sql(&[("a", "
+        table Orders {
+            #[primary_key]
+            id: integer,
+            amount: integer,
+        }
+
+        trigger audit on Orders after insert, update {
+            let x := 1;
+        }
+    ")]);
+    let expected = "
+CREATE OR ALTER TRIGGER [a::audit]
+    ON [a::Orders]
+    AFTER INSERT, UPDATE
+AS BEGIN
+";
+    assert!(sql.contains(expected), "{}", sql);
+}
+
+fn position_of(sql: &str, statement: &str) -> usize {
+    match sql.find(statement) {
+        Some(position) => position,
+        None => panic!("{} is not found in:\n{}", statement, sql),
+    }
+}
+
+const SCHEMA_ORDERS: &'static str = "
+    #![schema(shop)]
+
+    table Orders {
+        #[primary_key]
+        id: integer,
+        amount: integer,
+    }
+";
+
+#[test]
+fn objects_of_module_with_schema_are_created_in_it() {
+    let sql = generate_sql(&[
+        ("a", "
+            #![schema(shop)]
+
+            use b::Orders;
+
+            view Big = select o.id from Orders o where o.amount > 100;
+
+            trigger audit on Orders after insert {
+                let x := 1;
+            }
+        "),
+        ("b", SCHEMA_ORDERS),
+    ]);
+    let schema = position_of(&sql, "IF SCHEMA_ID('shop') IS NULL EXEC('CREATE SCHEMA [shop]');");
+    assert!(schema < position_of(&sql, "CREATE TABLE [shop].[Orders]"), "{}", sql);
+    assert!(sql.contains("CREATE OR ALTER VIEW [shop].[Big] AS"), "{}", sql);
+    assert!(sql.contains("CREATE OR ALTER TRIGGER [shop].[audit]\n    ON [shop].[Orders]\n    AFTER INSERT"), "{}", sql);
+}
+
+const GRANTS_MODULE: &'static str = "
+    #![grant(clerk)]
+
+    table Orders {
+        #[primary_key]
+        id: integer,
+        amount: integer,
+    }
+
+    #[grant(manager)]
+    pub fn order_count(x: integer): integer {
+        return x;
+    }
+
+    #[lite_weight]
+    pub fn big_orders(): {id: integer}[] {
+        return select o.id from Orders o where o.amount > 100;
+    }
+";
+
+#[test]
+fn grants_are_inherited_from_module() {
+    let sql = generate_sql(&[("a", GRANTS_MODULE)]);
+    let expected = "
+IF DATABASE_PRINCIPAL_ID('clerk') IS NULL CREATE ROLE [clerk];
+IF DATABASE_PRINCIPAL_ID('manager') IS NULL CREATE ROLE [manager];
+GO
+
+GRANT SELECT ON [a::Orders] TO [clerk];
+GRANT SELECT ON dbo.[a::big_orders] TO [clerk];
+GRANT EXECUTE ON dbo.[a::order_count] TO [clerk];
+GRANT EXECUTE ON dbo.[a::order_count] TO [manager];
+GO
+";
+    assert!(sql.contains(expected), "{}", sql);
+}
+
+#[test]
+fn permissions_report_lists_functions_of_each_role() {
+    let report = DatabaseProject::new(&resolve_ok(&[("a", GRANTS_MODULE)]))
+        .generate_permissions_report()
+        .expect("Cannot generate permissions report");
+    assert_eq!(report, "clerk:
+    a::big_orders
+    a::order_count
+manager:
+    a::order_count
+");
+}
+
+fn resolve_warnings(modules: &[(&str, &str)]) -> Vec<SemanticWarningKind> {
+    resolve_ok(modules).values()
+        .flat_map(|module| module.read().warnings().iter()
+            .map(|warning| warning.kind.clone())
+            .collect::<Vec<_>>())
+        .collect()
+}
+
+const HELPER_MODULE: (&'static str, &'static str) = ("b", "
+    pub fn helper(): integer {
+        return 1;
+    }
+");
+
+#[test]
+fn warnings_are_collected_without_errors() {
+    let mut warnings = resolve_warnings(&[HELPER_MODULE, ("a", "
+        use b::helper;
+
+        pub fn f(x: integer, y: integer): integer {
+            let unused := 1;
+            let z := x;
+            if x > 0 {
+                let z := 2;
+                return z;
+            };
+            return z;
+            return 3;
+        }
+    ")]);
+    warnings.sort_by_key(|warning| warning.to_string());
+    assert_eq!(warnings, vec![
+        SemanticWarningKind::UnreachableStatement,
+        SemanticWarningKind::UnusedArgument { name: "y".to_string() },
+        SemanticWarningKind::UnusedImport { name: "helper".to_string() },
+        SemanticWarningKind::UnusedVariable { name: "unused".to_string() },
+        SemanticWarningKind::ShadowedVariable { name: "z".to_string() },
+    ]);
+}
+
+#[test]
+fn used_items_produce_no_warnings() {
+    let warnings = resolve_warnings(&[HELPER_MODULE, ("a", "
+        use b::helper;
+
+        pub fn f(x: integer): integer {
+            let z := x + helper();
+            return z;
+        }
+    ")]);
+    assert_eq!(warnings, vec![]);
+}
+
+#[test]
+fn denied_warnings_become_errors() {
+    let mut warnings = SemanticWarnings {
+        warnings: vec![SemanticWarning::new(ItemPosition::default(), SemanticWarningKind::UnreachableStatement)],
+    };
+    assert!(warnings.to_string().contains("warning: unreachable statement"), "{}", warnings);
+    warnings.deny();
+    assert_eq!(warnings.warnings[0].severity, Severity::Error);
+    assert!(warnings.to_string().contains("error: unreachable statement"), "{}", warnings);
+}
+
+#[test]
+fn parser_reports_errors_of_every_broken_item() {
+    let errors = resolve_errors(&[("a", "struct A { x: integer, y: , }
+pub fn f(x: integer): integer { return x; }
+struct B { z: }
+")]);
+    let lines: Vec<usize> = errors.iter().map(|error| error.pos.begin.line).collect();
+    assert!(lines.contains(&1), "{:?}", lines);
+    assert!(lines.contains(&3), "{:?}", lines);
+    assert!(!lines.contains(&2), "{:?}", lines);
+}
+
+fn suggestion_module(statement: &str) -> String {
+    format!("
+        use b::helper;
+
+        struct Point {{
+            left: integer,
+            top: integer,
+        }}
+
+        pub fn f(total: integer, p: Point): integer {{
+            {}
+            return total + p.top + helper();
+        }}
+    ", statement)
+}
+
+fn assert_help(statement: &str, help: &str) {
+    let errors = resolve_errors(&[HELPER_MODULE, ("a", &suggestion_module(statement))]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].help, Some(help.to_string()), "{}", errors[0]);
+    assert!(errors[0].to_string().contains(&format!("help: {}", help)), "{}", errors[0]);
+}
+
+#[test]
+fn similar_variable_is_suggested() {
+    assert_help("let a := totl;", "did you mean total?");
+}
+
+#[test]
+fn similar_item_is_suggested() {
+    assert_help("let a := helpr();", "did you mean helper?");
+}
+
+#[test]
+fn similar_item_of_imported_module_is_suggested() {
+    assert_help("let a := b::helpe();", "did you mean helper?");
+}
+
+#[test]
+fn similar_stdlib_function_is_suggested() {
+    assert_help("let a := ab(total);", "did you mean abs?");
+}
+
+#[test]
+fn similar_field_is_suggested() {
+    assert_help("let a := p.lef;", "did you mean left?");
+}
+
+#[test]
+fn nothing_is_suggested_for_unlike_names() {
+    let errors = resolve_errors(&[HELPER_MODULE, ("a", &suggestion_module("let a := something_else;"))]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].help, None);
+}
+
+fn label_at(error: &SemanticError, index: usize) -> (usize, usize, &str) {
+    let label = &error.labels[index];
+    (label.pos.begin.line, label.pos.begin.column, label.message.as_str())
+}
+
+#[test]
+fn duplicate_item_points_to_first_definition() {
+    let errors = resolve_errors(&[("a", "struct A { x: integer }
+struct A { y: integer }")]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].labels.len(), 1);
+    assert_eq!(label_at(&errors[0], 0), (1, 8, "first defined here"));
+}
+
+#[test]
+fn duplicate_argument_points_to_first_definition() {
+    let errors = resolve_errors(&[("a", "pub fn f(x: integer, x: integer): integer {
+    return x;
+}")]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].labels.len(), 1);
+    assert_eq!(label_at(&errors[0], 0), (1, 10, "first defined here"));
+}
+
+#[test]
+fn cast_error_points_to_variable_declaration() {
+    let errors = resolve_errors(&[("a", "pub fn f(x: integer): integer {
+    let a: small integer := 1;
+    a := x;
+    return a;
+}")]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].labels.len(), 1);
+    assert_eq!(label_at(&errors[0], 0), (2, 9, "variable a declared here with type integer(16)"));
+    let text = errors[0].to_string();
+    assert!(text.contains("   2 |     let a: small integer := 1;"), "{}", text);
+    assert!(text.contains("-- variable a declared here with type integer(16)"), "{}", text);
+}
+
+#[test]
+fn write_failures_are_reported_as_generate_errors() {
+    struct FailingWriter;
+
+    impl std::fmt::Write for FailingWriter {
+        fn write_str(&mut self, _: &str) -> std::fmt::Result {
+            Err(std::fmt::Error)
+        }
+    }
+
+    let project = resolve_ok(&[("a", "pub fn f(): integer { return 1; }")]);
+    let error = DatabaseProject::new(&project)
+        .generate(&mut FailingWriter)
+        .expect_err("Generation into a failing writer should fail");
+    assert_eq!(error.message, "cannot write generated code");
+}
+
+#[test]
+fn shifts_are_computed_in_big_integers() {
+    let sql = generate_sql(&[("a", "
+        pub fn shift(x: tiny integer): tiny integer {
+            return x << 9;
+        }
+    ")]);
+    assert!(sql.contains("CAST(( CAST(@x AS bigint) * POWER(CAST(2 AS bigint), 9) ) AS tinyint)"), "{}", sql);
+}
+
+#[test]
+fn logical_xor_compares_predicates() {
+    let sql = generate_sql(&[("a", "
+        pub fn either(x: integer, y: integer): boolean {
+            return x > 1 xor y > 2;
+        }
+    ")]);
+    assert!(sql.contains("( ( ( @x > 1 ) AND NOT ( @y > 2 ) ) OR ( NOT ( @x > 1 ) AND ( @y > 2 ) ) )"), "{}", sql);
+}
+
+#[test]
+fn character_class_escapes_are_not_lowered_to_like_patterns() {
+    for pattern in &["\\\\d", "\\\\w", "\\\\s"] {
+        let errors = resolve_errors(&[("a", &format!("
+            pub fn matches(x: text): boolean {{
+                return x regexp \"^a{}\";
+            }}
+        ", pattern))]);
+        assert_eq!(errors.len(), 1);
+        match &errors[0].kind {
+            SemanticErrorKind::NotSupportedYet { .. } => {}
+            other => panic!("Unexpected error {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn compound_casts_require_the_same_fields() {
+    for (source, target) in &[
+        ("(a, b)", "(small integer)"),
+        ("(a)", "(small integer, small integer)"),
+        ("wide", "{x: small integer}"),
+        ("narrow", "{x: small integer, y: small integer}"),
+    ] {
+        let errors = resolve_errors(&[("a", &format!("
+            fn convert(a: tiny integer, b: tiny integer, wide: {{x: tiny integer, y: tiny integer}}, narrow: {{x: tiny integer}}) {{
+                let c := cast({}, {});
+            }}
+        ", source, target))]);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        match &errors[0].kind {
+            SemanticErrorKind::CannotConvertType { .. } => {}
+            other => panic!("Unexpected error {:?}", other),
+        }
+    }
+    resolve_ok(&[("a", "
+        fn convert(a: tiny integer, b: tiny integer, wide: {x: tiny integer, y: tiny integer}) {
+            let c := cast(wide, {y: small integer, x: small integer});
+            let d := cast((a, b), (small integer, small integer));
+        }
+    ")]);
+}
+
+#[test]
+fn views_are_created_after_tables_of_all_modules() {
+    let sql = generate_sql(&[
+        ("a", "
+            use b::Orders;
+
+            view Big = select o.id from Orders o where o.amount > 100;
+        "),
+        ("b", "
+            table Orders {
+                #[primary_key]
+                id: integer,
+                amount: integer,
+            }
+        "),
+    ]);
+    assert!(position_of(&sql, "CREATE TABLE [b::Orders]") < position_of(&sql, "CREATE OR ALTER VIEW [a::Big]"), "{}", sql);
+}
+
+#[test]
+fn views_follow_module_dependencies() {
+    let sql = generate_sql(&[
+        ("a", "
+            use b::Expensive;
+
+            view Huge = select e.id from Expensive e where e.amount > 1000;
+        "),
+        ("b", "
+            use c::Orders;
+
+            view Expensive = select o.id, o.amount from Orders o where o.amount > 100;
+        "),
+        ("c", "
+            table Orders {
+                #[primary_key]
+                id: integer,
+                amount: integer,
+            }
+        "),
+    ]);
+    assert!(position_of(&sql, "CREATE OR ALTER VIEW [b::Expensive]") < position_of(&sql, "CREATE OR ALTER VIEW [a::Huge]"), "{}", sql);
+}
+
+#[test]
+fn views_follow_functions_they_use() {
+    let sql = generate_sql(&[("a", "
+        table Users {
+            #[primary_key]
+            id: integer,
+            age: integer,
+        }
+
+        #[lite_weight]
+        fn older(since: integer): {id: integer}[] {
+            return select u.id from Users u where u.age > since;
+        }
+
+        view Old = select o.id from older(18) as o;
+    ")]);
+    assert!(position_of(&sql, "FUNCTION dbo.[a::older]") < position_of(&sql, "CREATE OR ALTER VIEW [a::Old]"), "{}", sql);
+}
+
+#[test]
+fn views_follow_views_they_use() {
+    let sql = generate_sql(&[("a", "
+        table Users {
+            #[primary_key]
+            id: integer,
+            age: integer,
+        }
+
+        view Verified = select u.id, u.age from Users u where u.id > 0;
+
+        view Adults = select v.id from Verified v where v.age > 18;
+    ")]);
+    assert!(position_of(&sql, "CREATE OR ALTER VIEW [a::Verified]") < position_of(&sql, "CREATE OR ALTER VIEW [a::Adults]"), "{}", sql);
+}
+
+#[test]
+fn triggers_are_created_after_tables_and_functions_of_all_modules() {
+    let sql = generate_sql(&[
+        ("a", "
+            use b::Orders;
+            use b::touch;
+
+            trigger audit on Orders after insert, update {
+                touch(1);
+            }
+        "),
+        ("b", "
+            table Orders {
+                #[primary_key]
+                id: integer,
+                amount: integer,
+            }
+
+            pub fn touch(x: integer): integer {
+                return x;
+            }
+        "),
+    ]);
+    let trigger = position_of(&sql, "CREATE OR ALTER TRIGGER [a::audit]\n    ON [b::Orders]");
+    assert!(position_of(&sql, "CREATE TABLE [b::Orders]") < trigger, "{}", sql);
+    assert!(position_of(&sql, "dbo.[b::touch]") < trigger, "{}", sql);
+}
+
+const HALF_MODULE: &'static str = "
+    const HALF: integer = 7 / 2;
+
+    pub fn half(): integer {
+        return HALF;
+    }
+";
+
+#[test]
+fn constants_are_folded_with_integer_division() {
+    let sql = generate_sql(&[("a", HALF_MODULE)]);
+    assert!(sql.contains("RETURN 3;"), "{}", sql);
+    let typescript = generate_typescript(&[("a", HALF_MODULE)]);
+    assert!(typescript.contains("export const HALF: number = 3;"), "{}", typescript);
+}
+
+#[test]
+fn constants_are_folded_through_other_constants() {
+    let typescript = generate_typescript(&[("a", "
+        const BASE: integer = 0x10;
+        const MASK: integer = (BASE << 2) - 1;
+        const RATIO: double = cast(BASE, double) / 5;
+        const ENABLED: boolean = MASK > BASE and !false;
+    ")]);
+    assert!(typescript.contains("export const MASK: number = 63;"), "{}", typescript);
+    assert!(typescript.contains("export const RATIO: number = 3.2;"), "{}", typescript);
+    assert!(typescript.contains("export const ENABLED: boolean = true;"), "{}", typescript);
+}
+
+#[test]
+fn decimal_constants_are_folded_exactly() {
+    let typescript = generate_typescript(&[("a", "
+        const SUM: double = 0.1 + 0.2;
+        const SCALED: double = 0.5 * 3;
+        const EIGHTH: double = 1.0 / 8;
+        const ROUNDED: decimal(3, 1) = cast(1.25, decimal(3, 1));
+    ")]);
+    assert!(typescript.contains("export const SUM: number = 0.3;"), "{}", typescript);
+    assert!(typescript.contains("export const SCALED: number = 1.5;"), "{}", typescript);
+    assert!(typescript.contains("export const EIGHTH: number = 0.125;"), "{}", typescript);
+    assert!(typescript.contains("export const ROUNDED: number = 1.3;"), "{}", typescript);
+}
+
+#[test]
+fn inexact_constant_division_is_reported() {
+    let errors = resolve_errors(&[("a", "
+        const THIRD: double = 1.0 / 3;
+    ")]);
+    assert_eq!(errors.len(), 1);
+    match &errors[0].kind {
+        SemanticErrorKind::NotSupportedYet { .. } => {}
+        other => panic!("Unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn unrepresentable_constant_is_reported() {
+    let errors = resolve_errors(&[("a", "
+        const HUGE: double = 10.0 ** 100;
+    ")]);
+    assert_eq!(errors.len(), 1);
+    match &errors[0].kind {
+        SemanticErrorKind::ConstantOutOfRange { .. } => {}
+        other => panic!("Unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn constant_cast_out_of_range_is_reported() {
+    let errors = resolve_errors(&[("a", "
+        const SMALL: integer = cast(300, tiny integer);
+    ")]);
+    assert_eq!(errors.len(), 1);
+    match &errors[0].kind {
+        SemanticErrorKind::ConstantOutOfRange { value, .. } => assert_eq!(value, "300"),
+        other => panic!("Unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn constant_division_by_zero_is_reported() {
+    let errors = resolve_errors(&[("a", "
+        const NOTHING: integer = 1 / (2 - 2);
+    ")]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, SemanticErrorKind::DivisionByZero);
+}
+
+#[test]
+fn nominal_type_is_equal_to_itself() {
+    let project = resolve_ok(&[("a", NOMINAL_MODULE)]);
+    assert_eq!(data_type(&project, "UserId"), data_type(&project, "UserId"));
+    assert_eq!(DataType::nullable(data_type(&project, "UserId")), DataType::nullable(data_type(&project, "UserId")));
+}
+
+#[test]
+fn aliases_are_equal_to_their_types() {
+    let project = resolve_ok(&[("a", NOMINAL_MODULE)]);
+    assert_eq!(data_type(&project, "Customer"), data_type(&project, "UserId"));
+    assert_eq!(data_type(&project, "UserId"), data_type(&project, "Customer"));
+    assert_eq!(data_type(&project, "Count"), data_type(&project, "Count"));
+    assert_ne!(data_type(&project, "Customer"), data_type(&project, "OrderId"));
+}
+
+#[test]
+fn trigger_should_be_in_schema_of_its_table() {
+    let errors = resolve_errors(&[
+        ("a", "
+            use b::Orders;
+
+            trigger audit on Orders after insert {
+                let x := 1;
+            }
+        "),
+        ("b", SCHEMA_ORDERS),
+    ]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, SemanticErrorKind::TriggerSchemaMismatch {
+        trigger_schema: None,
+        table_schema: Some("shop".to_string()),
+    });
+}
+
+const SCHEMA_ORDERS_COPY: &'static str = "
+    #![schema(shop)]
+
+    table Items {
+        #[primary_key]
+        id: integer,
+    }
+
+    fn Orders(): integer {
+        return 1;
+    }
+";
+
+fn assert_duplicate_orders(errors: &[SemanticError]) {
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, SemanticErrorKind::DuplicateDefinition {
+        name: "[shop].[Orders]".to_string(),
+        item_type: SemanticItemType::Function,
+    });
+}
+
+#[test]
+fn objects_with_the_same_name_in_one_schema_are_reported() {
+    assert_duplicate_orders(&resolve_errors(&[("a", SCHEMA_ORDERS), ("b", SCHEMA_ORDERS_COPY)]));
+}
+
+#[test]
+fn objects_with_the_same_name_in_different_schemas_are_allowed() {
+    let copy = SCHEMA_ORDERS_COPY.replace("schema(shop)", "schema(store)");
+    let sql = generate_sql(&[("a", SCHEMA_ORDERS), ("b", &copy)]);
+    assert!(sql.contains("CREATE TABLE [shop].[Orders]"), "{}", sql);
+    assert!(sql.contains("CREATE OR ALTER FUNCTION [store].[Orders]"), "{}", sql);
+}
+
+#[test]
+fn objects_of_cached_modules_are_checked_for_duplicates() {
+    let first = [("a", SCHEMA_ORDERS)];
+    let resolved = request_modules(&first).resolve_with_cache(&get_source(&first), &ProjectCache::new(0))
+        .unwrap_or_else(|_| panic!("Resolved some errors"));
+    let (_, _, cache) = ProjectCache::new(0).generate(&resolved)
+        .expect("Cannot generate output");
+    let second = [("a", SCHEMA_ORDERS), ("b", SCHEMA_ORDERS_COPY)];
+    match request_modules(&second).resolve_with_cache(&get_source(&second), &cache) {
+        Ok(_) => panic!("Project should not be resolved"),
+        Err(errors) => assert_duplicate_orders(&errors),
+    }
+}
+
+fn assert_parser_error_at(error: &SemanticError, line: usize, column: usize) {
+    match &error.kind {
+        SemanticErrorKind::ParserError { .. } => {}
+        other => panic!("Unexpected error {:?}", other),
+    }
+    assert_eq!((error.pos.begin.line, error.pos.begin.column), (line, column), "{}", error);
+}
+
+#[test]
+fn parser_error_points_to_the_furthest_position() {
+    let errors = resolve_errors(&[("a", "struct A { x: integer, y: , }")]);
+    assert_eq!(errors.len(), 1);
+    assert_parser_error_at(&errors[0], 1, 27);
+}
+
+#[test]
+fn parser_recovers_at_item_boundaries() {
+    let errors = resolve_errors(&[("a", "struct A { x: integer, y: , }
+pub fn f(x: integer): integer { return x; }
+struct B { z: }
+")]);
+    assert_eq!(errors.len(), 2);
+    assert_parser_error_at(&errors[0], 1, 27);
+    assert_parser_error_at(&errors[1], 3, 15);
+}
+
+#[test]
+fn parser_recovers_at_statement_boundaries() {
+    let errors = resolve_errors(&[("a", "pub fn f(x: integer): integer {
+    let y := 1 1;
+    let z := x;
+    return z z;
+}
+")]);
+    assert_eq!(errors.len(), 2);
+    assert_parser_error_at(&errors[0], 2, 16);
+    assert_parser_error_at(&errors[1], 4, 14);
+}
+
+#[test]
+fn references_to_unparsed_items_are_not_reported() {
+    let errors = resolve_errors(&[("a", "struct A { x: integer, y: , }
+pub fn f(a: A): integer { return 1; }
+pub fn g(a: B): integer { return 1; }
+")]);
+    assert_eq!(errors.len(), 2);
+    assert_parser_error_at(&errors[1], 1, 27);
+    match &errors[0].kind {
+        SemanticErrorKind::UnresolvedItem { path } => assert_eq!(path.the_only(), Some("B")),
+        other => panic!("Unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn module_reference_is_not_a_database_object() {
+    let project = resolve_ok(&[("a", "pub struct A { x: integer }")]);
+    let module = project.values().next().expect("Project should contain a module").clone();
+    let item = Item::module_ref(module);
+    let mut name = String::new();
+    let error = item.fmt_object_name(&mut name)
+        .expect_err("Module reference should not have an object name");
+    assert_eq!(error.message, "module references cannot be database objects");
+    assert_eq!(item.get_path(), Ok(PathBuf::from_path(Path::new("a", "::"))));
+}
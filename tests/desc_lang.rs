@@ -130,7 +130,7 @@ fn struct_and_tuple_bodies_parses_correctly() {
         FieldAST {
                 attributes: vec![],
             field_type: DataTypeAST::Compound(CompoundDataTypeAST::Structure(vec![
-                (Identifier::new("a"), FieldAST {
+                StructFieldAST::Field(Identifier::new("a"), FieldAST {
                         attributes: vec![],
                     field_type: DataTypeAST::Primitive(PrimitiveDataType::Number(NumberType::Integer {
                             integer_type: IntegerType::Normal,
@@ -139,7 +139,7 @@ fn struct_and_tuple_bodies_parses_correctly() {
                         })),
                         position: ItemPosition::new("(boolean, {", "a: integer"),
                     }),
-                (Identifier::new("b"), FieldAST {
+                StructFieldAST::Field(Identifier::new("b"), FieldAST {
                         attributes: vec![],
                     field_type: DataTypeAST::Primitive(PrimitiveDataType::Number(NumberType::Float {
                             size: None,
@@ -293,7 +293,7 @@ fn assert_module_of_complex_number_struct_and_wave_signals_table(module: &Vec<Mo
     assert_eq!(module[0].attributes.len(), 1);
     assert_eq!(module[0].attributes[0].name, "derive");
     assert_eq!(module[0].attributes[0].arguments, Some(vec![Identifier::new("Hash")]));
-    match_it!(&module[0].value, &ModuleDefinitionValue::DataType(DataTypeDefinition { ref name, ref body }) => {
+    match_it!(&module[0].value, &ModuleDefinitionValue::DataType(DataTypeDefinition { ref name, ref body, nominal: false }) => {
         assert_eq!(name, "Complex");
         body.assert("{ real: double, imag: float }");
     });
@@ -392,7 +392,7 @@ fn simple_submodule_parses_correctly() {
     });
     assert_eq!(result[3].public, true);
     assert_eq!(result[3].attributes.len(), 0);
-    match_it!(&result[3].value, &ModuleDefinitionValue::DataType(DataTypeDefinition { ref name, ref body }) => {
+    match_it!(&result[3].value, &ModuleDefinitionValue::DataType(DataTypeDefinition { ref name, ref body, nominal: false }) => {
         assert_eq!(name, "CachedSignal");
         body.assert("(Signals, Complex)");
     });
@@ -5,9 +5,11 @@ use indexmap::IndexMap;
 use n_lang::{
     code_generation::{
         DatabaseProject,
+        ProjectCache,
         RPCModule,
     },
     helpers::{
+        Path as ModulePath,
         PathBuf,
         Resolve,
         SyncRef,
@@ -19,16 +21,24 @@ use n_lang::{
         PrimitiveDataType,
     },
     project_analysis::{
+        hash_text,
         HashMapSource,
         Module,
         ProjectContext,
         SemanticError,
+        SemanticErrorKind,
         StdLib,
         StdLibBinaryOperation,
         StdLibFunction,
+        Text,
     },
 };
-use std::path::Path;
+use std::{
+    env,
+    fs,
+    path::Path,
+    process,
+};
 
 fn get_test_stdlib() -> StdLib {
     let mut stdlib = StdLib::new();
@@ -142,9 +152,12 @@ fn resolve_project() -> (DatabaseProject, RPCModule) {
 #[test]
 fn dir_resolve() {
     let (db, rpc) = resolve_project();
+    let db_code = db.generate_string().expect("Cannot generate output for database");
+    let rpc_code = rpc.generate_string().expect("Cannot generate output for RPC");
 
-    println!("{}", db.generate_string().expect("Cannot generate output for database"));
-    println!("{}", rpc.generate_string().expect("Cannot generate output for RPC"));
+    assert!(db_code.contains("RETURN CAST(@user#person_info#age AS nvarchar(3));"), "{}", db_code);
+    assert!(db_code.contains("(SELECT CAST(@a AS smallint) as [component0], CAST(@b AS nvarchar) as [component1])"), "{}", db_code);
+    assert!(rpc_code.contains("user_age_text"), "{}", rpc_code);
 }
 
 #[test]
@@ -172,3 +185,95 @@ fn item_order_should_always_be_the_same() {
         );
     }
 }
+
+#[test]
+fn import_cycle_should_be_reported_once() {
+    let mut source = HashMapSource::new();
+    source.simple_insert(ModulePath::new("a", "::"), "a.n", "use b::g;\npub fn f(x: small integer): small integer { return g(x); }");
+    source.simple_insert(ModulePath::new("b", "::"), "b.n", "use a::f;\npub fn g(x: small integer): small integer { return x; }");
+    let project = ProjectContext::new(SyncRef::new(get_test_stdlib()));
+    project.request_resolving_module(ModulePath::new("a", "::"));
+    let errors = match project.resolve(&source) {
+        Ok(_) => panic!("Import cycle was not detected"),
+        Err(errors) => errors,
+    };
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].kind,
+        SemanticErrorKind::ImportCycle {
+            cycle: vec![
+                PathBuf::from_path(ModulePath::new("a", "::")),
+                PathBuf::from_path(ModulePath::new("b", "::")),
+                PathBuf::from_path(ModulePath::new("a", "::")),
+            ],
+        },
+    );
+}
+
+#[test]
+fn unchanged_modules_should_be_reused_from_cache() {
+    let source = get_sources("dir_resolve");
+    let resolve_with_cache = |cache: &ProjectCache| {
+        let project = ProjectContext::new(SyncRef::new(get_test_stdlib()));
+        for (module_path, _) in source.texts() {
+            project.request_resolving_module(module_path.as_path());
+        }
+        let resolved = match project.resolve_with_cache(&source, cache) {
+            Ok(resolved) => resolved,
+            Err(_) => panic!("Resolved some errors"),
+        };
+        let (db, rpc, cache) = cache.generate(&resolved)
+            .expect("Cannot generate output");
+        let db_code = db.generate_string()
+            .expect("Cannot generate output for database");
+        let rpc_code = rpc.generate_string()
+            .expect("Cannot generate output for RPC");
+        (resolved.reused_modules.len(), db_code, rpc_code, cache)
+    };
+
+    let (first_reused, first_db_code, first_rpc_code, cache) = resolve_with_cache(&ProjectCache::new(0));
+    assert_eq!(first_reused, 0);
+    assert_eq!(cache.len(), source.texts().count());
+
+    let (second_reused, second_db_code, second_rpc_code, _) = resolve_with_cache(&cache);
+    assert_eq!(second_reused, source.texts().count());
+    assert_eq!(first_db_code, second_db_code);
+    assert_eq!(first_rpc_code, second_rpc_code);
+}
+
+#[test]
+fn text_hash_should_not_depend_on_build() {
+    assert_eq!(hash_text(&Text::new("a.n", "a")), 0xaf63dc4c8601ec8c);
+}
+
+#[test]
+fn cache_of_another_format_should_be_ignored() {
+    let source = get_sources("dir_resolve");
+    let project = ProjectContext::new(SyncRef::new(get_test_stdlib()));
+    for (module_path, _) in source.texts() {
+        project.request_resolving_module(module_path.as_path());
+    }
+    let resolved = match project.resolve_with_cache(&source, &ProjectCache::new(0)) {
+        Ok(resolved) => resolved,
+        Err(_) => panic!("Resolved some errors"),
+    };
+    let (_, _, cache) = ProjectCache::new(0).generate(&resolved)
+        .expect("Cannot generate output");
+
+    let dir = env::temp_dir().join(format!("n_lang_cache_format_{}", process::id()));
+    cache.save(&dir).expect("Cannot save cache");
+    assert_eq!(ProjectCache::load(&dir, 0).len(), cache.len());
+    assert_eq!(ProjectCache::load(&dir, 1).len(), 0);
+
+    for entry in fs::read_dir(&dir).expect("Cannot read cache directory") {
+        let path = entry.expect("Cannot read cache entry").path();
+        let text = fs::read_to_string(&path).expect("Cannot read cache file");
+        let mut fields = text.splitn(3, '\t');
+        let header = fields.next().expect("Cache file should have a header");
+        fields.next().expect("Cache file should have a format version");
+        let rest = fields.next().expect("Cache file should have a salt");
+        fs::write(&path, format!("{}\t1\t{}", header, rest)).expect("Cannot write cache file");
+    }
+    assert_eq!(ProjectCache::load(&dir, 0).len(), 0);
+    fs::remove_dir_all(&dir).expect("Cannot remove cache directory");
+}
@@ -0,0 +1,298 @@
+extern crate n_lang;
+
+use n_lang::{
+    code_generation::DatabaseProject,
+    helpers::{
+        Path,
+        Resolve,
+        SyncRef,
+    },
+    language::{
+        BinaryOperator,
+        DataType,
+        NumberType,
+        PrefixUnaryOperator,
+        PrimitiveDataType,
+    },
+    project_analysis::{
+        HashMapSource,
+        OverloadResolution,
+        ProjectContext,
+        SemanticErrorKind,
+        StdLib,
+        StdLibBinaryOperation,
+        StdLibFunction,
+        Text,
+    },
+};
+use std::sync::Arc;
+
+fn integer(size: u8, unsigned: bool) -> NumberType {
+    NumberType::Integer { size, unsigned, zerofill: false }
+}
+
+fn decimal(precision: u32, scale: u32) -> NumberType {
+    NumberType::Decimal { size: Some((precision, Some(scale))), unsigned: false, zerofill: false }
+}
+
+fn number(number: NumberType) -> DataType {
+    DataType::Primitive(PrimitiveDataType::Number(number))
+}
+
+fn found<T>(resolution: OverloadResolution<T>) -> Arc<T> {
+    match resolution {
+        OverloadResolution::Found(x) => x,
+        OverloadResolution::NotFound => panic!("Overload should be found"),
+        OverloadResolution::Ambiguous(_) => panic!("Overload should not be ambiguous"),
+    }
+}
+
+#[test]
+fn numbers_cast_through_kinds() {
+    assert!(NumberType::Bit { size: Some(4) }.can_cast(&integer(8, true)));
+    assert!(integer(3, true).can_cast(&decimal(10, 2)));
+    assert!(!integer(32, false).can_cast(&decimal(10, 2)));
+    assert!(decimal(10, 2).can_cast(&NumberType::Float { size: None, double: true }));
+    assert!(!NumberType::Float { size: None, double: false }.can_cast(&decimal(65, 30)));
+    assert!(!integer(8, false).can_cast(&integer(16, true)));
+}
+
+#[test]
+fn numbers_promote_to_the_narrowest_common_type() {
+    assert_eq!(integer(8, true).promote(&integer(16, false)), Some(integer(16, false)));
+    assert_eq!(integer(32, true).promote(&integer(16, false)), Some(integer(64, false)));
+    assert_eq!(NumberType::Bit { size: Some(9) }.promote(&integer(8, false)), Some(integer(16, false)));
+    assert_eq!(integer(16, true).promote(&integer(24, false)), Some(integer(24, false)));
+    assert_eq!(integer(64, true).promote(&integer(8, false)), Some(decimal(21, 0)));
+    assert_eq!(integer(32, false).promote(&decimal(10, 2)), Some(decimal(12, 2)));
+    assert_eq!(
+        decimal(10, 2).promote(&NumberType::Float { size: None, double: false }),
+        Some(NumberType::Float { size: None, double: true })
+    );
+    assert_eq!(NumberType::Boolean.promote(&integer(8, true)), None);
+}
+
+#[test]
+fn binary_arithmetic_returns_widened_type() {
+    let mut stdlib = StdLib::new();
+    let decimal_type = number(NumberType::Decimal { size: None, unsigned: false, zerofill: false });
+    stdlib.reg_binary_operation(StdLibBinaryOperation::new(
+        BinaryOperator::Plus,
+        decimal_type.clone(),
+        decimal_type.clone(),
+        decimal_type.clone(),
+    ));
+    stdlib.reg_binary_operation(StdLibBinaryOperation::new(
+        BinaryOperator::Times,
+        decimal_type.clone(),
+        decimal_type.clone(),
+        decimal_type.clone(),
+    ));
+
+    let plus = found(stdlib.resolve_binary_operation(
+        BinaryOperator::Plus,
+        &number(integer(32, false)),
+        &number(decimal(10, 2)),
+    ));
+    assert_eq!(plus.output, number(decimal(13, 2)));
+
+    let times = found(stdlib.resolve_binary_operation(
+        BinaryOperator::Times,
+        &number(decimal(5, 2)),
+        &number(decimal(4, 1)),
+    ));
+    assert_eq!(times.output, number(decimal(10, 3)));
+}
+
+#[test]
+fn overloads_prefer_exact_then_narrowest_match() {
+    let mut stdlib = StdLib::new();
+    for &(size, zerofill) in &[(32, true), (16, false), (8, true), (8, false)] {
+        let argument = number(NumberType::Integer { size, unsigned: false, zerofill });
+        stdlib.reg_function(
+            StdLibFunction::new("abs".to_string())
+                .gets(vec![argument.clone()])
+                .returns(argument)
+        );
+    }
+
+    let exact = number(integer(8, false));
+    assert_eq!(found(stdlib.resolve_function("abs", &[&exact])).output, exact);
+
+    let narrow = number(integer(12, false));
+    assert_eq!(found(stdlib.resolve_function("abs", &[&narrow])).output, number(integer(16, false)));
+
+    let wide = number(integer(64, false));
+    assert_eq!(stdlib.resolve_function("abs", &[&wide]), OverloadResolution::NotFound);
+}
+
+#[test]
+fn interchangeable_overloads_do_not_depend_on_registration_order() {
+    for &zerofill_first in &[true, false] {
+        let mut stdlib = StdLib::new();
+        for &zerofill in &[zerofill_first, !zerofill_first] {
+            let argument = number(NumberType::Integer { size: 16, unsigned: false, zerofill });
+            stdlib.reg_function(
+                StdLibFunction::new("abs".to_string())
+                    .gets(vec![argument.clone()])
+                    .returns(argument)
+            );
+        }
+
+        let narrow = number(integer(8, false));
+        assert_eq!(found(stdlib.resolve_function("abs", &[&narrow])).output, number(integer(16, false)));
+
+        let narrow_zerofill = number(NumberType::Integer { size: 8, unsigned: false, zerofill: true });
+        assert_eq!(
+            found(stdlib.resolve_function("abs", &[&narrow_zerofill])).output,
+            number(NumberType::Integer { size: 16, unsigned: false, zerofill: true })
+        );
+    }
+}
+
+#[test]
+fn equally_matching_overloads_are_ambiguous() {
+    let mut stdlib = StdLib::new();
+    for &zerofill in &[true, false] {
+        let argument = number(NumberType::Integer { size: 16, unsigned: false, zerofill });
+        stdlib.reg_function(
+            StdLibFunction::new("pair".to_string())
+                .gets(vec![argument.clone(), argument.clone()])
+                .returns(argument)
+        );
+    }
+
+    let zerofill = number(NumberType::Integer { size: 8, unsigned: false, zerofill: true });
+    let plain = number(integer(8, false));
+    match stdlib.resolve_function("pair", &[&zerofill, &plain]) {
+        OverloadResolution::Ambiguous(candidates) => assert_eq!(candidates.len(), 2),
+        other => panic!("Expected ambiguity, got {:?}", other),
+    }
+}
+
+#[test]
+fn incomparable_overloads_are_ambiguous() {
+    let mut stdlib = StdLib::new();
+    let int = number(integer(32, false));
+    let dec = number(decimal(20, 4));
+    stdlib.reg_function(
+        StdLibFunction::new("mix".to_string())
+            .gets(vec![int.clone(), dec.clone()])
+    );
+    stdlib.reg_function(
+        StdLibFunction::new("mix".to_string())
+            .gets(vec![dec.clone(), int.clone()])
+    );
+
+    match stdlib.resolve_function("mix", &[&int, &int]) {
+        OverloadResolution::Ambiguous(candidates) => assert_eq!(candidates.len(), 2),
+        other => panic!("Expected ambiguity, got {:?}", other),
+    }
+}
+
+#[test]
+fn selection_of_another_type_is_wrapped_into_insert_select() {
+    let mut source = HashMapSource::new();
+    source.simple_insert(Path::new("a", "::"), "a.n", "
+        table Wide {
+            #[primary_key]
+            amount: integer,
+        }
+
+        table Narrow {
+            #[primary_key]
+            amount: small integer,
+        }
+
+        fn wide(): {amount: integer}[] {
+            return select w.amount from Wide w;
+        }
+
+        pub fn amounts(): {amount: integer}[] {
+            let result := wide();
+            result := select n.amount from Narrow n;
+            return result;
+        }
+    ");
+    let project = ProjectContext::new(SyncRef::new(StdLib::new()));
+    project.request_resolving_module(Path::new("a", "::"));
+    let project = match project.resolve(&source) {
+        Ok(project) => project,
+        Err(_) => panic!("Resolved some errors"),
+    };
+    let sql = DatabaseProject::new(&project)
+        .generate_string()
+        .expect("Cannot generate output for database");
+    let expected = "
+    INSERT INTO @result ([amount])
+        SELECT t.[amount] FROM (
+            SELECT
+                [n].amount AS amount
+            FROM
+                [a::Narrow] AS [n]
+        ) as t;
+";
+    assert!(sql.contains(expected), "{}", sql);
+}
+
+#[test]
+fn stdlib_declarations_are_loaded_from_source() {
+    let mut stdlib = StdLib::new();
+    stdlib.reg_source(Arc::new(Text::new("test.nstd", "
+        extern operator - (a: integer): integer
+        extern operator * (a: integer, b: integer): integer
+        #[aggregate]
+        #[sql_name(COUNT_BIG)]
+        extern fn count(a: integer): big integer
+    "))).expect("Declarations should be loaded");
+
+    let int = number(integer(32, false));
+    let minus = found(stdlib.resolve_prefix_unary_operation(PrefixUnaryOperator::Minus, &int));
+    assert_eq!(minus.output, int);
+    let times = found(stdlib.resolve_binary_operation(BinaryOperator::Times, &int, &int));
+    assert_eq!(times.output, int);
+    let count = found(stdlib.resolve_function("count", &[&int]));
+    assert!(count.is_aggregate);
+    assert_eq!(count.get_sql_name(), "COUNT_BIG");
+    assert_eq!(count.output, number(integer(64, false)));
+
+    let errors = stdlib.reg_source(Arc::new(Text::new("test.nstd", "
+        fn implemented(a: integer): integer { return a; }
+    "))).expect_err("Implemented functions should be rejected");
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn stdlib_source_errors_point_to_their_text() {
+    let mut stdlib = StdLib::new();
+    let sources = [
+        ("extern fn broken(a: integer): integer \"", true),
+        ("extern fn broken(a: integer) integer", false),
+    ];
+    for &(source, is_scanner_error) in sources.iter() {
+        let errors = stdlib.reg_source(Arc::new(Text::new("broken.nstd", source)))
+            .expect_err("Broken source should be rejected");
+        assert!(!errors.is_empty());
+        for error in errors {
+            match error.kind {
+                SemanticErrorKind::ScannerError { .. } => assert!(is_scanner_error),
+                _ => assert!(!is_scanner_error),
+            }
+            assert_eq!(error.text.as_ref().map(|error_text| error_text.name.as_str()), Some("broken.nstd"));
+        }
+    }
+}
+
+#[test]
+fn nullable_types_cast_to_their_base_type_only_explicitly() {
+    let int = number(integer(32, false));
+    let nullable_int = int.clone().nullable();
+    assert!(nullable_int.is_nullable());
+    assert_eq!(nullable_int.clone().nullable(), nullable_int);
+    assert!(int.can_cast(&nullable_int));
+    assert!(nullable_int.can_cast(&nullable_int));
+    assert!(!nullable_int.can_cast(&int));
+    assert!(nullable_int.can_explicit_cast(&int));
+    assert_ne!(nullable_int, int);
+    assert_eq!(nullable_int.as_primitive(), int.as_primitive());
+}